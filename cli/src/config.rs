@@ -1,28 +1,64 @@
-use pcloud::credentials::Credentials;
+use crate::credentials::CredentialsConfig;
+use pcloud::http::limiter::RateLimit;
+use pcloud::http::transfer::{Throughput, TransferLimits};
 use pcloud::http::{HttpClient, HttpClientBuilder, HttpClientBuilderError};
 use pcloud::region::Region;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::time::Duration;
 
-#[derive(Deserialize)]
-pub struct CredentialsConfig {
-    username: String,
-    password: String,
+/// The name of the profile made of the top level fields of the configuration
+pub const DEFAULT_PROFILE: &str = "default";
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Format(serde_json::Error),
+    ProfileNotFound(String),
+    Credentials(crate::credentials::Error),
+    Builder(HttpClientBuilderError),
 }
 
-impl CredentialsConfig {
-    fn build(self) -> Credentials {
-        Credentials::UserPassword {
-            username: self.username,
-            password: self.password,
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "unable to access configuration: {err}"),
+            Self::Format(err) => write!(f, "invalid configuration: {err}"),
+            Self::ProfileNotFound(name) => write!(f, "profile {name:?} not found"),
+            Self::Credentials(err) => write!(f, "unable to load credentials: {err}"),
+            Self::Builder(err) => write!(f, "unable to build client: {err:?}"),
         }
     }
 }
 
-#[derive(Deserialize)]
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Format(value)
+    }
+}
+
+impl From<crate::credentials::Error> for Error {
+    fn from(value: crate::credentials::Error) -> Self {
+        Self::Credentials(value)
+    }
+}
+
+impl From<HttpClientBuilderError> for Error {
+    fn from(value: HttpClientBuilderError) -> Self {
+        Self::Builder(value)
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
 pub struct RegionConfig {
-    name: String,
+    pub name: String,
 }
 
 impl RegionConfig {
@@ -31,31 +67,293 @@ impl RegionConfig {
     }
 }
 
-#[derive(Default, Deserialize)]
-pub struct Config {
-    credentials: Option<CredentialsConfig>,
-    region: Option<RegionConfig>,
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct LimitsConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requests_per_second: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub burst: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_transfers: Option<usize>,
+    /// In bytes per second
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bandwidth: Option<u64>,
+}
+
+impl LimitsConfig {
+    fn apply(self, mut limit: RateLimit) -> RateLimit {
+        limit.requests_per_second = self.requests_per_second.or(limit.requests_per_second);
+        limit.burst = self.burst.or(limit.burst);
+        limit.max_transfers = self.max_transfers.or(limit.max_transfers);
+        limit.bandwidth = self.bandwidth.or(limit.bandwidth);
+        limit
+    }
+}
+
+/// The durations are in seconds
+#[derive(Clone, Deserialize, Serialize)]
+pub struct TransfersConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    idle_timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     timeout: Option<u64>,
+    /// In bytes per second
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_throughput: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    throughput_window: Option<u64>,
 }
 
-impl Config {
-    pub fn from_path(path: &Path) -> Result<Self, String> {
-        let reader = std::fs::File::open(path).map_err(|err| err.to_string())?;
-        let result = serde_json::from_reader(reader).map_err(|err| err.to_string())?;
-        Ok(result)
+impl TransfersConfig {
+    fn apply(self, mut limits: TransferLimits) -> TransferLimits {
+        limits.idle_timeout = self
+            .idle_timeout
+            .map(Duration::from_secs)
+            .or(limits.idle_timeout);
+        limits.total_timeout = self
+            .timeout
+            .map(Duration::from_secs)
+            .or(limits.total_timeout);
+        if let Some(value) = self.min_throughput {
+            let window = limits
+                .min_throughput
+                .map(|item| item.window)
+                .unwrap_or(Throughput::DEFAULT_WINDOW);
+            limits.min_throughput = Some(Throughput::new(value).window(window));
+        }
+        if let Some(window) = self.throughput_window.map(Duration::from_secs) {
+            limits.min_throughput = limits.min_throughput.map(|item| item.window(window));
+        }
+        limits
+    }
+}
+
+/// An account and the way to access it
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct Profile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<CredentialsConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<RegionConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connect_timeout: Option<u64>,
+    /// The folder used by the commands when none is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root_folder: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limits: Option<LimitsConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transfers: Option<TransfersConfig>,
+}
+
+impl Profile {
+    fn is_empty(&self) -> bool {
+        self.credentials.is_none()
+            && self.region.is_none()
+            && self.timeout.is_none()
+            && self.connect_timeout.is_none()
+            && self.root_folder.is_none()
+            && self.limits.is_none()
+            && self.transfers.is_none()
     }
 
-    pub fn build(self) -> Result<HttpClient, HttpClientBuilderError> {
+    pub fn root_folder(&self) -> u64 {
+        self.root_folder.unwrap_or(0)
+    }
+
+    /// The same profile, without the secrets written in the configuration, to display it
+    pub fn redacted(&self) -> Self {
+        Self {
+            credentials: self.credentials.as_ref().map(|item| item.redacted()),
+            ..self.clone()
+        }
+    }
+
+    pub fn build(self) -> Result<HttpClient, Error> {
         let mut builder = HttpClientBuilder::from_env();
         if let Some(timeout) = self.timeout.map(Duration::from_secs) {
             builder.timeout = Some(timeout);
         }
-        if let Some(creds) = self.credentials.map(|c| c.build()) {
-            builder.credentials = Some(creds);
+        if let Some(timeout) = self.connect_timeout.map(Duration::from_secs) {
+            builder.connect_timeout = Some(timeout);
+        }
+        if let Some(creds) = self.credentials {
+            builder.credentials = Some(creds.build()?);
         }
         if let Some(region) = self.region.map(|c| c.build()) {
             builder.region = Some(region);
         }
-        builder.build()
+        if let Some(limits) = self.limits {
+            builder.rate_limit = limits.apply(builder.rate_limit);
+        }
+        if let Some(transfers) = self.transfers {
+            builder.transfer_limits = transfers.apply(builder.transfer_limits);
+        }
+        Ok(builder.build()?)
+    }
+}
+
+/// The configuration file, with the top level fields making the `default` profile,
+/// as they did before the named profiles.
+#[derive(Default, Deserialize, Serialize)]
+pub struct Config {
+    /// The profile used when none is selected
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_profile: Option<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    profiles: BTreeMap<String, Profile>,
+    #[serde(flatten)]
+    default: Profile,
+}
+
+impl Config {
+    /// Loads the configuration, an empty one when the file doesn't exist
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        match std::fs::File::open(path) {
+            Ok(reader) => Ok(serde_json::from_reader(reader)?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Writes the configuration, readable only by its owner
+    pub fn write(&self, path: &Path) -> Result<(), Error> {
+        if let Some(parent) = path.parent().filter(|item| !item.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let writer = options.open(path)?;
+        // the mode only applies when the file is created
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            writer.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        }
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    /// The name of the profile to use, when none is given on the command line
+    pub fn selected<'a>(&'a self, name: Option<&'a str>) -> &'a str {
+        name.or(self.default_profile.as_deref())
+            .unwrap_or(DEFAULT_PROFILE)
+    }
+
+    pub fn profile(&self, name: Option<&str>) -> Result<&Profile, Error> {
+        match self.selected(name) {
+            DEFAULT_PROFILE => Ok(&self.default),
+            other => self
+                .profiles
+                .get(other)
+                .ok_or_else(|| Error::ProfileNotFound(other.to_string())),
+        }
+    }
+
+    /// The names of the profiles, the `default` one being listed when it's configured
+    pub fn names(&self) -> Vec<&str> {
+        let default = Some(DEFAULT_PROFILE).filter(|_| !self.default.is_empty());
+        default
+            .into_iter()
+            .chain(self.profiles.keys().map(String::as_str))
+            .collect()
+    }
+
+    /// Adds or replaces a profile
+    pub fn insert(&mut self, name: &str, profile: Profile) {
+        match name {
+            DEFAULT_PROFILE => self.default = profile,
+            other => {
+                self.profiles.insert(other.to_string(), profile);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Result<Profile, Error> {
+        let removed = match name {
+            DEFAULT_PROFILE => Some(std::mem::take(&mut self.default)).filter(|p| !p.is_empty()),
+            other => self.profiles.remove(other),
+        };
+        if self.default_profile.as_deref() == Some(name) {
+            self.default_profile = None;
+        }
+        removed.ok_or_else(|| Error::ProfileNotFound(name.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, Profile, DEFAULT_PROFILE};
+
+    #[test]
+    fn should_read_single_account_config() {
+        let config: Config = serde_json::from_str(
+            r#"{
+                "credentials": { "username": "someone", "password": "secret" },
+                "region": { "name": "eu" },
+                "limits": { "requests_per_second": 5 }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.names(), vec![DEFAULT_PROFILE]);
+        let profile = config.profile(None).unwrap();
+        assert!(profile.credentials.is_some());
+        assert_eq!(profile.region.as_ref().unwrap().name, "eu");
+        assert!(config.profile(Some("team")).is_err());
+    }
+
+    #[test]
+    fn should_select_and_edit_profiles() {
+        let mut config: Config = serde_json::from_str(
+            r#"{
+                "default_profile": "team",
+                "profiles": {
+                    "personal": { "region": { "name": "eu" } },
+                    "team": { "region": { "name": "us" }, "root_folder": 42, "timeout": 10 }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(config.names(), vec!["personal", "team"]);
+        assert_eq!(config.profile(None).unwrap().root_folder(), 42);
+        assert_eq!(config.profile(Some("personal")).unwrap().root_folder(), 0);
+
+        config.insert(
+            DEFAULT_PROFILE,
+            Profile {
+                timeout: Some(5),
+                ..Default::default()
+            },
+        );
+        config.remove("team").unwrap();
+        assert!(config.remove("team").is_err());
+        assert_eq!(config.names(), vec![DEFAULT_PROFILE, "personal"]);
+        assert_eq!(config.profile(None).unwrap().timeout, Some(5));
+
+        let written = serde_json::to_value(&config).unwrap();
+        assert_eq!(
+            written,
+            serde_json::json!({
+                "profiles": { "personal": { "region": { "name": "eu" } } },
+                "timeout": 5
+            })
+        );
+    }
+    #[cfg(unix)]
+    #[test]
+    fn should_restrict_existing_config() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_dir::TempDir::new().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, "{}").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        Config::default().write(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
     }
 }
@@ -0,0 +1,306 @@
+//! The sources of the credentials, from the configuration file, a helper command,
+//! a secret file or an encrypted store.
+
+mod store;
+
+use clap::Parser;
+use pcloud::credentials::{Credentials, Secret};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub use store::Store;
+
+#[derive(Debug)]
+pub enum Error {
+    Helper(String, std::io::Error),
+    HelperFailed(String, std::process::ExitStatus),
+    File(PathBuf, std::io::Error),
+    Store(PathBuf, store::Error),
+    Invalid(String),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Helper(helper, err) => write!(f, "unable to run helper {helper:?}: {err}"),
+            Self::HelperFailed(helper, status) => write!(f, "helper {helper:?} failed: {status}"),
+            Self::File(path, err) => write!(f, "unable to read {path:?}: {err}"),
+            Self::Store(path, err) => write!(f, "unable to open store {path:?}: {err}"),
+            Self::Invalid(origin) => write!(f, "no credentials found in {origin}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Where to find the credentials, the source being picked by the name of its field
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum CredentialsConfig {
+    UserPassword {
+        username: String,
+        password: String,
+    },
+    AccessToken {
+        access_token: String,
+    },
+    /// A command printing the credentials, like the git credential helpers
+    Helper {
+        helper: String,
+    },
+    /// A file containing the credentials, like the docker or kubernetes secrets
+    File {
+        file: PathBuf,
+    },
+    /// A store encrypted with a passphrase, see [`Store`]
+    Store {
+        store: PathBuf,
+    },
+}
+
+impl CredentialsConfig {
+    /// The same source, without the secrets written in the configuration, to display it
+    pub fn redacted(&self) -> Self {
+        match self {
+            Self::UserPassword { username, .. } => Self::UserPassword {
+                username: username.clone(),
+                password: Secret::default().to_string(),
+            },
+            Self::AccessToken { .. } => Self::AccessToken {
+                access_token: Secret::default().to_string(),
+            },
+            other => other.clone(),
+        }
+    }
+
+    pub fn build(self) -> Result<Credentials, Error> {
+        match self {
+            Self::UserPassword { username, password } => {
+                Ok(Credentials::user_password(username, password))
+            }
+            Self::AccessToken { access_token } => Ok(Credentials::access_token(access_token)),
+            Self::Helper { helper } => run_helper(&helper),
+            Self::File { file } => {
+                let content = std::fs::read_to_string(&file)
+                    .map(Secret::from)
+                    .map_err(|err| Error::File(file.clone(), err))?;
+                parse(content.expose()).ok_or_else(|| Error::Invalid(format!("{file:?}")))
+            }
+            Self::Store { store } => {
+                let passphrase = store::passphrase(&store)
+                    .map_err(|err| Error::Store(store.clone(), err.into()))?;
+                Store::read(&store)
+                    .and_then(|content| content.open(&passphrase))
+                    .map_err(|err| Error::Store(store, err))
+            }
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn shell(helper: &str) -> std::process::Command {
+    let mut cmd = std::process::Command::new("sh");
+    cmd.arg("-c").arg(helper);
+    cmd
+}
+
+#[cfg(windows)]
+fn shell(helper: &str) -> std::process::Command {
+    let mut cmd = std::process::Command::new("cmd");
+    cmd.arg("/C").arg(helper);
+    cmd
+}
+
+/// Runs the helper with the shell and reads the credentials it prints
+fn run_helper(helper: &str) -> Result<Credentials, Error> {
+    let output = shell(helper)
+        .stdin(std::process::Stdio::null())
+        .stderr(std::process::Stdio::inherit())
+        .output()
+        .map_err(|err| Error::Helper(helper.to_string(), err))?;
+    if !output.status.success() {
+        return Err(Error::HelperFailed(helper.to_string(), output.status));
+    }
+    let content = String::from_utf8(output.stdout)
+        .map(Secret::from)
+        .map_err(|_| Error::Invalid(format!("the output of {helper:?}")))?;
+    parse(content.expose()).ok_or_else(|| Error::Invalid(format!("the output of {helper:?}")))
+}
+
+/// Reads the credentials printed by a helper or written in a file
+///
+/// The content is either an access token alone, or `key=value` lines
+/// with the `access_token` or the `username` and the `password`.
+pub fn parse(content: &str) -> Option<Credentials> {
+    let content = content.trim();
+    if content.is_empty() {
+        return None;
+    }
+    let mut access_token = None;
+    let mut username = None;
+    let mut password = None;
+    let mut found = false;
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim_end_matches('\r');
+        match key.trim() {
+            "access_token" => access_token = Some(value),
+            "username" => username = Some(value),
+            "password" => password = Some(value),
+            _ => continue,
+        }
+        found = true;
+    }
+    if !found {
+        // a token alone, on a single line
+        return match content.lines().count() {
+            1 => Some(Credentials::access_token(content)),
+            _ => None,
+        };
+    }
+    match (access_token, username, password) {
+        (Some(token), _, _) => Some(Credentials::access_token(token)),
+        (None, Some(username), Some(password)) => {
+            Some(Credentials::user_password(username, password))
+        }
+        _ => None,
+    }
+}
+
+/// Formats the credentials the way [`parse`] reads them
+fn format(creds: &Credentials) -> Secret {
+    Secret::new(match creds {
+        Credentials::AccessToken(token) => format!("access_token={}\n", token.expose()),
+        Credentials::UserPassword { username, password } => {
+            format!("username={username}\npassword={}\n", password.expose())
+        }
+    })
+}
+
+#[derive(Parser)]
+pub struct Command {
+    #[clap(subcommand)]
+    subcommand: SubCommand,
+}
+
+impl Command {
+    pub fn execute(&self) {
+        self.subcommand.execute()
+    }
+}
+
+#[derive(Parser)]
+enum SubCommand {
+    /// Saves the credentials in a store encrypted with a passphrase.
+    ///
+    /// The passphrase is read from PCLOUD_STORE_PASSPHRASE or prompted.
+    Store(StoreCommand),
+}
+
+impl SubCommand {
+    fn execute(&self) {
+        match self {
+            Self::Store(cmd) => cmd.execute(),
+        }
+    }
+}
+
+#[derive(Parser)]
+struct StoreCommand {
+    /// The username, an access token is prompted when missing
+    #[clap(short, long)]
+    username: Option<String>,
+    /// The path of the store, to reference in the configuration file
+    path: PathBuf,
+}
+
+impl StoreCommand {
+    fn prompt(&self) -> std::io::Result<Credentials> {
+        Ok(match self.username {
+            Some(ref username) => {
+                let password = rpassword::prompt_password("Password: ")?;
+                Credentials::user_password(username.clone(), password)
+            }
+            None => Credentials::access_token(rpassword::prompt_password("Access token: ")?),
+        })
+    }
+
+    fn write(&self, path: &Path) -> Result<(), store::Error> {
+        let creds = self.prompt()?;
+        let passphrase = store::new_passphrase(path)?;
+        Store::seal(&creds, &passphrase, store::Kdf::default())?.write(path)
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub fn execute(&self) {
+        match self.write(&self.path) {
+            Ok(_) => {
+                tracing::info!("credentials stored in {:?}", self.path);
+                std::process::exit(exitcode::OK);
+            }
+            Err(err) => {
+                tracing::error!("unable to store credentials: {}", err);
+                std::process::exit(exitcode::IOERR);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, CredentialsConfig};
+    use pcloud::credentials::Credentials;
+
+    fn is_user_password(creds: &Credentials, name: &str, secret: &str) -> bool {
+        matches!(creds, Credentials::UserPassword { username, password } if username == name && password.expose() == secret)
+    }
+
+    #[test]
+    fn should_parse_token_or_key_values() {
+        assert!(matches!(
+            parse("some-token\n"),
+            Some(Credentials::AccessToken(token)) if token.expose() == "some-token"
+        ));
+        assert!(is_user_password(
+            &parse("protocol=https\nusername=someone\r\npassword=p=ss\n").unwrap(),
+            "someone",
+            "p=ss"
+        ));
+        assert!(matches!(
+            parse("access_token=abc\nusername=someone"),
+            Some(Credentials::AccessToken(token)) if token.expose() == "abc"
+        ));
+        assert!(parse("username=someone").is_none());
+        assert!(parse("some\ntoken").is_none());
+        assert!(parse("  \n").is_none());
+    }
+
+    #[test]
+    fn should_read_credentials_from_helper_and_file() {
+        let config: CredentialsConfig =
+            serde_json::from_str(r#"{"helper": "echo username=someone; echo password=secret"}"#)
+                .unwrap();
+        assert!(is_user_password(
+            &config.build().unwrap(),
+            "someone",
+            "secret"
+        ));
+
+        let config: CredentialsConfig = serde_json::from_str(r#"{"helper": "exit 1"}"#).unwrap();
+        assert!(matches!(
+            config.build(),
+            Err(super::Error::HelperFailed(..))
+        ));
+
+        let dir = temp_dir::TempDir::new().unwrap();
+        let path = dir.path().join("token");
+        std::fs::write(&path, "some-token\n").unwrap();
+        let config = CredentialsConfig::File { file: path };
+        assert!(matches!(
+            config.build().unwrap(),
+            Credentials::AccessToken(token) if token.expose() == "some-token"
+        ));
+    }
+}
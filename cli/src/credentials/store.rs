@@ -0,0 +1,232 @@
+//! A local file holding the credentials, encrypted with ChaCha20-Poly1305
+//! and a key derived from a passphrase with scrypt.
+
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use pcloud::credentials::{Credentials, Secret};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const VERSION: u8 = 1;
+const SALT_SIZE: usize = 16;
+const PASSPHRASE_VAR: &str = "PCLOUD_STORE_PASSPHRASE";
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Format(serde_json::Error),
+    Version(u8),
+    Kdf,
+    /// The passphrase is wrong or the store has been altered
+    Decrypt,
+    Invalid,
+    Mismatch,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{err}"),
+            Self::Format(err) => write!(f, "invalid store: {err}"),
+            Self::Version(version) => write!(f, "unsupported store version {version}"),
+            Self::Kdf => write!(f, "invalid key derivation parameters"),
+            Self::Decrypt => write!(f, "wrong passphrase or corrupted store"),
+            Self::Invalid => write!(f, "invalid credentials in store"),
+            Self::Mismatch => write!(f, "the passphrases don't match"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Format(value)
+    }
+}
+
+/// The scrypt parameters, kept in the store to be able to strengthen them later
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct Kdf {
+    log_n: u8,
+    r: u32,
+    p: u32,
+}
+
+impl Default for Kdf {
+    fn default() -> Self {
+        Self {
+            log_n: scrypt::Params::RECOMMENDED_LOG_N,
+            r: scrypt::Params::RECOMMENDED_R,
+            p: scrypt::Params::RECOMMENDED_P,
+        }
+    }
+}
+
+impl Kdf {
+    fn derive(&self, passphrase: &Secret, salt: &[u8]) -> Result<Key, Error> {
+        let params = scrypt::Params::new(self.log_n, self.r, self.p, 32).map_err(|_| Error::Kdf)?;
+        let mut key = Key::default();
+        scrypt::scrypt(passphrase.expose().as_bytes(), salt, &params, &mut key)
+            .map_err(|_| Error::Kdf)?;
+        Ok(key)
+    }
+}
+
+/// The content of the store, with the binary fields encoded in hexadecimal
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Store {
+    version: u8,
+    kdf: Kdf,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+impl Store {
+    pub fn seal(creds: &Credentials, passphrase: &Secret, kdf: Kdf) -> Result<Self, Error> {
+        let mut salt = [0u8; SALT_SIZE];
+        OsRng.fill_bytes(&mut salt);
+        let cipher = ChaCha20Poly1305::new(&kdf.derive(passphrase, &salt)?);
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let plaintext = super::format(creds);
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.expose().as_bytes())
+            .map_err(|_| Error::Invalid)?;
+        Ok(Self {
+            version: VERSION,
+            kdf,
+            salt: hex::encode(salt),
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    pub fn open(&self, passphrase: &Secret) -> Result<Credentials, Error> {
+        if self.version != VERSION {
+            return Err(Error::Version(self.version));
+        }
+        let salt = hex::decode(&self.salt).map_err(|_| Error::Invalid)?;
+        let nonce = hex::decode(&self.nonce).map_err(|_| Error::Invalid)?;
+        if nonce.len() != 12 {
+            return Err(Error::Invalid);
+        }
+        let ciphertext = hex::decode(&self.ciphertext).map_err(|_| Error::Invalid)?;
+        let cipher = ChaCha20Poly1305::new(&self.kdf.derive(passphrase, &salt)?);
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| Error::Decrypt)?;
+        let plaintext = String::from_utf8(plaintext)
+            .map(Secret::from)
+            .map_err(|_| Error::Invalid)?;
+        super::parse(plaintext.expose()).ok_or(Error::Invalid)
+    }
+
+    pub fn read(path: &Path) -> Result<Self, Error> {
+        let reader = std::fs::File::open(path)?;
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    /// Writes the store, readable only by its owner
+    pub fn write(&self, path: &Path) -> Result<(), Error> {
+        let mut options = std::fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let writer = options.open(path)?;
+        // the mode only applies when the file is created
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            writer.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+        }
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+}
+
+/// The passphrase to open the store, from the environment or prompted
+pub fn passphrase(path: &Path) -> std::io::Result<Secret> {
+    match std::env::var(PASSPHRASE_VAR) {
+        Ok(value) => Ok(Secret::from(value)),
+        Err(_) => rpassword::prompt_password(format!("Passphrase for {}: ", path.display()))
+            .map(Secret::from),
+    }
+}
+
+/// The passphrase of a new store, prompted twice when not in the environment
+pub fn new_passphrase(path: &Path) -> Result<Secret, Error> {
+    if let Ok(value) = std::env::var(PASSPHRASE_VAR) {
+        return Ok(Secret::from(value));
+    }
+    let first = passphrase(path)?;
+    let second = rpassword::prompt_password("Confirm passphrase: ").map(Secret::from)?;
+    if first == second {
+        Ok(first)
+    } else {
+        Err(Error::Mismatch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, Kdf, Store};
+    use pcloud::credentials::{Credentials, Secret};
+
+    // fast parameters, the recommended ones are too slow for the tests
+    const KDF: Kdf = Kdf {
+        log_n: 4,
+        r: 8,
+        p: 1,
+    };
+
+    #[test]
+    fn should_seal_and_open_store() {
+        let dir = temp_dir::TempDir::new().unwrap();
+        let path = dir.path().join("store.json");
+        let passphrase = Secret::from("passphrase");
+        let creds = Credentials::user_password("someone", "super-secret");
+        Store::seal(&creds, &passphrase, KDF)
+            .unwrap()
+            .write(&path)
+            .unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("someone"));
+        assert!(!content.contains("super-secret"));
+
+        let store = Store::read(&path).unwrap();
+        let opened = store.open(&passphrase).unwrap();
+        assert!(matches!(
+            opened,
+            Credentials::UserPassword { username, password } if username == "someone" && password.expose() == "super-secret"
+        ));
+        assert!(matches!(
+            store.open(&Secret::from("wrong")),
+            Err(Error::Decrypt)
+        ));
+    }
+    #[cfg(unix)]
+    #[test]
+    fn should_restrict_existing_store() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_dir::TempDir::new().unwrap();
+        let path = dir.path().join("store.json");
+        std::fs::write(&path, "{}").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        let creds = Credentials::access_token("token");
+        Store::seal(&creds, &Secret::from("passphrase"), KDF)
+            .unwrap()
+            .write(&path)
+            .unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
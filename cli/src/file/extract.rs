@@ -0,0 +1,66 @@
+use clap::Parser;
+use pcloud::archive::extract_archive::{ExtractArchiveCommand, OverwritePolicy};
+use pcloud::http::HttpClient;
+use pcloud::prelude::HttpCommand;
+use std::time::Duration;
+
+#[derive(Parser)]
+pub struct Command {
+    /// Folder to extract the archive in.
+    #[clap(long, default_value = "0")]
+    folder_id: u64,
+    /// What to do when a file already exists: rename, overwrite or skip.
+    #[clap(long, default_value = "rename")]
+    overwrite: OverwritePolicy,
+    /// Prompt for the password of the archive.
+    #[clap(long)]
+    password: bool,
+    /// Interval between two progress checks, in milliseconds.
+    #[clap(long, default_value_t = 1000)]
+    interval: u64,
+    /// Remote archive file id
+    file_id: u64,
+}
+
+impl Command {
+    #[tracing::instrument(skip_all)]
+    pub async fn execute(&self, pcloud: HttpClient) {
+        let mut cmd = ExtractArchiveCommand::new(self.file_id.into(), self.folder_id.into())
+            .overwrite(self.overwrite);
+        if self.password {
+            match rpassword::prompt_password("Archive password: ") {
+                Ok(password) => cmd = cmd.password(password),
+                Err(err) => {
+                    tracing::error!("unable to read password: {:?}", err);
+                    std::process::exit(exitcode::IOERR);
+                }
+            }
+        }
+        let job = match cmd.execute(&pcloud).await {
+            Ok(job) => job,
+            Err(err) => {
+                tracing::error!("unable to extract archive: {:?}", err);
+                std::process::exit(exitcode::DATAERR);
+            }
+        };
+        for line in job.output.iter() {
+            println!("{line}");
+        }
+        let mut progress = job.progress(&pcloud, Duration::from_millis(self.interval));
+        while let Some(item) = progress.next().await {
+            match item {
+                Ok(item) => {
+                    for line in item.output {
+                        println!("{line}");
+                    }
+                }
+                Err(err) => {
+                    tracing::error!("unable to follow archive extraction: {:?}", err);
+                    std::process::exit(exitcode::DATAERR);
+                }
+            }
+        }
+        tracing::info!("archive extracted");
+        std::process::exit(exitcode::OK);
+    }
+}
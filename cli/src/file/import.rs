@@ -0,0 +1,58 @@
+use clap::Parser;
+use pcloud::file::import::FileImportAsyncCommand;
+use pcloud::http::HttpClient;
+use pcloud::prelude::HttpCommand;
+use std::time::Duration;
+
+#[derive(Parser)]
+pub struct Command {
+    /// Folder to import the files in.
+    #[clap(long, default_value = "0")]
+    folder: u64,
+    /// Interval between two progress checks, in milliseconds.
+    #[clap(long, default_value_t = 1000)]
+    interval: u64,
+    /// Urls of the files to import
+    #[clap(required = true)]
+    urls: Vec<String>,
+}
+
+impl Command {
+    #[tracing::instrument(skip_all)]
+    pub async fn execute(&self, pcloud: HttpClient) {
+        let cmd = FileImportAsyncCommand::new(self.urls.clone(), self.folder.into());
+        let job = match cmd.execute(&pcloud).await {
+            Ok(job) => job,
+            Err(err) => {
+                tracing::error!("unable to import files: {:?}", err);
+                std::process::exit(exitcode::DATAERR);
+            }
+        };
+        let mut progress = job.progress(&pcloud, Duration::from_millis(self.interval));
+        while let Some(item) = progress.next().await {
+            match item {
+                Ok(item) if item.finished => {
+                    for file in item.files {
+                        println!("{}\t{}", file.file_id, file.base.name);
+                    }
+                }
+                Ok(item) => {
+                    tracing::info!(
+                        "imported {}/{} bytes{}",
+                        item.uploaded,
+                        item.total,
+                        item.current_file
+                            .map(|name| format!(" ({name})"))
+                            .unwrap_or_default()
+                    );
+                }
+                Err(err) => {
+                    tracing::error!("unable to follow files import: {:?}", err);
+                    std::process::exit(exitcode::DATAERR);
+                }
+            }
+        }
+        tracing::info!("files imported");
+        std::process::exit(exitcode::OK);
+    }
+}
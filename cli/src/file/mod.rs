@@ -1,7 +1,10 @@
 mod delete;
 mod download;
+mod extract;
+mod import;
 mod moving;
 mod rename;
+mod stream;
 mod upload;
 
 use clap::Parser;
@@ -14,8 +17,8 @@ pub struct Command {
 }
 
 impl Command {
-    pub async fn execute(&self, pcloud: HttpClient) {
-        self.subcommand.execute(pcloud).await
+    pub async fn execute(&self, pcloud: HttpClient, root_folder: u64) {
+        self.subcommand.execute(pcloud, root_folder).await
     }
 }
 
@@ -23,19 +26,25 @@ impl Command {
 enum SubCommand {
     Delete(delete::Command),
     Download(download::Command),
+    Extract(extract::Command),
+    Import(import::Command),
     Move(moving::Command),
     Rename(rename::Command),
+    Stream(stream::Command),
     Upload(upload::Command),
 }
 
 impl SubCommand {
-    pub async fn execute(&self, pcloud: HttpClient) {
+    pub async fn execute(&self, pcloud: HttpClient, root_folder: u64) {
         match self {
             Self::Delete(cmd) => cmd.execute(pcloud).await,
             Self::Download(cmd) => cmd.execute(pcloud).await,
+            Self::Extract(cmd) => cmd.execute(pcloud).await,
+            Self::Import(cmd) => cmd.execute(pcloud).await,
             Self::Move(cmd) => cmd.execute(pcloud).await,
             Self::Rename(cmd) => cmd.execute(pcloud).await,
-            Self::Upload(cmd) => cmd.execute(pcloud).await,
+            Self::Stream(cmd) => cmd.execute(pcloud).await,
+            Self::Upload(cmd) => cmd.execute(pcloud, root_folder).await,
         }
     }
 }
@@ -0,0 +1,68 @@
+use clap::Parser;
+use pcloud::http::HttpClient;
+use pcloud::prelude::HttpCommand;
+use pcloud::streaming::get_hls_link::GetHlsLinkCommand;
+use pcloud::streaming::get_video_links::GetVideoLinksCommand;
+
+#[derive(Parser)]
+pub struct Command {
+    /// Also prints the HTTP Live Streaming link.
+    #[clap(long)]
+    hls: bool,
+    /// Remote video file id
+    file_id: u64,
+}
+
+impl Command {
+    #[tracing::instrument(skip_all)]
+    pub async fn execute(&self, pcloud: HttpClient) {
+        let variants = match GetVideoLinksCommand::new(self.file_id.into())
+            .execute(&pcloud)
+            .await
+        {
+            Ok(variants) => variants,
+            Err(err) => {
+                tracing::error!("unable to get video links: {:?}", err);
+                std::process::exit(exitcode::DATAERR);
+            }
+        };
+        for variant in variants {
+            let resolution = match (variant.width, variant.height) {
+                (Some(width), Some(height)) => format!("{width}x{height}"),
+                _ => "-".to_string(),
+            };
+            let video = format!(
+                "{}@{}k",
+                variant.video_codec.as_deref().unwrap_or("-"),
+                variant.video_bit_rate.unwrap_or_default()
+            );
+            let audio = format!(
+                "{}@{}k",
+                variant.audio_codec.as_deref().unwrap_or("-"),
+                variant.audio_bit_rate.unwrap_or_default()
+            );
+            let kind = if variant.transcoded {
+                "transcoded"
+            } else {
+                "original"
+            };
+            println!(
+                "{kind}\t{resolution}\t{video}\t{audio}\t{}",
+                variant.link.url().unwrap_or_default()
+            );
+        }
+        if self.hls {
+            match GetHlsLinkCommand::new(self.file_id.into())
+                .execute(&pcloud)
+                .await
+            {
+                Ok(link) => println!("hls\t{}", link.url().unwrap_or_default()),
+                Err(err) => {
+                    tracing::error!("unable to get hls link: {:?}", err);
+                    std::process::exit(exitcode::DATAERR);
+                }
+            }
+        }
+        std::process::exit(exitcode::OK);
+    }
+}
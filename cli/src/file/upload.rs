@@ -10,9 +10,9 @@ pub struct Command {
     /// Name of the created remote file.
     #[clap(long)]
     filename: Option<String>,
-    /// Folder to store the file in.
-    #[clap(long, default_value = "0")]
-    folder_id: u64,
+    /// Folder to store the file in. Default to the root folder of the profile.
+    #[clap(long)]
+    folder_id: Option<u64>,
     /// Keep partial file if upload fails.
     #[clap(long)]
     allow_partial_upload: bool,
@@ -33,10 +33,10 @@ impl Command {
     }
 
     #[tracing::instrument(skip_all)]
-    pub async fn execute(&self, pcloud: HttpClient) {
+    pub async fn execute(&self, pcloud: HttpClient, root_folder: u64) {
         let file = File::open(&self.path).expect("unable to open file");
         let filename = self.filename();
-        let cmd = MultipartFileUploadCommand::new(self.folder_id);
+        let cmd = MultipartFileUploadCommand::new(self.folder_id.unwrap_or(root_folder));
         let cmd = match cmd.add_sync_file_entry(filename, file) {
             Ok(cmd) => cmd,
             Err(err) => {
@@ -1,5 +1,4 @@
 use async_recursion::async_recursion;
-use pcloud::entry::{Entry, Folder};
 use pcloud::error::Error;
 use pcloud::http::HttpClient;
 use pcloud::prelude::HttpCommand;
@@ -7,37 +6,6 @@ use sha2::{Digest, Sha256};
 use std::path::Path;
 use std::str::FromStr;
 
-/// returns the content of a folder with retry mechanism
-#[async_recursion]
-pub(crate) async fn try_get_folder(
-    pcloud: &HttpClient,
-    folder_id: u64,
-    retries: usize,
-) -> Result<Folder, Error> {
-    tracing::info!("loading folder, {} retries left", retries);
-    match pcloud::folder::list::FolderListCommand::new(folder_id.into())
-        .execute(pcloud)
-        .await
-    {
-        Err(err) if retries > 0 => {
-            tracing::warn!("unable to load folder: {:?}", err);
-            try_get_folder(pcloud, folder_id, retries - 1).await
-        }
-        other => other,
-    }
-}
-
-/// returns the content of a folder with retry mechanism
-pub(crate) async fn try_get_folder_content(
-    pcloud: &HttpClient,
-    folder_id: u64,
-    retries: usize,
-) -> Result<Vec<Entry>, Error> {
-    try_get_folder(pcloud, folder_id, retries)
-        .await
-        .map(|folder| folder.contents.unwrap_or_default())
-}
-
 #[async_recursion]
 pub async fn try_get_file_checksum(
     pcloud: &HttpClient,
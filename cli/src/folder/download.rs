@@ -1,9 +1,10 @@
-use super::common::{get_checksum, try_get_file_checksum, try_get_folder_content, CompareMethod};
+use super::common::{get_checksum, try_get_file_checksum, CompareMethod};
 use async_recursion::async_recursion;
 use clap::Parser;
 use pcloud::entry::{Entry, File};
 use pcloud::error::Error as PCloudError;
 use pcloud::file::download::FileDownloadCommand;
+use pcloud::folder::walker::Walker;
 use pcloud::http::HttpClient;
 use pcloud::prelude::HttpCommand;
 use std::fs;
@@ -103,62 +104,6 @@ impl FileDownloader {
     }
 }
 
-struct FolderVisitor {
-    remote_path: PathBuf,
-    remote_folder_id: u64,
-    local_path: PathBuf,
-}
-
-impl FolderVisitor {
-    async fn execute(
-        self,
-        client: &HttpClient,
-        excludes: &[glob::Pattern],
-        retries: usize,
-        queue: async_channel::Sender<FileDownloader>,
-    ) -> Result<Vec<FolderVisitor>, Error> {
-        let mut results = Vec::new();
-        for entry in try_get_folder_content(client, self.remote_folder_id, retries).await? {
-            let new_remote_path = self.remote_path.join(entry.base().name.as_str());
-            if excludes.iter().any(|p| p.matches_path(&new_remote_path)) {
-                tracing::info!(
-                    "{:?} is matching an exclusion pattern, ignoring",
-                    new_remote_path
-                );
-                continue;
-            }
-            match entry {
-                Entry::File(file) => {
-                    if let Err(err) = queue
-                        .send(FileDownloader {
-                            remote_path: new_remote_path,
-                            local_path: self.local_path.join(file.base.name.as_str()),
-                            remote_file: file,
-                        })
-                        .await
-                    {
-                        tracing::error!("unable to append file to download queue: {err}");
-                    }
-                }
-                Entry::Folder(folder) => {
-                    let new_remote_path = self.remote_path.join(folder.base.name.as_str());
-                    let new_local_path = self.local_path.join(folder.base.name.as_str());
-                    if let Err(err) = fs::create_dir_all(&new_local_path) {
-                        tracing::warn!("unable to create folder {:?}: {:?}", new_local_path, err);
-                    } else {
-                        results.push(FolderVisitor {
-                            remote_path: new_remote_path,
-                            remote_folder_id: folder.folder_id,
-                            local_path: new_local_path,
-                        });
-                    }
-                }
-            }
-        }
-        Ok(results)
-    }
-}
-
 #[derive(Debug)]
 #[allow(dead_code)]
 enum Error {
@@ -201,6 +146,12 @@ pub struct Command {
 }
 
 impl Command {
+    /// Local path of a remote path relative to the synchronized folder
+    fn local_path(&self, remote_path: &Path) -> PathBuf {
+        self.path
+            .join(remote_path.strip_prefix("/").unwrap_or(remote_path))
+    }
+
     pub async fn execute(&self, pcloud: HttpClient, folder_id: u64) {
         let (tx, rx) = async_channel::bounded::<FileDownloader>(self.download_queue_capacity);
 
@@ -235,21 +186,33 @@ impl Command {
                 .instrument(info_span!("downloader", index)),
             ));
         }
-        let mut visitor_queue = vec![FolderVisitor {
-            remote_path: PathBuf::from("/"),
-            remote_folder_id: folder_id,
-            local_path: self.path.clone(),
-        }];
-        while let Some(next) = visitor_queue.pop() {
-            let path = next.remote_path.clone();
-            match next
-                .execute(&pcloud, &self.exclude, self.retries, tx.clone())
-                .instrument(info_span!("visitor", path = path.to_str()))
-                .await
-            {
-                Ok(found) => visitor_queue.extend(found),
+        let mut walker = Walker::new(&pcloud, folder_id.into()).retries(self.retries);
+        for pattern in self.exclude.iter() {
+            walker = walker.exclude(pattern.clone());
+        }
+        while let Some(next) = walker.next().instrument(info_span!("visitor")).await {
+            match next {
+                Ok((remote_path, Entry::File(file))) => {
+                    let local_path = self.local_path(&remote_path);
+                    if let Err(err) = tx
+                        .send(FileDownloader {
+                            remote_path,
+                            remote_file: file,
+                            local_path,
+                        })
+                        .await
+                    {
+                        tracing::error!("unable to append file to download queue: {err}");
+                    }
+                }
+                Ok((remote_path, Entry::Folder(_))) => {
+                    let local_path = self.local_path(&remote_path);
+                    if let Err(err) = fs::create_dir_all(&local_path) {
+                        tracing::warn!("unable to create folder {:?}: {:?}", local_path, err);
+                    }
+                }
                 Err(err) => tracing::error!("unable to visit folder: {:?}", err),
-            };
+            }
         }
         tx.close();
         tracing::info!("visitor is done, waiting for the downloader to finish");
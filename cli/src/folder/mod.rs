@@ -5,21 +5,23 @@ mod delete;
 mod download;
 mod list;
 mod upload;
+mod zip;
 
 use clap::Parser;
 use pcloud::http::HttpClient;
 
 #[derive(Parser)]
 pub struct Command {
-    #[clap(default_value = "0")]
-    folder_id: u64,
+    /// Default to the root folder of the profile
+    folder_id: Option<u64>,
     #[clap(subcommand)]
     subcommand: SubCommand,
 }
 
 impl Command {
-    pub async fn execute(&self, pcloud: HttpClient) {
-        self.subcommand.execute(pcloud, self.folder_id).await
+    pub async fn execute(&self, pcloud: HttpClient, root_folder: u64) {
+        let folder_id = self.folder_id.unwrap_or(root_folder);
+        self.subcommand.execute(pcloud, folder_id).await
     }
 }
 
@@ -30,6 +32,7 @@ enum SubCommand {
     Download(download::Command),
     List(list::Command),
     Upload(upload::Command),
+    Zip(zip::Command),
 }
 
 impl SubCommand {
@@ -40,6 +43,7 @@ impl SubCommand {
             Self::Download(cmd) => cmd.execute(pcloud, folder_id).await,
             Self::List(cmd) => cmd.execute(pcloud, folder_id).await,
             Self::Upload(cmd) => cmd.execute(pcloud, folder_id).await,
+            Self::Zip(cmd) => cmd.execute(pcloud, folder_id).await,
         }
     }
 }
@@ -1,11 +1,12 @@
-use super::common::{get_checksum, try_get_file_checksum, try_get_folder, CompareMethod};
+use super::common::{get_checksum, try_get_file_checksum, CompareMethod};
 use async_recursion::async_recursion;
 use clap::Parser;
 use pcloud::entry::{Entry, Folder};
 use pcloud::error::Error as PCloudError;
+use pcloud::folder::walker::Walker;
 use pcloud::http::HttpClient;
 use pcloud::prelude::HttpCommand;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::io::Error as IoError;
 use std::path::{Path, PathBuf};
 use tracing::{info_span, Instrument};
@@ -106,28 +107,86 @@ impl FileUploader {
     }
 }
 
-enum RemoteFolder {
-    Existing(u64),
-    Missing(u64, String),
+fn local_path(root: &Path, remote_path: &Path) -> PathBuf {
+    root.join(remote_path.strip_prefix("/").unwrap_or(remote_path))
 }
 
-impl RemoteFolder {
-    async fn get(&self, client: &HttpClient, retries: usize) -> Result<Folder, Error> {
-        let folder_id = match self {
-            Self::Existing(folder_id) => *folder_id,
-            Self::Missing(parent_folder_id, filename) => {
-                let created_folder =
-                    try_create_folder(client, *parent_folder_id, filename, retries).await?;
-                created_folder.folder_id
+/// The remote entries already existing in the synchronized folder and having a local counterpart
+#[derive(Default)]
+struct RemoteContent {
+    /// The files already sent to the uploading queue
+    files: HashSet<PathBuf>,
+    folders: HashMap<PathBuf, u64>,
+}
+
+impl RemoteContent {
+    /// Walks lazily through the remote folder, skipping the folders missing locally,
+    /// and sends the files existing on both sides to the uploading queue.
+    async fn load(
+        client: &HttpClient,
+        folder_id: u64,
+        root: &Path,
+        excludes: &[glob::Pattern],
+        retries: usize,
+        queue: &async_channel::Sender<FileUploader>,
+    ) -> Self {
+        let prune_root = root.to_path_buf();
+        let mut walker = Walker::new(client, folder_id.into())
+            .retries(retries)
+            .prune(move |path, _| !local_path(&prune_root, path).is_dir());
+        for pattern in excludes {
+            walker = walker.exclude(pattern.clone());
+        }
+        let mut result = Self::default();
+        while let Some(next) = walker.next().await {
+            match next {
+                Ok((path, Entry::File(file))) => {
+                    let local_path = local_path(root, &path);
+                    if !local_path.is_file() {
+                        continue;
+                    }
+                    let remote_folder_id = path
+                        .parent()
+                        .and_then(|parent| result.folders.get(parent))
+                        .copied()
+                        .unwrap_or(folder_id);
+                    let Some(filename) = path
+                        .file_name()
+                        .and_then(|name| name.to_str())
+                        .map(String::from)
+                    else {
+                        continue;
+                    };
+                    if let Err(err) = queue
+                        .send(FileUploader {
+                            remote_path: path.clone(),
+                            remote_existing_id: Some(file.file_id),
+                            remote_folder_id,
+                            filename,
+                            local_path,
+                        })
+                        .await
+                    {
+                        tracing::warn!("unable to add {:?} to the uploading queue: {err}", path);
+                        continue;
+                    }
+                    result.files.insert(path);
+                }
+                Ok((path, Entry::Folder(folder))) => {
+                    if local_path(root, &path).is_dir() {
+                        result.folders.insert(path, folder.folder_id);
+                    }
+                }
+                Err(err) => tracing::error!("unable to visit remote folder: {:?}", err),
             }
-        };
-        Ok(try_get_folder(client, folder_id, retries).await?)
+        }
+        result
     }
 }
 
 struct FolderVisitor {
     remote_path: PathBuf,
-    remote_folder: RemoteFolder,
+    remote_folder_id: u64,
     local_path: PathBuf,
 }
 
@@ -135,21 +194,11 @@ impl FolderVisitor {
     async fn execute(
         self,
         client: &HttpClient,
+        remote_content: &RemoteContent,
         excludes: &[glob::Pattern],
         retries: usize,
         queue: async_channel::Sender<FileUploader>,
     ) -> Result<Vec<FolderVisitor>, Error> {
-        let folder = self.remote_folder.get(client, retries).await?;
-        let remote_content: HashMap<&str, &Entry> = folder
-            .contents
-            .as_ref()
-            .map(|items| {
-                items
-                    .iter()
-                    .map(|entry| (entry.base().name.as_str(), entry))
-                    .collect()
-            })
-            .unwrap_or_default();
         let mut result = Vec::new();
         for (fname, local_path) in read_local_folder(&self.local_path) {
             let new_remote_path = self.remote_path.join(&fname);
@@ -161,25 +210,25 @@ impl FolderVisitor {
                 continue;
             }
             if local_path.is_dir() {
-                let remote_folder = remote_content
-                    .get(fname.as_str())
-                    .and_then(|entry| entry.as_folder())
-                    .map(|folder| RemoteFolder::Existing(folder.folder_id))
-                    .unwrap_or_else(|| RemoteFolder::Missing(folder.folder_id, fname));
+                let remote_folder_id = match remote_content.folders.get(&new_remote_path) {
+                    Some(folder_id) => *folder_id,
+                    None => {
+                        try_create_folder(client, self.remote_folder_id, &fname, retries)
+                            .await?
+                            .folder_id
+                    }
+                };
                 result.push(FolderVisitor {
                     remote_path: new_remote_path,
-                    remote_folder,
+                    remote_folder_id,
                     local_path,
                 });
-            } else if local_path.is_file() {
+            } else if local_path.is_file() && !remote_content.files.contains(&new_remote_path) {
                 if let Err(err) = queue
                     .send(FileUploader {
+                        remote_existing_id: None,
                         remote_path: new_remote_path.clone(),
-                        remote_existing_id: remote_content
-                            .get(fname.as_str())
-                            .and_then(|entry| entry.as_file())
-                            .map(|file| file.file_id),
-                        remote_folder_id: folder.folder_id,
+                        remote_folder_id: self.remote_folder_id,
                         filename: fname,
                         local_path,
                     })
@@ -312,15 +361,32 @@ impl Command {
             ));
         }
 
+        let remote_content = RemoteContent::load(
+            &client,
+            folder_id,
+            &self.path,
+            &self.exclude,
+            self.retries,
+            &tx,
+        )
+        .instrument(info_span!("remote"))
+        .await;
+
         let mut visitor_queue = vec![FolderVisitor {
             remote_path: PathBuf::from("/"),
-            remote_folder: RemoteFolder::Existing(folder_id),
+            remote_folder_id: folder_id,
             local_path: self.path.clone(),
         }];
         while let Some(next) = visitor_queue.pop() {
             let path = next.remote_path.clone();
             match next
-                .execute(&client, &self.exclude, self.retries, tx.clone())
+                .execute(
+                    &client,
+                    &remote_content,
+                    &self.exclude,
+                    self.retries,
+                    tx.clone(),
+                )
                 .instrument(info_span!("visitor", path = path.to_str()))
                 .await
             {
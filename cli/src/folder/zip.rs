@@ -0,0 +1,98 @@
+use clap::Parser;
+use pcloud::archive::get_zip::GetZipCommand;
+use pcloud::archive::save_zip::SaveZipCommand;
+use pcloud::archive::Tree;
+use pcloud::http::HttpClient;
+use pcloud::prelude::HttpCommand;
+use std::fs::OpenOptions;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+
+#[derive(Parser)]
+pub struct Command {
+    /// Overrides an existing file
+    #[clap(long)]
+    overrides: bool,
+    /// Folder ids to exclude from the archive
+    #[clap(long)]
+    exclude_folder: Vec<u64>,
+    /// File ids to exclude from the archive
+    #[clap(long)]
+    exclude_file: Vec<u64>,
+    /// Creates the archive in the given remote folder instead of downloading it
+    #[clap(long, requires = "name")]
+    save_to: Option<u64>,
+    /// Name of the archive created remotely
+    #[clap(long)]
+    name: Option<String>,
+    /// Output path for the archive
+    #[clap(required_unless_present = "save_to")]
+    path: Option<PathBuf>,
+}
+
+impl Command {
+    fn tree(&self, folder_id: u64) -> Tree {
+        let tree = Tree::default().with_folder(folder_id);
+        let tree = self
+            .exclude_folder
+            .iter()
+            .fold(tree, |tree, id| tree.exclude_folder(*id));
+        self.exclude_file
+            .iter()
+            .fold(tree, |tree, id| tree.exclude_file(*id))
+    }
+
+    async fn save(&self, pcloud: HttpClient, folder_id: u64, to_folder_id: u64, name: String) {
+        match SaveZipCommand::new(self.tree(folder_id), to_folder_id.into(), name)
+            .execute(&pcloud)
+            .await
+        {
+            Ok(res) => {
+                tracing::info!("archive created {}", res.file_id);
+                std::process::exit(exitcode::OK);
+            }
+            Err(err) => {
+                tracing::error!("unable to create archive: {:?}", err);
+                std::process::exit(exitcode::CANTCREAT);
+            }
+        }
+    }
+
+    async fn download(&self, pcloud: HttpClient, folder_id: u64, path: &Path) {
+        let file = OpenOptions::new()
+            .create_new(!self.overrides)
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(path)
+            .expect("unable to create file");
+        match GetZipCommand::new(self.tree(folder_id), BufWriter::new(file))
+            .execute(&pcloud)
+            .await
+        {
+            Ok(res) => {
+                tracing::info!("archive downloaded: {}", res);
+                std::process::exit(exitcode::OK);
+            }
+            Err(err) => {
+                tracing::error!("unable to download archive: {:?}", err);
+                std::process::exit(exitcode::DATAERR);
+            }
+        }
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn execute(&self, pcloud: HttpClient, folder_id: u64) {
+        match (self.save_to, self.name.as_ref(), self.path.as_ref()) {
+            (Some(to_folder_id), Some(name), _) => {
+                self.save(pcloud, folder_id, to_folder_id, name.clone())
+                    .await
+            }
+            (_, _, Some(path)) => self.download(pcloud, folder_id, path).await,
+            _ => {
+                tracing::error!("an output path or a remote folder should be provided");
+                std::process::exit(exitcode::USAGE);
+            }
+        }
+    }
+}
@@ -1,6 +1,9 @@
 mod config;
+mod credentials;
 mod file;
 mod folder;
+mod profile;
+mod share;
 
 #[cfg(all(test, feature = "protected"))]
 mod tests;
@@ -15,6 +18,10 @@ struct Command {
     /// Path to load the configuration file. Default to ~/.config/pcloud.json. If not found, loading from environment.
     #[clap(short, long)]
     config: Option<PathBuf>,
+    /// Name of the profile to use from the configuration file. Default to PCLOUD_PROFILE,
+    /// then to the default profile of the configuration file.
+    #[clap(short, long)]
+    profile: Option<String>,
     // #[clap(flatten)]
     // verbose: Verbosity<InfoLevel>,
     #[clap(subcommand)]
@@ -31,6 +38,12 @@ impl Command {
             PathBuf::from(".pcloud.json")
         }
     }
+
+    fn profile(&self) -> Option<String> {
+        self.profile
+            .clone()
+            .or_else(|| std::env::var("PCLOUD_PROFILE").ok())
+    }
 }
 
 #[derive(Parser)]
@@ -41,13 +54,25 @@ enum SubCommand {
     /// File related sub command
     #[clap()]
     File(file::Command),
+    /// Share related sub command
+    #[clap()]
+    Share(share::Command),
+    /// Credentials related sub command
+    #[clap()]
+    Credentials(credentials::Command),
+    /// Profile related sub command
+    #[clap()]
+    Profile(profile::Command),
 }
 
 impl Command {
-    async fn execute(&self, pcloud: HttpClient) {
+    async fn execute(&self, pcloud: HttpClient, root_folder: u64) {
         match &self.subcmd {
-            SubCommand::Folder(sub) => sub.execute(pcloud).await,
-            SubCommand::File(sub) => sub.execute(pcloud).await,
+            SubCommand::Folder(sub) => sub.execute(pcloud, root_folder).await,
+            SubCommand::File(sub) => sub.execute(pcloud, root_folder).await,
+            SubCommand::Share(sub) => sub.execute(pcloud).await,
+            SubCommand::Credentials(sub) => sub.execute(),
+            SubCommand::Profile(sub) => sub.execute(&self.config(), self.profile().as_deref()),
         }
     }
 
@@ -64,7 +89,31 @@ impl Command {
 async fn main() {
     let cmd = Command::parse();
     cmd.set_log_level();
-    let cfg = config::Config::from_path(&cmd.config()).unwrap_or_default();
-    let pcloud = cfg.build().expect("couldn't build client");
-    cmd.execute(pcloud).await;
+    match cmd.subcmd {
+        // they don't need a client, nor the credentials they are about to write
+        SubCommand::Credentials(ref sub) => return sub.execute(),
+        SubCommand::Profile(ref sub) => {
+            return sub.execute(&cmd.config(), cmd.profile().as_deref())
+        }
+        _ => {}
+    }
+    let profile = config::Config::load(&cmd.config())
+        .and_then(|cfg| cfg.profile(cmd.profile().as_deref()).cloned());
+    let (pcloud, root_folder) = match profile {
+        Ok(profile) => {
+            let root_folder = profile.root_folder();
+            match profile.build() {
+                Ok(pcloud) => (pcloud, root_folder),
+                Err(err) => {
+                    tracing::error!("{}", err);
+                    std::process::exit(exitcode::CONFIG);
+                }
+            }
+        }
+        Err(err) => {
+            tracing::error!("{}", err);
+            std::process::exit(exitcode::CONFIG);
+        }
+    };
+    cmd.execute(pcloud, root_folder).await;
 }
@@ -0,0 +1,177 @@
+use crate::config::{Config, LimitsConfig, Profile, RegionConfig};
+use crate::credentials::CredentialsConfig;
+use clap::Parser;
+use std::path::{Path, PathBuf};
+
+#[derive(Parser)]
+pub struct Command {
+    #[clap(subcommand)]
+    subcommand: SubCommand,
+}
+
+impl Command {
+    pub fn execute(&self, path: &Path, selected: Option<&str>) {
+        let result = Config::load(path).and_then(|mut config| match &self.subcommand {
+            SubCommand::List => {
+                list(&config, selected);
+                Ok(())
+            }
+            SubCommand::Show(cmd) => cmd.execute(&config, selected),
+            SubCommand::Add(cmd) => cmd.execute(&mut config, path),
+            SubCommand::Remove(cmd) => cmd.execute(&mut config, path),
+        });
+        match result {
+            Ok(_) => std::process::exit(exitcode::OK),
+            Err(err) => {
+                tracing::error!("{}", err);
+                std::process::exit(exitcode::CONFIG);
+            }
+        }
+    }
+}
+
+#[derive(Parser)]
+enum SubCommand {
+    /// Lists the profiles, the selected one being marked with a star
+    List,
+    /// Shows a profile, without its secrets
+    Show(ShowCommand),
+    /// Adds a profile to the configuration file
+    Add(Box<AddCommand>),
+    /// Removes a profile from the configuration file
+    Remove(RemoveCommand),
+}
+
+fn list(config: &Config, selected: Option<&str>) {
+    let selected = config.selected(selected);
+    for name in config.names() {
+        let marker = if name == selected { "*" } else { " " };
+        println!("{marker} {name}");
+    }
+}
+
+#[derive(Parser)]
+struct ShowCommand {
+    /// Default to the selected profile
+    name: Option<String>,
+}
+
+impl ShowCommand {
+    fn execute(&self, config: &Config, selected: Option<&str>) -> Result<(), crate::config::Error> {
+        let name = self.name.as_deref().or(selected);
+        let profile = config.profile(name)?.redacted();
+        println!("{}", serde_json::to_string_pretty(&profile)?);
+        Ok(())
+    }
+}
+
+#[derive(Parser)]
+#[clap(group = clap::ArgGroup::new("source").multiple(false))]
+struct AddCommand {
+    name: String,
+    /// Replace the profile when it already exists
+    #[clap(long)]
+    force: bool,
+    /// Username of the account, the password is prompted and written in the configuration
+    #[clap(long, group = "source")]
+    username: Option<String>,
+    /// Command printing the credentials
+    #[clap(long, group = "source")]
+    helper: Option<String>,
+    /// File containing the credentials
+    #[clap(long, group = "source")]
+    file: Option<PathBuf>,
+    /// Store created with `pcloud-cli credentials store`
+    #[clap(long, group = "source")]
+    store: Option<PathBuf>,
+    /// Region of the account, eu or us
+    #[clap(long)]
+    region: Option<String>,
+    /// Timeout of the api calls, in seconds
+    #[clap(long)]
+    timeout: Option<u64>,
+    /// Folder used by the commands when none is given
+    #[clap(long)]
+    root_folder: Option<u64>,
+    #[clap(long)]
+    requests_per_second: Option<f64>,
+    #[clap(long)]
+    max_transfers: Option<usize>,
+    /// In bytes per second
+    #[clap(long)]
+    bandwidth: Option<u64>,
+}
+
+impl AddCommand {
+    fn credentials(&self) -> std::io::Result<Option<CredentialsConfig>> {
+        Ok(if let Some(ref username) = self.username {
+            Some(CredentialsConfig::UserPassword {
+                username: username.clone(),
+                password: rpassword::prompt_password("Password: ")?,
+            })
+        } else if let Some(ref helper) = self.helper {
+            Some(CredentialsConfig::Helper {
+                helper: helper.clone(),
+            })
+        } else if let Some(ref file) = self.file {
+            Some(CredentialsConfig::File { file: file.clone() })
+        } else {
+            self.store.as_ref().map(|store| CredentialsConfig::Store {
+                store: store.clone(),
+            })
+        })
+    }
+
+    fn limits(&self) -> Option<LimitsConfig> {
+        if self.requests_per_second.is_none()
+            && self.max_transfers.is_none()
+            && self.bandwidth.is_none()
+        {
+            return None;
+        }
+        Some(LimitsConfig {
+            requests_per_second: self.requests_per_second,
+            max_transfers: self.max_transfers,
+            bandwidth: self.bandwidth,
+            ..Default::default()
+        })
+    }
+
+    #[tracing::instrument(skip_all)]
+    fn execute(&self, config: &mut Config, path: &Path) -> Result<(), crate::config::Error> {
+        if !self.force && config.names().contains(&self.name.as_str()) {
+            tracing::error!(
+                "profile {:?} already exists, use --force to replace it",
+                self.name
+            );
+            std::process::exit(exitcode::DATAERR);
+        }
+        let profile = Profile {
+            credentials: self.credentials()?,
+            region: self.region.clone().map(|name| RegionConfig { name }),
+            timeout: self.timeout,
+            root_folder: self.root_folder,
+            limits: self.limits(),
+            ..Default::default()
+        };
+        config.insert(&self.name, profile);
+        config.write(path)?;
+        tracing::info!("profile {:?} added to {:?}", self.name, path);
+        Ok(())
+    }
+}
+
+#[derive(Parser)]
+struct RemoveCommand {
+    name: String,
+}
+
+impl RemoveCommand {
+    #[tracing::instrument(skip_all)]
+    fn execute(&self, config: &mut Config, path: &Path) -> Result<(), crate::config::Error> {
+        config.remove(&self.name)?;
+        config.write(path)?;
+        tracing::info!("profile {:?} removed from {:?}", self.name, path);
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use clap::Parser;
+use pcloud::http::HttpClient;
+use pcloud::prelude::HttpCommand;
+use pcloud::sharing::accept_share::AcceptShareCommand;
+use pcloud::sharing::decline_share::DeclineShareCommand;
+
+#[derive(Parser)]
+pub struct Command {
+    /// Decline the share request instead of accepting it
+    #[clap(long, conflicts_with_all = ["folder_id", "name"])]
+    decline: bool,
+    /// Folder to mount the share in, default to the root folder
+    #[clap(long)]
+    folder_id: Option<u64>,
+    /// Name of the mounted folder, default to the name of the share
+    #[clap(long)]
+    name: Option<String>,
+    /// Share request id
+    share_request_id: u64,
+}
+
+impl Command {
+    async fn accept(&self, pcloud: &HttpClient) -> Result<(), pcloud::error::Error> {
+        let mut cmd = AcceptShareCommand::new(self.share_request_id);
+        if let Some(folder_id) = self.folder_id {
+            cmd = cmd.folder_id(folder_id);
+        }
+        if let Some(ref name) = self.name {
+            cmd = cmd.name(name.clone());
+        }
+        cmd.execute(pcloud).await
+    }
+
+    async fn decline(&self, pcloud: &HttpClient) -> Result<(), pcloud::error::Error> {
+        DeclineShareCommand::new(self.share_request_id)
+            .execute(pcloud)
+            .await
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn execute(&self, pcloud: HttpClient) {
+        let result = if self.decline {
+            self.decline(&pcloud).await
+        } else {
+            self.accept(&pcloud).await
+        };
+        match result {
+            Ok(_) if self.decline => {
+                tracing::info!("share request declined");
+                std::process::exit(exitcode::OK);
+            }
+            Ok(_) => {
+                tracing::info!("share request accepted");
+                std::process::exit(exitcode::OK);
+            }
+            Err(err) => {
+                tracing::error!("unable to answer share request: {:?}", err);
+                std::process::exit(exitcode::DATAERR);
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use clap::Parser;
+use pcloud::http::HttpClient;
+use pcloud::prelude::HttpCommand;
+use pcloud::sharing::share_folder::ShareFolderCommand;
+use pcloud::sharing::Permissions;
+
+#[derive(Parser)]
+pub struct Command {
+    /// Allow the receiver to create files and folders
+    #[clap(long)]
+    create: bool,
+    /// Allow the receiver to modify files and folders
+    #[clap(long)]
+    modify: bool,
+    /// Allow the receiver to delete files and folders
+    #[clap(long)]
+    delete: bool,
+    /// Name of the share, default to the folder name
+    #[clap(long)]
+    name: Option<String>,
+    /// Message sent with the invitation
+    #[clap(long)]
+    message: Option<String>,
+    /// Remote folder id
+    folder_id: u64,
+    /// Email address of the receiver
+    mail: String,
+}
+
+impl Command {
+    fn permissions(&self) -> Permissions {
+        let mut res = Permissions::READ;
+        if self.create {
+            res |= Permissions::CREATE;
+        }
+        if self.modify {
+            res |= Permissions::MODIFY;
+        }
+        if self.delete {
+            res |= Permissions::DELETE;
+        }
+        res
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn execute(&self, pcloud: HttpClient) {
+        let mut cmd = ShareFolderCommand::new(self.folder_id.into(), self.mail.clone())
+            .permissions(self.permissions());
+        if let Some(ref name) = self.name {
+            cmd = cmd.name(name.clone());
+        }
+        if let Some(ref message) = self.message {
+            cmd = cmd.message(message.clone());
+        }
+        match cmd.execute(&pcloud).await {
+            Ok(res) => {
+                tracing::info!("share request created {}", res);
+                std::process::exit(exitcode::OK);
+            }
+            Err(err) => {
+                tracing::error!("unable to share folder: {:?}", err);
+                std::process::exit(exitcode::DATAERR);
+            }
+        }
+    }
+}
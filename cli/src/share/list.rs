@@ -0,0 +1,78 @@
+use clap::Parser;
+use pcloud::http::HttpClient;
+use pcloud::prelude::HttpCommand;
+use pcloud::sharing::list_shares::{ListSharesCommand, ShareList};
+
+#[derive(Parser)]
+pub struct Command {
+    /// Only list the pending share requests
+    #[clap(long, conflicts_with = "no_requests")]
+    no_shares: bool,
+    /// Only list the active shares
+    #[clap(long)]
+    no_requests: bool,
+}
+
+impl Command {
+    fn print(&self, result: ShareList) {
+        println!(
+            "{:<12} {:<8} {:<8} {:<6} {:<32} {:<32}",
+            "ID", "Kind", "Way", "Perms", "Name", "With"
+        );
+        let shares = result
+            .shares
+            .incoming
+            .iter()
+            .map(|item| ("in", item))
+            .chain(result.shares.outgoing.iter().map(|item| ("out", item)));
+        for (way, share) in shares {
+            let with = share.from_mail.as_ref().or(share.to_mail.as_ref());
+            println!(
+                "{:<12} {:<8} {:<8} {:<6} {:<32} {:<32}",
+                share.share_id,
+                "share",
+                way,
+                share.permissions.to_string(),
+                share.name,
+                with.map(String::as_str).unwrap_or_default(),
+            );
+        }
+        let requests = result
+            .requests
+            .incoming
+            .iter()
+            .map(|item| ("in", item))
+            .chain(result.requests.outgoing.iter().map(|item| ("out", item)));
+        for (way, request) in requests {
+            let with = request.from_mail.as_ref().or(request.to_mail.as_ref());
+            println!(
+                "{:<12} {:<8} {:<8} {:<6} {:<32} {:<32}",
+                request.share_request_id,
+                "request",
+                way,
+                request.permissions.to_string(),
+                request.name,
+                with.map(String::as_str).unwrap_or_default(),
+            );
+        }
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn execute(&self, pcloud: HttpClient) {
+        match ListSharesCommand::default()
+            .no_shares(self.no_shares)
+            .no_requests(self.no_requests)
+            .execute(&pcloud)
+            .await
+        {
+            Ok(res) => {
+                self.print(res);
+                std::process::exit(exitcode::OK);
+            }
+            Err(err) => {
+                tracing::error!("unable to list shares: {:?}", err);
+                std::process::exit(exitcode::DATAERR);
+            }
+        }
+    }
+}
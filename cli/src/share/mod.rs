@@ -0,0 +1,38 @@
+mod accept;
+mod folder;
+mod list;
+mod revoke;
+
+use clap::Parser;
+use pcloud::http::HttpClient;
+
+#[derive(Parser)]
+pub struct Command {
+    #[clap(subcommand)]
+    subcommand: SubCommand,
+}
+
+impl Command {
+    pub async fn execute(&self, pcloud: HttpClient) {
+        self.subcommand.execute(pcloud).await
+    }
+}
+
+#[derive(Parser)]
+enum SubCommand {
+    Accept(accept::Command),
+    Folder(folder::Command),
+    List(list::Command),
+    Revoke(revoke::Command),
+}
+
+impl SubCommand {
+    pub async fn execute(&self, pcloud: HttpClient) {
+        match self {
+            Self::Accept(cmd) => cmd.execute(pcloud).await,
+            Self::Folder(cmd) => cmd.execute(pcloud).await,
+            Self::List(cmd) => cmd.execute(pcloud).await,
+            Self::Revoke(cmd) => cmd.execute(pcloud).await,
+        }
+    }
+}
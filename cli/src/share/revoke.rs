@@ -0,0 +1,37 @@
+use clap::Parser;
+use pcloud::http::HttpClient;
+use pcloud::prelude::HttpCommand;
+use pcloud::sharing::cancel_share_request::CancelShareRequestCommand;
+use pcloud::sharing::remove_share::RemoveShareCommand;
+
+#[derive(Parser)]
+pub struct Command {
+    /// The given id is a pending share request that should be cancelled
+    #[clap(long)]
+    request: bool,
+    /// Share id, or share request id when `--request` is set
+    id: u64,
+}
+
+impl Command {
+    #[tracing::instrument(skip_all)]
+    pub async fn execute(&self, pcloud: HttpClient) {
+        let result = if self.request {
+            CancelShareRequestCommand::new(self.id)
+                .execute(&pcloud)
+                .await
+        } else {
+            RemoveShareCommand::new(self.id).execute(&pcloud).await
+        };
+        match result {
+            Ok(_) => {
+                tracing::info!("share revoked");
+                std::process::exit(exitcode::OK);
+            }
+            Err(err) => {
+                tracing::error!("unable to revoke share: {:?}", err);
+                std::process::exit(exitcode::DATAERR);
+            }
+        }
+    }
+}
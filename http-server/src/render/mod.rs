@@ -4,9 +4,11 @@ use pcloud::entry::{Entry, File, Folder};
 use crate::FolderCloudPath;
 
 const DATE_FORMAT: &str = "%Y-%b-%d %T";
+/// Size of the thumbnails displayed in the listing
+const THUMB_SIZE: &str = "64x64";
 
 pub(crate) enum EntrySize {
-    File(usize),
+    File(u64),
     Folder,
 }
 
@@ -36,6 +38,7 @@ impl<'a> std::fmt::Display for ContentType<'a> {
 
 pub(crate) struct EntryRow<'a> {
     href: String,
+    thumb: Option<String>,
     name: &'a str,
     modified: String,
     size: EntrySize,
@@ -46,23 +49,36 @@ impl<'a> std::fmt::Display for EntryRow<'a> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let Self {
             href,
+            thumb,
             name,
             modified,
             size,
             content_type,
         } = self;
 
+        f.write_str(r#"<tr><td class="n">"#)?;
+        if let Some(thumb) = thumb {
+            write!(
+                f,
+                r#"<a href="{href}"><img class="thumb" src="{thumb}" alt="" loading="lazy" /></a>"#
+            )?;
+        }
         write!(
             f,
-            r#"<tr><td class="n"><a href="{href}">{name}</a></td><td class="m">{modified}</td><td class="s">{size}</td><td class="t">{content_type}</td></tr>"#,
+            r#"<a href="{href}">{name}</a></td><td class="m">{modified}</td><td class="s">{size}</td><td class="t">{content_type}</td></tr>"#,
         )
     }
 }
 
 impl<'a> EntryRow<'a> {
     pub fn from_file(prefix: &'static str, path: &'a FolderCloudPath, entry: &'a File) -> Self {
+        let href = format!("{prefix}{}", path.with_file(entry.base.name.as_str()));
         Self {
-            href: format!("{prefix}{}", path.with_file(entry.base.name.as_str())),
+            thumb: entry
+                .base
+                .thumb
+                .then(|| format!("{href}?thumb={THUMB_SIZE}")),
+            href,
             name: entry.base.name.as_str(),
             modified: entry.base.modified.format(DATE_FORMAT).to_string(),
             size: EntrySize::File(entry.size.unwrap_or(0)),
@@ -73,6 +89,7 @@ impl<'a> EntryRow<'a> {
     pub fn from_folder(prefix: &'static str, path: &'a FolderCloudPath, entry: &'a Folder) -> Self {
         Self {
             href: format!("{prefix}{}", path.with_folder(entry.base.name.as_str())),
+            thumb: None,
             name: entry.base.name.as_str(),
             modified: entry.base.modified.format(DATE_FORMAT).to_string(),
             size: EntrySize::Folder,
@@ -123,7 +140,7 @@ impl<'a> std::fmt::Display for IndexPage<'a> {
         f.write_str("<head>")?;
         f.write_str(r#"<meta charset="utf-8" />"#)?;
         write!(f, "<title>Index of {}{}</title>", self.prefix, self.path)?;
-        f.write_str("<style>table { width: 100%; }</style>")?;
+        f.write_str("<style>table { width: 100%; } img.thumb { max-width: 64px; max-height: 64px; margin-right: 0.5em; vertical-align: middle; }</style>")?;
         f.write_str("</head>")?;
         f.write_str("<body>")?;
         write!(f, "<h2>Index of {}{}</h2>", self.prefix, self.path)?;
@@ -2,14 +2,19 @@ use crate::{RootPrefix, Storage};
 use axum::extract::Query;
 use axum::response::{Html, IntoResponse};
 use axum::{extract::Path, Extension};
+use pcloud::archive::get_zip_link::GetZipLinkCommand;
+use pcloud::archive::Tree;
 use pcloud::entry::File;
 use pcloud::file::FileIdentifier;
 use pcloud::folder::list::FolderListCommand;
 use pcloud::prelude::HttpCommand;
-use std::path::PathBuf;
+use pcloud::streaming::get_audio_link::GetAudioLinkCommand;
+use pcloud::streaming::get_file_link::GetFileLinkCommand;
+use pcloud::streaming::get_video_link::GetVideoLinkCommand;
+use pcloud::thumbnail::get_thumb_link::GetThumbLinkCommand;
+use pcloud::thumbnail::ThumbSize;
 use std::str::FromStr;
 use std::string::FromUtf8Error;
-use std::sync::Arc;
 
 const PREFIX: &str = "/by-path";
 
@@ -17,6 +22,9 @@ const PREFIX: &str = "/by-path";
 struct ErrorReponse {
     message: String,
     details: Option<String>,
+    /// The request to pcloud that failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<pcloud::error::Context>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -27,6 +35,10 @@ pub enum Error {
     UnableListFolder(#[source] pcloud::error::Error),
     #[error("unable to get file")]
     UnableGetFile(#[source] pcloud::error::Error),
+    #[error("unable to get folder archive")]
+    UnableGetArchive(#[source] pcloud::error::Error),
+    #[error("no host available to serve the file")]
+    NoHostAvailable,
 }
 
 impl Error {
@@ -39,6 +51,7 @@ impl Error {
             // Unauthorized
             Self::UnableGetFile(pcloud::error::Error::Protocol(1000, _))
             | Self::UnableListFolder(pcloud::error::Error::Protocol(1000, _))
+            | Self::UnableGetArchive(pcloud::error::Error::Protocol(1000, _))
             | Self::UnableGetFile(pcloud::error::Error::Protocol(2000, _))
             | Self::UnableListFolder(pcloud::error::Error::Protocol(2000, _)) => {
                 axum::http::StatusCode::UNAUTHORIZED
@@ -59,7 +72,18 @@ impl Error {
 
     fn details(&self) -> Option<String> {
         match self {
-            Self::UnableGetFile(inner) | Self::UnableListFolder(inner) => Some(inner.to_string()),
+            Self::UnableGetFile(inner)
+            | Self::UnableListFolder(inner)
+            | Self::UnableGetArchive(inner) => Some(inner.to_string()),
+            _ => None,
+        }
+    }
+
+    fn context(&self) -> Option<pcloud::error::Context> {
+        match self {
+            Self::UnableGetFile(inner)
+            | Self::UnableListFolder(inner)
+            | Self::UnableGetArchive(inner) => inner.context().cloned(),
             _ => None,
         }
     }
@@ -68,6 +92,7 @@ impl Error {
         ErrorReponse {
             message: self.to_string(),
             details: self.details(),
+            context: self.context(),
         }
     }
 }
@@ -81,23 +106,6 @@ impl axum::response::IntoResponse for Error {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct RootFolder(Arc<PathBuf>);
-
-impl RootFolder {
-    pub fn new(value: PathBuf) -> Self {
-        Self(Arc::new(value))
-    }
-
-    pub fn join(&self, child: &str) -> String {
-        self.0.join(child).to_str().unwrap().to_string()
-    }
-
-    pub fn as_str(&self) -> String {
-        self.0.to_str().unwrap().to_string()
-    }
-}
-
 pub(crate) enum Success {
     Directory(String),
     File(String),
@@ -114,6 +122,14 @@ impl IntoResponse for Success {
     }
 }
 
+fn file_link_command(identifier: FileIdentifier, params: &QueryParams) -> GetFileLinkCommand {
+    let cmd = GetFileLinkCommand::new(identifier).force_download(params.download);
+    match params.speed {
+        Some(speed) => cmd.max_speed(speed),
+        None => cmd,
+    }
+}
+
 fn is_video(file: &File) -> bool {
     match file.content_type {
         Some(ref inner) => inner.starts_with("video/"),
@@ -139,9 +155,25 @@ async fn handle(
         let remote_path = root_prefix.root_path().join_folder(local_path.clone());
         // FolderListCommand shouldn't get '/' at the end of path
         let folder_content = FolderListCommand::new(remote_path.into_inner().to_string().into())
+            .no_files(params.zip)
             .execute(engine.as_ref())
             .await
             .map_err(Error::UnableListFolder)?;
+        if params.zip {
+            let filename = if folder_content.base.name == "/" {
+                "pcloud.zip".to_string()
+            } else {
+                format!("{}.zip", folder_content.base.name)
+            };
+            let link =
+                GetZipLinkCommand::new(Tree::default().with_folder(folder_content.folder_id))
+                    .filename(filename)
+                    .execute(engine.as_ref())
+                    .await
+                    .map_err(Error::UnableGetArchive)?;
+            let link = link.url().ok_or(Error::NoHostAvailable)?;
+            return Ok(Success::File(link));
+        }
         Ok(Success::Directory(
             crate::render::IndexPage::from_folder_list(PREFIX, &local_path, &folder_content)
                 .to_string(),
@@ -151,6 +183,15 @@ async fn handle(
         let remote_path = root_prefix.root_path().join_file(local_path);
 
         let identifier: FileIdentifier = remote_path.to_string().into();
+        if let Some(size) = params.thumb {
+            let link = GetThumbLinkCommand::new(identifier, size)
+                .execute(engine.as_ref())
+                .await
+                .map_err(Error::UnableGetFile)?;
+            let link = link.url().ok_or(Error::NoHostAvailable)?;
+            return Ok(Success::File(link));
+        }
+
         let link = if params.stream {
             let file = pcloud::file::checksum::FileCheckSumCommand::new(identifier.clone())
                 .execute(engine.as_ref())
@@ -158,25 +199,28 @@ async fn handle(
                 .map_err(Error::UnableGetFile)?;
 
             if is_video(&file.metadata) {
-                pcloud::streaming::get_video_link::GetVideoLinkCommand::new(identifier)
+                GetVideoLinkCommand::new(identifier)
+                    .force_download(params.download)
                     .execute(engine.as_ref())
                     .await
             } else if is_audio(&file.metadata) {
-                pcloud::streaming::get_audio_link::GetAudioLinkCommand::new(identifier)
+                GetAudioLinkCommand::new(identifier)
+                    .force_download(params.download)
                     .execute(engine.as_ref())
                     .await
             } else {
-                pcloud::streaming::get_file_link::GetFileLinkCommand::new(identifier)
+                file_link_command(identifier, &params)
                     .execute(engine.as_ref())
                     .await
             }
         } else {
-            pcloud::streaming::get_file_link::GetFileLinkCommand::new(identifier)
+            file_link_command(identifier, &params)
                 .execute(engine.as_ref())
                 .await
         };
 
         let link = link.map_err(Error::UnableGetFile)?;
+        let link = link.url().ok_or(Error::NoHostAvailable)?;
 
         Ok(Success::File(link))
     }
@@ -185,14 +229,44 @@ async fn handle(
 pub(crate) async fn index_handler(
     Extension(engine): Extension<Storage>,
     Extension(root_prefix): Extension<RootPrefix>,
+    Query(params): Query<QueryParams>,
 ) -> Result<Success, Error> {
-    handle(engine, root_prefix, "/", QueryParams::default()).await
+    handle(engine, root_prefix, "/", params).await
+}
+
+/// Accepts `1`, `true`, `0`, `false` or an empty value for boolean query parameters
+fn deserialize_flag<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::Deserialize;
+
+    let value = String::deserialize(deserializer)?;
+    match value.as_str() {
+        "" | "1" | "true" => Ok(true),
+        "0" | "false" => Ok(false),
+        other => Err(serde::de::Error::custom(format!(
+            "invalid boolean value {other:?}"
+        ))),
+    }
 }
 
 #[derive(Debug, Default, serde::Deserialize)]
 pub struct QueryParams {
-    #[serde(default)]
+    #[serde(default, deserialize_with = "deserialize_flag")]
     stream: bool,
+    /// Forces the browser to download the file instead of displaying it
+    #[serde(default, deserialize_with = "deserialize_flag")]
+    download: bool,
+    /// Maximum download speed, in bytes per second
+    #[serde(default)]
+    speed: Option<u64>,
+    /// Redirects to a zip archive of the folder
+    #[serde(default, deserialize_with = "deserialize_flag")]
+    zip: bool,
+    /// Redirects to the thumbnail of the file with the given size, like `64x64`
+    #[serde(default)]
+    thumb: Option<ThumbSize>,
 }
 
 pub(crate) async fn any_handler(
@@ -0,0 +1,108 @@
+//! Parsing of synthetic recursive listings, with 10 files per folder
+//!
+//! Run with `cargo bench -p pcloud --bench listing`
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use pcloud::entry::{EntryBase, File, Folder};
+use pcloud::folder::listing::parse_listing;
+use std::fmt::Write;
+
+const FILES_PER_FOLDER: usize = 10;
+
+fn write_file(output: &mut String, id: usize) {
+    write!(
+        output,
+        r#"{{"name":"file-{id}.jpg","created":"Sat, 24 Jul 2021 07:38:41 +0000","modified":"Sat, 24 Jul 2021 07:38:41 +0000","thumb":true,"isfolder":false,"fileid":{id},"id":"f{id}","isshared":false,"ismine":true,"icon":"image","category":1,"size":1048576,"hash":10681802873941075000,"contenttype":"image/jpeg","width":1920,"height":1080,"parentfolderid":1}}"#
+    )
+    .unwrap();
+}
+
+/// Builds a `listfolder` response with the given number of entries
+fn build_listing(count: usize) -> String {
+    let mut output = String::with_capacity(count * 400);
+    output.push_str(r#"{"result":0,"metadata":{"name":"/","created":"Sat, 24 Jul 2021 07:38:41 +0000","modified":"Sat, 24 Jul 2021 07:38:41 +0000","thumb":false,"isfolder":true,"folderid":0,"id":"d0","isshared":false,"ismine":true,"icon":"folder","contents":["#);
+    let folders = count / (FILES_PER_FOLDER + 1);
+    for folder in 0..folders {
+        if folder > 0 {
+            output.push(',');
+        }
+        write!(
+            output,
+            r#"{{"name":"folder-{folder}","created":"Sat, 24 Jul 2021 07:38:41 +0000","modified":"Sat, 24 Jul 2021 07:38:41 +0000","thumb":false,"isfolder":true,"folderid":{folder},"id":"d{folder}","isshared":false,"ismine":true,"icon":"folder","parentfolderid":0,"contents":["#
+        )
+        .unwrap();
+        for file in 0..FILES_PER_FOLDER {
+            if file > 0 {
+                output.push(',');
+            }
+            write_file(&mut output, folder * FILES_PER_FOLDER + file);
+        }
+        output.push_str("]}");
+    }
+    output.push_str("]}}");
+    output
+}
+
+#[derive(serde::Deserialize)]
+struct Response {
+    metadata: Folder,
+}
+
+/// The previous representation, trying a file and then a folder for each entry
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+#[allow(dead_code, clippy::large_enum_variant)]
+enum UntaggedEntry {
+    File(File),
+    Folder(UntaggedFolder),
+}
+
+#[derive(serde::Deserialize)]
+#[allow(dead_code)]
+struct UntaggedFolder {
+    #[serde(flatten)]
+    base: EntryBase,
+    #[serde(rename = "folderid")]
+    folder_id: u64,
+    contents: Option<Vec<UntaggedEntry>>,
+}
+
+#[derive(serde::Deserialize)]
+struct UntaggedResponse {
+    metadata: UntaggedFolder,
+}
+
+fn listing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("listfolder");
+    group.sample_size(10);
+    for count in [10_000, 100_000] {
+        let data = build_listing(count);
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        group.bench_with_input(BenchmarkId::new("untagged", count), &data, |b, data| {
+            b.iter(|| {
+                let response: UntaggedResponse = serde_json::from_str(black_box(data)).unwrap();
+                black_box(response.metadata)
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("tree", count), &data, |b, data| {
+            b.iter(|| {
+                let response: Response = serde_json::from_str(black_box(data)).unwrap();
+                black_box(response.metadata)
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("incremental", count), &data, |b, data| {
+            b.iter(|| {
+                let mut total = 0;
+                parse_listing(black_box(data.as_bytes()), |_, entry| {
+                    total += entry.as_file().and_then(|file| file.size).unwrap_or(0);
+                })
+                .unwrap();
+                black_box(total)
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, listing);
+criterion_main!(benches);
@@ -0,0 +1,249 @@
+//! Resources needed to extract an archive in a folder
+
+use crate::credentials::Secret;
+use crate::file::FileIdentifier;
+use crate::folder::FolderIdentifier;
+
+/// What to do when an extracted file already exists in the destination folder
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverwritePolicy {
+    /// Keep both files by renaming the extracted one
+    #[default]
+    Rename,
+    /// Replace the existing file
+    Overwrite,
+    /// Keep the existing file and skip the extracted one
+    Skip,
+}
+
+impl OverwritePolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rename => "rename",
+            Self::Overwrite => "overwrite",
+            Self::Skip => "skip",
+        }
+    }
+}
+
+impl std::str::FromStr for OverwritePolicy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "rename" => Ok(Self::Rename),
+            "overwrite" => Ok(Self::Overwrite),
+            "skip" => Ok(Self::Skip),
+            _ => Err(format!("invalid overwrite policy {:?}", value)),
+        }
+    }
+}
+
+/// Command to extract an archive stored on PCloud in a folder
+///
+/// The extraction is done in background by the server. Executing this command
+/// will return an [`ExtractArchiveJob`](ExtractArchiveJob) that can be used to follow
+/// the progress with the [`ExtractArchiveProgressCommand`](super::extract_archive_progress::ExtractArchiveProgressCommand).
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/archiving/extractarchive.html)
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::archive::extract_archive::{ExtractArchiveCommand, OverwritePolicy};
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use std::time::Duration;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = ExtractArchiveCommand::new(12.into(), 42.into())
+///     .overwrite(OverwritePolicy::Skip);
+/// let job = cmd.execute(&client).await.unwrap();
+/// let mut progress = job.progress(&client, Duration::from_secs(1));
+/// while let Some(item) = progress.next().await {
+///     for line in item.unwrap().output {
+///         println!("{line}");
+///     }
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct ExtractArchiveCommand {
+    pub identifier: FileIdentifier,
+    pub to_folder: FolderIdentifier,
+    pub overwrite: OverwritePolicy,
+    pub password: Option<Secret>,
+    pub no_output: bool,
+}
+
+impl ExtractArchiveCommand {
+    pub fn new(identifier: FileIdentifier, to_folder: FolderIdentifier) -> Self {
+        Self {
+            identifier,
+            to_folder,
+            overwrite: OverwritePolicy::default(),
+            password: None,
+            no_output: false,
+        }
+    }
+
+    pub fn overwrite(mut self, value: OverwritePolicy) -> Self {
+        self.overwrite = value;
+        self
+    }
+
+    /// Password to decrypt the archive
+    pub fn password(mut self, value: impl Into<Secret>) -> Self {
+        self.password = Some(value.into());
+        self
+    }
+
+    /// If set, the server will not report the extracted files
+    pub fn no_output(mut self, value: bool) -> Self {
+        self.no_output = value;
+        self
+    }
+}
+
+/// The extraction job started by the server
+#[derive(Debug, serde::Deserialize)]
+pub struct ExtractArchiveJob {
+    #[serde(rename = "progresshash")]
+    pub progress_hash: String,
+    #[serde(default)]
+    pub finished: bool,
+    /// The lines already written by the extraction
+    #[serde(default)]
+    pub output: Vec<String>,
+}
+
+mod command {
+    use super::{ExtractArchiveCommand, ExtractArchiveJob};
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Body, Request};
+
+    impl ExtractArchiveCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let mut res = self.identifier.to_http_params();
+            res.push(self.to_folder.to_named_http_param("topath", "tofolderid"));
+            res.push(("overwrite", self.overwrite.as_str().to_string()));
+            if self.no_output {
+                res.push(("nooutput", "1".to_string()));
+            }
+            res
+        }
+    }
+
+    impl Command for ExtractArchiveCommand {
+        type Output = ExtractArchiveJob;
+
+        fn request(&self) -> Request<'_> {
+            let request = Request::new("extractarchive", self.to_http_params());
+            // the password is kept out of the url
+            match self.password {
+                Some(ref password) => request.body(Body::Form(vec![("password", password)])),
+                None => request,
+            }
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<ExtractArchiveJob>(body)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::{ExtractArchiveCommand, OverwritePolicy};
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::{Command, HttpCommand};
+    use crate::region::Region;
+    use mockito::Matcher;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn success_with_progress() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m_extract = server
+            .mock("GET", "/extractarchive")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("fileid".into(), "12".into()),
+                Matcher::UrlEncoded("tofolderid".into(), "42".into()),
+                Matcher::UrlEncoded("overwrite".into(), "skip".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                r#"{ "result": 0, "progresshash": "abcdef", "finished": false, "output": [] }"#,
+            )
+            .create();
+        let m_first = server
+            .mock("GET", "/extractarchiveprogress")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("progresshash".into(), "abcdef".into()),
+                Matcher::UrlEncoded("lines".into(), "0".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{ "result": 0, "finished": false, "output": ["foo.txt"] }"#)
+            .create();
+        let m_second = server
+            .mock("GET", "/extractarchiveprogress")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("progresshash".into(), "abcdef".into()),
+                Matcher::UrlEncoded("lines".into(), "1".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{ "result": 0, "finished": true, "output": ["bar.txt"] }"#)
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let job = ExtractArchiveCommand::new(12.into(), 42.into())
+            .overwrite(OverwritePolicy::Skip)
+            .execute(&api)
+            .await
+            .unwrap();
+        let mut progress = job.progress(&api, Duration::from_millis(1));
+        let mut lines = Vec::new();
+        while let Some(item) = progress.next().await {
+            lines.extend(item.unwrap().output);
+        }
+        assert_eq!(lines, vec!["foo.txt", "bar.txt"]);
+        m_extract.assert();
+        m_first.assert();
+        m_second.assert();
+    }
+
+    #[tokio::test]
+    async fn with_password() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("POST", "/extractarchive")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::Exact(
+                "fileid=12&tofolderid=42&overwrite=rename".into(),
+            ))
+            .match_body(Matcher::UrlEncoded(
+                "password".into(),
+                "archive-secret".into(),
+            ))
+            .with_status(200)
+            .with_body(r#"{ "result": 0, "progresshash": "abcdef", "finished": true }"#)
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let api = HttpClient::new(creds, Region::new(server.url()));
+        let cmd = ExtractArchiveCommand::new(12.into(), 42.into()).password("archive-secret");
+        assert!(!format!("{:?}", cmd).contains("archive-secret"));
+        assert!(!format!("{:?}", cmd.request()).contains("archive-secret"));
+        let job = cmd.execute(&api).await.unwrap();
+        assert!(job.finished);
+        m.assert();
+    }
+}
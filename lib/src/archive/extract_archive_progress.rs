@@ -0,0 +1,117 @@
+//! Resources needed to follow the extraction of an archive
+
+/// Command to get the progress of an archive extraction
+///
+/// Executing this command will return an [`ExtractProgress`](ExtractProgress) on success.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/archiving/extractarchiveprogress.html)
+#[derive(Debug)]
+pub struct ExtractArchiveProgressCommand {
+    pub progress_hash: String,
+    /// Number of output lines already read, that the server should skip
+    pub lines: usize,
+}
+
+impl ExtractArchiveProgressCommand {
+    pub fn new(progress_hash: String) -> Self {
+        Self {
+            progress_hash,
+            lines: 0,
+        }
+    }
+
+    pub fn lines(mut self, value: usize) -> Self {
+        self.lines = value;
+        self
+    }
+}
+
+/// The state of an extraction
+#[derive(Debug, serde::Deserialize)]
+pub struct ExtractProgress {
+    #[serde(default)]
+    pub finished: bool,
+    /// The lines written by the extraction since the last call
+    #[serde(default)]
+    pub output: Vec<String>,
+}
+
+#[cfg(feature = "client-http")]
+impl super::extract_archive::ExtractArchiveJob {
+    /// Creates a stream polling the progress of the extraction every `interval`,
+    /// until the extraction is finished.
+    pub fn progress(
+        self,
+        client: &crate::http::HttpClient,
+        interval: std::time::Duration,
+    ) -> ExtractProgressStream<'_> {
+        let job = ExtractJob {
+            progress_hash: self.progress_hash,
+            lines: self.output.len(),
+            finished: self.finished,
+        };
+        crate::http::progress::ProgressStream::new(client, interval, job)
+    }
+}
+
+/// An extraction followed with the [`ExtractArchiveProgressCommand`](ExtractArchiveProgressCommand)
+#[cfg(feature = "client-http")]
+#[derive(Debug)]
+pub struct ExtractJob {
+    progress_hash: String,
+    /// Number of output lines already read
+    lines: usize,
+    finished: bool,
+}
+
+#[cfg(feature = "client-http")]
+impl crate::http::progress::Job for ExtractJob {
+    type Command = ExtractArchiveProgressCommand;
+
+    fn command(&self) -> Self::Command {
+        ExtractArchiveProgressCommand::new(self.progress_hash.clone()).lines(self.lines)
+    }
+
+    fn update(&mut self, progress: &ExtractProgress) {
+        self.lines += progress.output.len();
+        self.finished = progress.finished;
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// A stream of [`ExtractProgress`](ExtractProgress), ending when the extraction is finished.
+///
+/// Each item contains the output lines written since the previous one.
+#[cfg(feature = "client-http")]
+pub type ExtractProgressStream<'a> = crate::http::progress::ProgressStream<'a, ExtractJob>;
+
+mod command {
+    use super::{ExtractArchiveProgressCommand, ExtractProgress};
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    impl ExtractArchiveProgressCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            vec![
+                ("progresshash", self.progress_hash.clone()),
+                ("lines", self.lines.to_string()),
+            ]
+        }
+    }
+
+    impl Command for ExtractArchiveProgressCommand {
+        type Output = ExtractProgress;
+
+        fn request(&self) -> Request<'_> {
+            Request::new("extractarchiveprogress", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<ExtractProgress>(body)
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Resources needed to download a zip archive
+
+use super::Tree;
+use std::io::Write;
+
+/// Command to download a set of files and folders as a zip archive
+///
+/// The archive is generated on the fly by the server and streamed in the writer.
+/// Executing this command with return the size of the downloaded archive as a `usize`.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/archiving/getzip.html)
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::archive::Tree;
+/// use pcloud::archive::get_zip::GetZipCommand;
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use std::fs::File;
+///
+/// # tokio_test::block_on(async {
+/// let file = File::create("./photos.zip").unwrap();
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = GetZipCommand::new(Tree::default().with_folder(12), file);
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct GetZipCommand<W> {
+    pub tree: Tree,
+    pub writer: W,
+}
+
+impl<W: Write> GetZipCommand<W> {
+    pub fn new(tree: Tree, writer: W) -> Self {
+        Self { tree, writer }
+    }
+}
+
+#[cfg(feature = "client-http")]
+mod http {
+    use super::GetZipCommand;
+    use crate::error::Error;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use std::io::Write;
+
+    #[async_trait::async_trait]
+    impl<W: Write + Send> HttpCommand for GetZipCommand<W> {
+        type Output = usize;
+
+        async fn execute(mut self, client: &HttpClient) -> Result<Self::Output, Error> {
+            let mut req = client
+                .get_request_raw("getzip", &self.tree.to_http_params())
+                .await?;
+            let mut size = 0;
+            while let Some(chunk) = req.chunk().await? {
+                self.writer
+                    .write_all(chunk.as_ref())
+                    .map_err(Error::Download)?;
+                size += chunk.len();
+            }
+            Ok(size)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::GetZipCommand;
+    use crate::archive::Tree;
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn success() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/getzip")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("folderids".into(), "12".into()),
+                Matcher::UrlEncoded("fileids".into(), "42,43".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/zip")
+            .with_body("PK fake archive")
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let mut buffer: Vec<u8> = Vec::new();
+        let tree = Tree::default().with_folder(12).with_file(42).with_file(43);
+        let size = GetZipCommand::new(tree, &mut buffer)
+            .execute(&api)
+            .await
+            .unwrap();
+        assert_eq!(size, 15);
+        assert_eq!(buffer, b"PK fake archive");
+        m.assert();
+    }
+}
@@ -0,0 +1,174 @@
+//! Resources needed to get a link to download a zip archive
+
+use super::Tree;
+
+/// Command to get a link to download a set of files and folders as a zip archive
+///
+/// Executing this command will return a [`StreamingLink`](crate::streaming::StreamingLink) to the archive.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/archiving/getziplink.html)
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::archive::Tree;
+/// use pcloud::archive::get_zip_link::GetZipLinkCommand;
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = GetZipLinkCommand::new(Tree::default().with_folder(12))
+///     .filename("photos.zip".into());
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct GetZipLinkCommand {
+    pub tree: Tree,
+    /// Name of the downloaded archive
+    pub filename: Option<String>,
+    /// Download with Content-Type = application/octet-stream
+    pub force_download: bool,
+    /// Maximum download speed, in bytes per second
+    pub max_speed: Option<u64>,
+}
+
+impl GetZipLinkCommand {
+    pub fn new(tree: Tree) -> Self {
+        Self {
+            tree,
+            filename: None,
+            force_download: false,
+            max_speed: None,
+        }
+    }
+
+    pub fn filename(mut self, value: String) -> Self {
+        self.filename = Some(value);
+        self
+    }
+
+    pub fn force_download(mut self, value: bool) -> Self {
+        self.force_download = value;
+        self
+    }
+
+    pub fn max_speed(mut self, value: u64) -> Self {
+        self.max_speed = Some(value);
+        self
+    }
+}
+
+mod command {
+    use super::GetZipLinkCommand;
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+    use crate::streaming::StreamingLink;
+
+    impl GetZipLinkCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let mut res = self.tree.to_http_params();
+            if let Some(ref filename) = self.filename {
+                res.push(("filename", filename.clone()));
+            }
+            if self.force_download {
+                res.push(("forcedownload", "1".to_string()));
+            }
+            if let Some(max_speed) = self.max_speed {
+                res.push(("maxspeed", max_speed.to_string()));
+            }
+            res
+        }
+    }
+
+    impl Command for GetZipLinkCommand {
+        type Output = StreamingLink;
+
+        fn request(&self) -> Request<'_> {
+            Request::new("getziplink", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<StreamingLink>(body)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::GetZipLinkCommand;
+    use crate::archive::Tree;
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn success() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/getziplink")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("folderids".into(), "12".into()),
+                Matcher::UrlEncoded("filterfileids".into(), "42".into()),
+                Matcher::UrlEncoded("filename".into(), "photos.zip".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                r#"{
+    "result": 0,
+    "dwltag": "yvkNr0TqT6HFAWlVpdnHs5",
+    "expires": "Sat, 24 Jul 2021 03:18:31 +0000",
+    "path": "\/cBZkvG2pXZ\/photos.zip",
+    "hosts": [
+        "edef2.pcloud.com"
+    ]
+}"#,
+            )
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let result = GetZipLinkCommand::new(Tree::default().with_folder(12).exclude_file(42))
+            .filename("photos.zip".into())
+            .execute(&api)
+            .await
+            .unwrap();
+        assert_eq!(
+            result.url().unwrap(),
+            "https://edef2.pcloud.com/cBZkvG2pXZ/photos.zip"
+        );
+        m.assert();
+    }
+
+    #[tokio::test]
+    async fn without_host() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/getziplink")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::UrlEncoded("folderids".into(), "12".into()))
+            .with_status(200)
+            .with_body(r#"{"result": 0, "path": "/photos.zip", "hosts": []}"#)
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let api = HttpClient::new(creds, Region::new(server.url()));
+        let result = GetZipLinkCommand::new(Tree::default().with_folder(12))
+            .execute(&api)
+            .await
+            .unwrap();
+        assert!(result.url().is_none());
+        m.assert();
+    }
+}
@@ -0,0 +1,108 @@
+pub mod extract_archive;
+pub mod extract_archive_progress;
+pub mod get_zip;
+pub mod get_zip_link;
+pub mod save_zip;
+
+/// The set of files and folders to put in an archive.
+///
+/// The folders are added recursively, the excluded files and folders
+/// are removed from the result.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/structures/tree.html).
+///
+/// ```
+/// use pcloud::archive::Tree;
+///
+/// let tree = Tree::default()
+///     .with_folder(12)
+///     .with_file(42)
+///     .exclude_folder(13);
+/// assert!(!tree.is_empty());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Tree {
+    pub folder_ids: Vec<u64>,
+    pub file_ids: Vec<u64>,
+    pub exclude_folder_ids: Vec<u64>,
+    pub exclude_file_ids: Vec<u64>,
+}
+
+impl Tree {
+    /// Adds a folder, and all its content, to the tree
+    pub fn with_folder(mut self, folder_id: u64) -> Self {
+        self.folder_ids.push(folder_id);
+        self
+    }
+
+    /// Adds a single file to the tree
+    pub fn with_file(mut self, file_id: u64) -> Self {
+        self.file_ids.push(file_id);
+        self
+    }
+
+    /// Removes a folder, and all its content, from the tree
+    pub fn exclude_folder(mut self, folder_id: u64) -> Self {
+        self.exclude_folder_ids.push(folder_id);
+        self
+    }
+
+    /// Removes a single file from the tree
+    pub fn exclude_file(mut self, file_id: u64) -> Self {
+        self.exclude_file_ids.push(file_id);
+        self
+    }
+
+    /// Returns true when no file nor folder has been added to the tree
+    pub fn is_empty(&self) -> bool {
+        self.folder_ids.is_empty() && self.file_ids.is_empty()
+    }
+}
+
+fn join_ids(ids: &[u64]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+impl Tree {
+    pub fn to_http_params(&self) -> Vec<(&'static str, String)> {
+        let mut res = Vec::new();
+        if !self.folder_ids.is_empty() {
+            res.push(("folderids", join_ids(&self.folder_ids)));
+        }
+        if !self.file_ids.is_empty() {
+            res.push(("fileids", join_ids(&self.file_ids)));
+        }
+        if !self.exclude_folder_ids.is_empty() {
+            res.push(("filterfolderids", join_ids(&self.exclude_folder_ids)));
+        }
+        if !self.exclude_file_ids.is_empty() {
+            res.push(("filterfileids", join_ids(&self.exclude_file_ids)));
+        }
+        res
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod tests {
+    use super::Tree;
+
+    #[test]
+    fn tree_params() {
+        let tree = Tree::default()
+            .with_folder(1)
+            .with_folder(2)
+            .with_file(3)
+            .exclude_file(4);
+        assert_eq!(
+            tree.to_http_params(),
+            vec![
+                ("folderids", "1,2".to_string()),
+                ("fileids", "3".to_string()),
+                ("filterfileids", "4".to_string()),
+            ]
+        );
+    }
+}
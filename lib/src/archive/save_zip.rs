@@ -0,0 +1,76 @@
+//! Resources needed to create a zip archive in a folder
+
+use super::Tree;
+use crate::folder::FolderIdentifier;
+
+/// Command to create a zip archive of a set of files and folders, and store it in a folder
+///
+/// Executing this command will return the created [`File`](crate::entry::File) on success.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/archiving/savezip.html)
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::archive::Tree;
+/// use pcloud::archive::save_zip::SaveZipCommand;
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = SaveZipCommand::new(Tree::default().with_folder(12), 42.into(), "photos.zip".into());
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct SaveZipCommand {
+    pub tree: Tree,
+    pub to_folder: FolderIdentifier,
+    pub to_name: String,
+}
+
+impl SaveZipCommand {
+    pub fn new(tree: Tree, to_folder: FolderIdentifier, to_name: String) -> Self {
+        Self {
+            tree,
+            to_folder,
+            to_name,
+        }
+    }
+}
+
+mod command {
+    use super::SaveZipCommand;
+    use crate::entry::File;
+    use crate::error::Error;
+    use crate::file::FileResponse;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    impl SaveZipCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let mut res = self.tree.to_http_params();
+            res.push(self.to_folder.to_named_http_param("topath", "tofolderid"));
+            res.push(("toname", self.to_name.clone()));
+            res
+        }
+    }
+
+    impl Command for SaveZipCommand {
+        type Output = File;
+
+        fn request(&self) -> Request<'_> {
+            Request::new("savezip", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<FileResponse>(body).map(|item| item.metadata)
+        }
+    }
+}
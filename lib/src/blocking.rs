@@ -0,0 +1,172 @@
+//! A synchronous client, for the applications not running in an async runtime
+//!
+//! The [`BlockingHttpClient`](BlockingHttpClient) wraps a [`HttpClient`](crate::http::HttpClient)
+//! and its own runtime, so every command implementing [`HttpCommand`](crate::prelude::HttpCommand)
+//! can be executed with [`BlockingCommand`](crate::prelude::BlockingCommand). The downloads and
+//! the uploads directly use a [`std::io::Write`] or a [`std::io::Read`].
+//!
+//! The client must not be used, nor dropped, from an async context.
+//!
+//! ```no_run
+//! use pcloud::blocking::BlockingHttpClient;
+//! use pcloud::file::download::FileDownloadCommand;
+//! use pcloud::folder::list::FolderListCommand;
+//! use pcloud::http::HttpClientBuilder;
+//! use pcloud::prelude::BlockingCommand;
+//! use std::fs::File;
+//!
+//! let client = HttpClientBuilder::from_env()
+//!     .build_blocking()
+//!     .expect("unable to build blocking client");
+//! let root = FolderListCommand::new(0.into())
+//!     .execute(&client)
+//!     .expect("unable to list folder");
+//! let file = File::create("./output.txt").unwrap();
+//! let size = FileDownloadCommand::new("/foo/bar.txt".into(), file)
+//!     .execute(&client)
+//!     .expect("unable to download file");
+//! ```
+
+use crate::http::{HttpClient, HttpClientBuilder, HttpClientBuilderError};
+use std::future::Future;
+use std::sync::Arc;
+
+/// Synchronous client for the pCloud REST API
+///
+/// Its clones share the same runtime, rate limits and connections.
+#[derive(Clone)]
+pub struct BlockingHttpClient {
+    inner: HttpClient,
+    runtime: Arc<tokio::runtime::Runtime>,
+}
+
+impl BlockingHttpClient {
+    /// Wraps the http client with a runtime to execute its requests
+    pub fn new(inner: HttpClient) -> std::io::Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// The wrapped asynchronous client
+    pub fn http_client(&self) -> &HttpClient {
+        &self.inner
+    }
+
+    /// Runs a future on the runtime of the client, like a call to
+    /// [`FolderListCommand::for_each_entry`](crate::folder::list::FolderListCommand::for_each_entry)
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+}
+
+impl HttpClientBuilder {
+    /// Builds a synchronous client for the http protocol
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`build`](HttpClientBuilder::build), or
+    /// `Err(HttpClientBuilderError::Runtime)` when the runtime cannot be started.
+    pub fn build_blocking(self) -> Result<BlockingHttpClient, HttpClientBuilderError> {
+        BlockingHttpClient::new(self.build()?).map_err(HttpClientBuilderError::Runtime)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BlockingHttpClient;
+    use crate::credentials::Credentials;
+    use crate::file::download::FileDownloadCommand;
+    use crate::folder::list::FolderListCommand;
+    use crate::http::HttpClient;
+    use crate::prelude::BlockingCommand;
+    use crate::region::Region;
+    use mockito::Matcher;
+
+    fn client(server: &mockito::Server) -> BlockingHttpClient {
+        let creds = Credentials::AccessToken("access-token".into());
+        BlockingHttpClient::new(HttpClient::new(creds, Region::new(server.url()))).unwrap()
+    }
+
+    #[test]
+    fn should_list_folder() {
+        crate::tests::init();
+        let mut server = mockito::Server::new();
+        let m = server
+            .mock("GET", "/listfolder")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
+                "folderid".into(),
+                "0".into(),
+            )]))
+            .with_status(200)
+            .with_body(
+                r#"{
+    "result": 0,
+    "metadata": {
+        "path": "/",
+        "name": "/",
+        "created": "Sat, 24 Jul 2021 07:38:41 +0000",
+        "ismine": true,
+        "thumb": false,
+        "modified": "Sat, 24 Jul 2021 07:38:41 +0000",
+        "id": "d0",
+        "isshared": false,
+        "icon": "folder",
+        "isfolder": true,
+        "folderid": 0,
+        "contents": []
+    }
+}"#,
+            )
+            .create();
+        let client = client(&server);
+        let result = FolderListCommand::new(0.into()).execute(&client).unwrap();
+        assert_eq!(result.folder_id, 0);
+        assert_eq!(result.contents.unwrap().len(), 0);
+        m.assert();
+    }
+
+    #[test]
+    fn should_download_into_writer() {
+        crate::tests::init();
+        let mut server = mockito::Server::new();
+        let host = server.host_with_port();
+        let m_link = server
+            .mock("GET", "/getfilelink")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
+                "fileid".into(),
+                "42".into(),
+            )]))
+            .with_status(200)
+            .with_body(format!(
+                r#"{{
+    "result": 0,
+    "path": "/download/42/foo.txt",
+    "expires": "Sat, 24 Jul 2021 07:38:41 +0000",
+    "hosts": ["http://{}"]
+}}"#,
+                host
+            ))
+            .create();
+        let m_file = server
+            .mock("GET", "/download/42/foo.txt")
+            .with_status(200)
+            .with_body("hello world")
+            .create();
+        let client = client(&server);
+        let mut buffer: Vec<u8> = Vec::new();
+        let size = FileDownloadCommand::new(42.into(), &mut buffer)
+            .execute(&client)
+            .unwrap();
+        assert_eq!(size, 11);
+        assert_eq!(buffer, b"hello world");
+        m_link.assert();
+        m_file.assert();
+    }
+}
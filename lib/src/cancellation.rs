@@ -0,0 +1,142 @@
+//! Cooperative cancellation of the long running commands, like the downloads and the uploads
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// The tasks waiting for the cancellation, each [`Cancelled`] future owning a slot
+#[derive(Debug, Default)]
+struct Waiters {
+    next_slot: u64,
+    wakers: HashMap<u64, Waker>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    waiters: Mutex<Waiters>,
+}
+
+/// A token to stop a command from another task or thread
+///
+/// The token and its clones share the same state: once cancelled, every command
+/// using it stops at its next step with [`Error::Cancelled`](crate::error::Error::Cancelled).
+///
+/// ```
+/// use pcloud::cancellation::CancellationToken;
+///
+/// let token = CancellationToken::new();
+/// let other = token.clone();
+/// assert!(!token.is_cancelled());
+/// other.cancel();
+/// assert!(token.is_cancelled());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<Inner>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+        let wakers = std::mem::take(&mut self.lock().wakers);
+        wakers.into_values().for_each(Waker::wake);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Completes once the token is cancelled
+    pub fn cancelled(&self) -> Cancelled<'_> {
+        Cancelled {
+            token: self,
+            slot: None,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Waiters> {
+        self.0
+            .waiters
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// The future returned by [`CancellationToken::cancelled`]
+#[derive(Debug)]
+pub struct Cancelled<'a> {
+    token: &'a CancellationToken,
+    /// The slot of the waker, once the future has been polled
+    slot: Option<u64>,
+}
+
+impl Future for Cancelled<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        if this.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+        let mut waiters = this.token.lock();
+        // checked again, the token could have been cancelled before taking the lock
+        if this.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+        let slot = match this.slot {
+            Some(slot) => slot,
+            None => {
+                let slot = waiters.next_slot;
+                waiters.next_slot += 1;
+                this.slot = Some(slot);
+                slot
+            }
+        };
+        match waiters.wakers.get_mut(&slot) {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            Some(waker) => waker.clone_from(cx.waker()),
+            None => {
+                waiters.wakers.insert(slot, cx.waker().clone());
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for Cancelled<'_> {
+    fn drop(&mut self) {
+        if let Some(slot) = self.slot {
+            self.token.lock().wakers.remove(&slot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CancellationToken;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn should_forget_dropped_waiters() {
+        let token = CancellationToken::new();
+        for _ in 0..3 {
+            let result = tokio::time::timeout(Duration::from_millis(1), token.cancelled()).await;
+            assert!(result.is_err());
+        }
+        assert!(token.lock().wakers.is_empty());
+
+        let waiting = token.clone();
+        let handle = tokio::spawn(async move { waiting.cancelled().await });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(token.lock().wakers.len(), 1);
+        token.cancel();
+        handle.await.unwrap();
+        assert!(token.lock().wakers.is_empty());
+    }
+}
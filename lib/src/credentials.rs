@@ -1,20 +1,106 @@
 //! This gives the required structure to authenticate with the PCloud API as specified in [the documentation](https://docs.pcloud.com/methods/intro/authentication.html).
 
+/// The parameters and the fields of the responses holding secrets, never to be logged nor stored
+const SECRETS: &[&str] = &[
+    "access_token",
+    "auth",
+    "code",
+    "digest",
+    "email",
+    "password",
+    "passworddigest",
+    "token",
+    "username",
+];
+
+/// Whether a parameter, or a field of a response, holds a secret
+pub(crate) fn is_secret(name: &str) -> bool {
+    SECRETS.contains(&name)
+}
+
+/// A password or a token, never printed and zeroed in memory when dropped
+///
+/// ```rust
+/// use pcloud::credentials::Secret;
+///
+/// let secret = Secret::from("my-password");
+/// assert_eq!(format!("{secret:?} {secret}"), "[REDACTED] [REDACTED]");
+/// assert_eq!(secret.expose(), "my-password");
+/// ```
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// The actual value, to use only when sending it to pCloud
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for Secret {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Secret {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+impl std::fmt::Display for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("[REDACTED]")
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.0);
+    }
+}
+
 /// The different kind of credentials used for authentication
 #[derive(Clone, Debug)]
 pub enum Credentials {
-    AccessToken(String),
-    UserPassword { username: String, password: String },
+    AccessToken(Secret),
+    UserPassword { username: String, password: Secret },
 }
 
 impl Credentials {
+    pub fn access_token(value: impl Into<Secret>) -> Self {
+        Self::AccessToken(value.into())
+    }
+
+    pub fn user_password(username: impl Into<String>, password: impl Into<Secret>) -> Self {
+        Self::UserPassword {
+            username: username.into(),
+            password: password.into(),
+        }
+    }
+
+    /// The parameters authenticating a request
+    ///
+    /// The [`HttpClient`](crate::http::HttpClient) sends them in the headers or in the body
+    /// of the requests, but never in their url, where they could be recorded.
     #[cfg(feature = "client-http")]
-    pub(crate) fn to_http_params(&self) -> Vec<(&str, String)> {
+    pub(crate) fn to_http_params(&self) -> Vec<(&str, &str)> {
         match self {
-            Self::AccessToken(value) => vec![("access_token", value.clone())],
+            Self::AccessToken(value) => vec![("access_token", value.expose())],
             Self::UserPassword { username, password } => vec![
-                ("username", username.clone()),
-                ("password", password.clone()),
+                ("username", username.as_str()),
+                ("password", password.expose()),
             ],
         }
     }
@@ -40,14 +126,162 @@ impl Credentials {
     /// ```
     pub fn from_env() -> Option<Self> {
         if let Ok(access_token) = std::env::var("PCLOUD_ACCESS_TOKEN") {
-            Some(Self::AccessToken(access_token))
+            Some(Self::access_token(access_token))
         } else if let (Ok(username), Ok(password)) = (
             std::env::var("PCLOUD_USERNAME"),
             std::env::var("PCLOUD_PASSWORD"),
         ) {
-            Some(Self::UserPassword { username, password })
+            Some(Self::user_password(username, password))
         } else {
             None
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Credentials, Secret};
+
+    #[test]
+    fn should_not_print_secrets() {
+        let token = Credentials::access_token("super-secret-token");
+        let password = Credentials::user_password("someone", "super-secret-password");
+        let secret = Secret::from("super-secret");
+        for output in [
+            format!("{:?}", token),
+            format!("{:#?}", password),
+            format!("{:?} {}", secret, secret),
+        ] {
+            assert!(!output.contains("super-secret"), "{}", output);
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::Credentials;
+    use crate::error::Error;
+    use crate::file::download::FileDownloadCommand;
+    use crate::folder::list::FolderListCommand;
+    use crate::http::{HttpClient, HttpClientBuilder};
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use crate::streaming::get_file_link::GetFileLinkCommand;
+    use crate::testing::FakeServer;
+    use mockito::Matcher;
+    use std::sync::{Arc, Mutex};
+
+    /// Collects everything logged with tracing, at every level
+    #[derive(Clone, Default)]
+    struct Logs(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Logs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> tracing_subscriber::fmt::MakeWriter<'a> for Logs {
+        type Writer = Logs;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    impl Logs {
+        fn capture(&self) -> tracing::subscriber::DefaultGuard {
+            let subscriber = tracing_subscriber::fmt()
+                .with_max_level(tracing::Level::TRACE)
+                .with_writer(self.clone())
+                .finish();
+            tracing::subscriber::set_default(subscriber)
+        }
+
+        fn content(&self) -> String {
+            String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+        }
+    }
+
+    #[tokio::test]
+    async fn should_not_leak_access_token() {
+        let logs = Logs::default();
+        let _guard = logs.capture();
+        let server = FakeServer::start().await.unwrap();
+        server.set_access_token("super-secret-token");
+        let builder = server.client_builder();
+        let mut outputs = vec![format!("{:?}", builder)];
+        let client = builder.build().unwrap();
+        FolderListCommand::new(0.into())
+            .execute(&client)
+            .await
+            .unwrap();
+        let error = FolderListCommand::new(42.into())
+            .execute(&client)
+            .await
+            .unwrap_err();
+        outputs.push(format!("{:?} {}", error, error));
+        // a download, whose link gives access to the file
+        let file_id = server.add_file(0, "link-secret.txt", "hello");
+        let link = GetFileLinkCommand::new(file_id.into())
+            .execute(&client)
+            .await
+            .unwrap();
+        let size = FileDownloadCommand::new(file_id.into(), Vec::new())
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(size, 5);
+        // a network error, with the url of the request
+        let creds = Credentials::access_token("super-secret-token");
+        let client = HttpClient::new(creds, Region::new("http://127.0.0.1:1".into()));
+        let error = FolderListCommand::new(0.into())
+            .execute(&client)
+            .await
+            .unwrap_err();
+        assert!(matches!(error.inner(), Error::Reqwest(_)));
+        outputs.push(format!("{:?} {}", error, error));
+        outputs.push(logs.content());
+        for output in outputs {
+            assert!(!output.contains("super-secret-token"), "{}", output);
+            assert!(!output.contains(&link.path), "{}", output);
+        }
+    }
+
+    #[tokio::test]
+    async fn should_send_password_in_body() {
+        let logs = Logs::default();
+        let _guard = logs.capture();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("POST", "/listfolder")
+            .match_query(Matcher::Exact("folderid=0".into()))
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("username".into(), "someone".into()),
+                Matcher::UrlEncoded("password".into(), "super-secret-password".into()),
+            ]))
+            .with_status(200)
+            .with_body("not json")
+            .create();
+        let creds = Credentials::user_password("someone", "super-secret-password");
+        let client = HttpClient::new(creds.clone(), Region::new(server.url()));
+        let error = FolderListCommand::new(0.into())
+            .execute(&client)
+            .await
+            .unwrap_err();
+        m.assert();
+        let builder = HttpClientBuilder::default().credentials(creds);
+        for output in [
+            format!("{:?}", builder),
+            format!("{:?} {}", error, error),
+            logs.content(),
+        ] {
+            assert!(!output.contains("super-secret-password"), "{}", output);
+        }
+    }
+}
@@ -35,3 +35,33 @@ where
         .map(|fixed| fixed.into())
         .map_err(serde::de::Error::custom)
 }
+
+/// Same as the parent module but for optional dates
+pub mod optional {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(date: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match date {
+            Some(value) => super::serialize(value, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Option::<String>::deserialize(deserializer)?;
+        value
+            .map(|value| {
+                DateTime::parse_from_rfc2822(&value)
+                    .map(|fixed| fixed.into())
+                    .map_err(serde::de::Error::custom)
+            })
+            .transpose()
+    }
+}
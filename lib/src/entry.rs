@@ -2,6 +2,8 @@
 
 use chrono::prelude::{DateTime, Utc};
 use std::cmp::Ordering;
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
 
 /// A set of shared fields between [`File`](File) and [`Folder`](Folder).
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
@@ -21,6 +23,140 @@ pub struct EntryBase {
     pub is_shared: bool,
     #[serde(rename = "ismine")]
     pub is_mine: bool,
+    #[serde(default, rename = "isfolder")]
+    pub is_folder: bool,
+    #[serde(default, rename = "isdeleted")]
+    pub is_deleted: bool,
+    /// Number of comments on the entry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comments: Option<u64>,
+}
+
+/// The category of a file, as computed by PCloud from its content type
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize,
+)]
+#[serde(from = "u8", into = "u8")]
+pub enum Category {
+    #[default]
+    Uncategorized,
+    Image,
+    Video,
+    Audio,
+    Document,
+    Archive,
+    /// A category not handled yet by this library
+    Unknown(u8),
+}
+
+impl From<u8> for Category {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Self::Uncategorized,
+            1 => Self::Image,
+            2 => Self::Video,
+            3 => Self::Audio,
+            4 => Self::Document,
+            5 => Self::Archive,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+impl From<Category> for u8 {
+    fn from(value: Category) -> Self {
+        match value {
+            Category::Uncategorized => 0,
+            Category::Image => 1,
+            Category::Video => 2,
+            Category::Audio => 3,
+            Category::Document => 4,
+            Category::Archive => 5,
+            Category::Unknown(other) => other,
+        }
+    }
+}
+
+/// The metadata PCloud extracts from images, videos and audio files.
+///
+/// Each field is only set when it applies to the category of the file.
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct MediaMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// Duration in seconds
+    #[serde(
+        default,
+        with = "crate::number::optional_float",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub duration: Option<f64>,
+    #[serde(
+        default,
+        with = "crate::number::optional_float",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub fps: Option<f64>,
+    /// Rotation of the video, in degrees
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotate: Option<i32>,
+    #[serde(
+        default,
+        rename = "videocodec",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub video_codec: Option<String>,
+    #[serde(
+        default,
+        rename = "videobitrate",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub video_bitrate: Option<u64>,
+    #[serde(
+        default,
+        rename = "audiocodec",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub audio_codec: Option<String>,
+    #[serde(
+        default,
+        rename = "audiobitrate",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub audio_bitrate: Option<u64>,
+    #[serde(
+        default,
+        rename = "audiosamplerate",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub audio_sample_rate: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artist: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub album: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genre: Option<String>,
+    /// Number of the track in the album, as written in the file tags
+    #[serde(default, rename = "trackno", skip_serializing_if = "Option::is_none")]
+    pub track_number: Option<String>,
+}
+
+impl MediaMetadata {
+    /// Width and height of an image or a video
+    pub fn dimensions(&self) -> Option<(u32, u32)> {
+        self.width.zip(self.height)
+    }
+
+    /// Duration of a video or an audio file
+    pub fn duration(&self) -> Option<std::time::Duration> {
+        self.duration
+            .filter(|value| value.is_finite() && *value >= 0.0)
+            .map(std::time::Duration::from_secs_f64)
+    }
 }
 
 /// A structure representing a file on PCloud
@@ -30,10 +166,14 @@ pub struct File {
     pub base: EntryBase,
     #[serde(rename = "fileid")]
     pub file_id: u64,
-    pub size: Option<usize>,
-    pub hash: Option<usize>,
+    pub size: Option<u64>,
+    pub hash: Option<u64>,
     #[serde(rename = "contenttype")]
     pub content_type: Option<String>,
+    #[serde(default)]
+    pub category: Category,
+    #[serde(flatten)]
+    pub media: MediaMetadata,
 }
 
 impl Eq for File {}
@@ -108,10 +248,119 @@ impl Folder {
                 .find(|item| item.base.name == name)
         })
     }
+
+    /// Finds an entry in the loaded tree, with a path relative to this folder like `a/b/c.txt`
+    pub fn get_by_path(&self, path: &str) -> Option<&Entry> {
+        let mut names = path.split('/').filter(|name| !name.is_empty());
+        let mut current = self.find_entry(names.next()?)?;
+        for name in names {
+            current = current.as_folder()?.find_entry(name)?;
+        }
+        Some(current)
+    }
+
+    /// Iterates over the loaded tree, a folder being given before its content
+    pub fn iter_depth_first(&self) -> DepthFirst<'_> {
+        let mut stack: Vec<_> = children(Path::new(""), self).collect();
+        stack.reverse();
+        DepthFirst { stack }
+    }
+
+    /// Iterates over the loaded tree, level by level
+    pub fn iter_breadth_first(&self) -> BreadthFirst<'_> {
+        BreadthFirst {
+            queue: children(Path::new(""), self).collect(),
+        }
+    }
+
+    /// Iterates over the entries of the loaded tree matching the predicate, depth first
+    pub fn filter<'a, P>(&'a self, predicate: P) -> impl Iterator<Item = (PathBuf, &'a Entry)>
+    where
+        P: Fn(&Path, &Entry) -> bool + 'a,
+    {
+        self.iter_depth_first()
+            .filter(move |(path, entry)| predicate(path, entry))
+    }
+
+    /// Indexes all the entries of the loaded tree by their relative path
+    pub fn flatten(&self) -> BTreeMap<PathBuf, &Entry> {
+        self.iter_depth_first().collect()
+    }
+
+    /// Sum of the size of the files in the loaded tree
+    pub fn total_size(&self) -> u64 {
+        self.iter_depth_first()
+            .filter_map(|(_, entry)| entry.as_file())
+            .filter_map(|file| file.size)
+            .sum()
+    }
+
+    /// Number of files in the loaded tree
+    pub fn file_count(&self) -> usize {
+        self.iter_depth_first()
+            .filter(|(_, entry)| entry.is_file())
+            .count()
+    }
+
+    /// Number of folders in the loaded tree, without this one
+    pub fn folder_count(&self) -> usize {
+        self.iter_depth_first()
+            .filter(|(_, entry)| entry.is_folder())
+            .count()
+    }
+}
+
+fn children<'a>(parent: &Path, folder: &'a Folder) -> impl Iterator<Item = (PathBuf, &'a Entry)> {
+    let parent = parent.to_path_buf();
+    folder
+        .contents
+        .iter()
+        .flatten()
+        .map(move |entry| (parent.join(&entry.base().name), entry))
+}
+
+/// Depth first iterator over a loaded tree, see [`Folder::iter_depth_first`]
+#[derive(Debug)]
+pub struct DepthFirst<'a> {
+    stack: Vec<(PathBuf, &'a Entry)>,
+}
+
+impl<'a> Iterator for DepthFirst<'a> {
+    type Item = (PathBuf, &'a Entry);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, entry) = self.stack.pop()?;
+        if let Some(folder) = entry.as_folder() {
+            let start = self.stack.len();
+            self.stack.extend(children(&path, folder));
+            self.stack[start..].reverse();
+        }
+        Some((path, entry))
+    }
+}
+
+/// Breadth first iterator over a loaded tree, see [`Folder::iter_breadth_first`]
+#[derive(Debug)]
+pub struct BreadthFirst<'a> {
+    queue: VecDeque<(PathBuf, &'a Entry)>,
+}
+
+impl<'a> Iterator for BreadthFirst<'a> {
+    type Item = (PathBuf, &'a Entry);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (path, entry) = self.queue.pop_front()?;
+        if let Some(folder) = entry.as_folder() {
+            self.queue.extend(children(&path, folder));
+        }
+        Some((path, entry))
+    }
 }
 
 /// The representation of what can be returned by the PCloud API, a file or a folder.
-#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
+///
+/// When deserializing, the kind of entry is decided by the `isfolder` field.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize)]
 #[serde(untagged)]
 #[allow(clippy::large_enum_variant)]
 pub enum Entry {
@@ -119,6 +368,143 @@ pub enum Entry {
     Folder(Folder),
 }
 
+/// All the fields of a file or a folder, read in a single pass before knowing the kind of entry
+#[derive(serde::Deserialize)]
+struct RawEntry {
+    #[serde(with = "crate::date")]
+    created: DateTime<Utc>,
+    #[serde(with = "crate::date")]
+    modified: DateTime<Utc>,
+    #[serde(default, rename = "parentfolderid")]
+    parent_folder_id: Option<u64>,
+    icon: String,
+    id: String,
+    name: String,
+    #[serde(default)]
+    path: Option<String>,
+    thumb: bool,
+    #[serde(rename = "isshared")]
+    is_shared: bool,
+    #[serde(rename = "ismine")]
+    is_mine: bool,
+    #[serde(default, rename = "isfolder")]
+    is_folder: Option<bool>,
+    #[serde(default, rename = "isdeleted")]
+    is_deleted: bool,
+    #[serde(default)]
+    comments: Option<u64>,
+    #[serde(default, rename = "fileid")]
+    file_id: Option<u64>,
+    #[serde(default)]
+    size: Option<u64>,
+    #[serde(default)]
+    hash: Option<u64>,
+    #[serde(default, rename = "contenttype")]
+    content_type: Option<String>,
+    #[serde(default)]
+    category: Category,
+    #[serde(default, rename = "folderid")]
+    folder_id: Option<u64>,
+    #[serde(default)]
+    contents: Option<Vec<Entry>>,
+    #[serde(default)]
+    width: Option<u32>,
+    #[serde(default)]
+    height: Option<u32>,
+    #[serde(default, with = "crate::number::optional_float")]
+    duration: Option<f64>,
+    #[serde(default, with = "crate::number::optional_float")]
+    fps: Option<f64>,
+    #[serde(default)]
+    rotate: Option<i32>,
+    #[serde(default, rename = "videocodec")]
+    video_codec: Option<String>,
+    #[serde(default, rename = "videobitrate")]
+    video_bitrate: Option<u64>,
+    #[serde(default, rename = "audiocodec")]
+    audio_codec: Option<String>,
+    #[serde(default, rename = "audiobitrate")]
+    audio_bitrate: Option<u64>,
+    #[serde(default, rename = "audiosamplerate")]
+    audio_sample_rate: Option<u64>,
+    #[serde(default)]
+    artist: Option<String>,
+    #[serde(default)]
+    album: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    genre: Option<String>,
+    #[serde(default, rename = "trackno")]
+    track_number: Option<String>,
+}
+
+impl RawEntry {
+    fn into_entry<E: serde::de::Error>(self) -> Result<Entry, E> {
+        // older responses don't always have the flag, the identifiers are enough to decide
+        let is_folder = self
+            .is_folder
+            .unwrap_or(self.file_id.is_none() && self.folder_id.is_some());
+        let base = EntryBase {
+            created: self.created,
+            modified: self.modified,
+            parent_folder_id: self.parent_folder_id,
+            icon: self.icon,
+            id: self.id,
+            name: self.name,
+            path: self.path,
+            thumb: self.thumb,
+            is_shared: self.is_shared,
+            is_mine: self.is_mine,
+            is_folder,
+            is_deleted: self.is_deleted,
+            comments: self.comments,
+        };
+        if is_folder {
+            Ok(Entry::Folder(Folder {
+                base,
+                folder_id: self.folder_id.ok_or_else(|| E::missing_field("folderid"))?,
+                contents: self.contents,
+            }))
+        } else {
+            Ok(Entry::File(File {
+                base,
+                file_id: self.file_id.ok_or_else(|| E::missing_field("fileid"))?,
+                size: self.size,
+                hash: self.hash,
+                content_type: self.content_type,
+                category: self.category,
+                media: MediaMetadata {
+                    width: self.width,
+                    height: self.height,
+                    duration: self.duration,
+                    fps: self.fps,
+                    rotate: self.rotate,
+                    video_codec: self.video_codec,
+                    video_bitrate: self.video_bitrate,
+                    audio_codec: self.audio_codec,
+                    audio_bitrate: self.audio_bitrate,
+                    audio_sample_rate: self.audio_sample_rate,
+                    artist: self.artist,
+                    album: self.album,
+                    title: self.title,
+                    genre: self.genre,
+                    track_number: self.track_number,
+                },
+            }))
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Entry {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        RawEntry::deserialize(deserializer)?.into_entry()
+    }
+}
+
 impl PartialOrd for Entry {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -207,6 +593,19 @@ impl Entry {
             _ => None,
         }
     }
+
+    /// The full path of the entry, only given by some commands
+    pub fn path(&self) -> Option<&str> {
+        self.base().path.as_deref()
+    }
+
+    /// The size of a file, or the sum of the sizes of the files in a folder when its content is loaded
+    pub fn size(&self) -> Option<u64> {
+        match self {
+            Self::File(file) => file.size,
+            Self::Folder(folder) => folder.contents.as_ref().map(|_| folder.total_size()),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -226,11 +625,16 @@ mod tests {
                 thumb: false,
                 is_shared: false,
                 is_mine: false,
+                is_folder: false,
+                is_deleted: false,
+                comments: None,
             },
             file_id: id,
             size: Some(42),
             hash: Some(42),
             content_type: None,
+            category: Category::Uncategorized,
+            media: MediaMetadata::default(),
         }
     }
 
@@ -247,6 +651,9 @@ mod tests {
                 thumb: false,
                 is_shared: false,
                 is_mine: false,
+                is_folder: true,
+                is_deleted: false,
+                comments: None,
             },
             folder_id: id,
             contents: None,
@@ -265,4 +672,165 @@ mod tests {
         let ids: Vec<_> = data.iter().map(|item| item.base().id.clone()).collect();
         assert_eq!(ids, vec!["d2", "d4", "f3", "f1"]);
     }
+
+    #[test]
+    fn deserialize_video_with_metadata() {
+        let file: File = serde_json::from_str(
+            r#"{
+    "name": "holidays.mp4",
+    "created": "Sat, 24 Jul 2021 07:38:41 +0000",
+    "modified": "Sat, 24 Jul 2021 07:38:41 +0000",
+    "thumb": true,
+    "isfolder": false,
+    "fileid": 5257731387,
+    "id": "f5257731387",
+    "isshared": false,
+    "ismine": true,
+    "icon": "video",
+    "comments": 0,
+    "category": 2,
+    "size": 5368709120,
+    "hash": 10681802873941075000,
+    "contenttype": "video/mp4",
+    "width": 1920,
+    "height": 1080,
+    "duration": "60.06",
+    "fps": "29.97",
+    "rotate": 0,
+    "videocodec": "h264",
+    "videobitrate": 8000000,
+    "audiocodec": "aac",
+    "audiobitrate": 128000,
+    "audiosamplerate": 44100
+}"#,
+        )
+        .unwrap();
+        assert_eq!(file.category, Category::Video);
+        assert_eq!(file.size, Some(5368709120));
+        assert_eq!(file.hash, Some(10681802873941075000));
+        assert_eq!(file.media.dimensions(), Some((1920, 1080)));
+        assert_eq!(file.media.fps, Some(29.97));
+        assert_eq!(
+            file.media.duration(),
+            Some(std::time::Duration::from_millis(60060))
+        );
+        assert_eq!(file.media.video_codec.as_deref(), Some("h264"));
+        assert_eq!(file.base.comments, Some(0));
+        // serializing and deserializing back should give the same file
+        let json = serde_json::to_string(&file).unwrap();
+        let other: File = serde_json::from_str(&json).unwrap();
+        assert_eq!(other.category, file.category);
+        assert_eq!(other.hash, file.hash);
+        assert_eq!(other.media, file.media);
+    }
+
+    #[test]
+    fn deserialize_unknown_category() {
+        let category: Category = serde_json::from_str("42").unwrap();
+        assert_eq!(category, Category::Unknown(42));
+        assert_eq!(serde_json::to_string(&category).unwrap(), "42");
+    }
+
+    const FOLDER_WITHOUT_ID: &str = r#"{
+    "name": "broken",
+    "created": "Sat, 24 Jul 2021 07:38:41 +0000",
+    "modified": "Sat, 24 Jul 2021 07:38:41 +0000",
+    "thumb": false,
+    "isfolder": true,
+    "fileid": 12,
+    "id": "d12",
+    "isshared": false,
+    "ismine": true,
+    "icon": "folder"
+}"#;
+
+    #[test]
+    fn deserialize_entry_by_flag() {
+        let err = serde_json::from_str::<Entry>(FOLDER_WITHOUT_ID).unwrap_err();
+        assert!(err.to_string().contains("missing field `folderid`"));
+        let entry: Entry = serde_json::from_str(
+            r#"{
+    "name": "photos",
+    "created": "Sat, 24 Jul 2021 07:38:41 +0000",
+    "modified": "Sat, 24 Jul 2021 07:38:41 +0000",
+    "thumb": false,
+    "folderid": 12,
+    "id": "d12",
+    "isshared": false,
+    "ismine": true,
+    "icon": "folder",
+    "contents": []
+}"#,
+        )
+        .unwrap();
+        assert_eq!(entry.folder_id(), Some(12));
+        assert!(entry.base().is_folder);
+    }
+
+    fn create_tree() -> Folder {
+        let mut photos = create_folder(2, "photos");
+        photos.contents = Some(vec![
+            create_file(3, "a.jpg").into(),
+            create_folder(4, "empty").into(),
+        ]);
+        let mut root = create_folder(0, "/");
+        root.contents = Some(vec![photos.into(), create_file(5, "notes.txt").into()]);
+        root
+    }
+
+    #[test]
+    fn iterate_over_tree() {
+        let root = create_tree();
+        let paths: Vec<_> = root.iter_depth_first().map(|(path, _)| path).collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("photos"),
+                PathBuf::from("photos/a.jpg"),
+                PathBuf::from("photos/empty"),
+                PathBuf::from("notes.txt"),
+            ]
+        );
+        let paths: Vec<_> = root.iter_breadth_first().map(|(path, _)| path).collect();
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("photos"),
+                PathBuf::from("notes.txt"),
+                PathBuf::from("photos/a.jpg"),
+                PathBuf::from("photos/empty"),
+            ]
+        );
+        let folders: Vec<_> = root
+            .filter(|_, entry| entry.is_folder())
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(
+            folders,
+            vec![PathBuf::from("photos"), PathBuf::from("photos/empty")]
+        );
+        assert_eq!(root.flatten().len(), 4);
+    }
+
+    #[test]
+    fn navigate_and_aggregate_tree() {
+        let root = create_tree();
+        assert_eq!(
+            root.get_by_path("photos/a.jpg").and_then(Entry::file_id),
+            Some(3)
+        );
+        assert_eq!(
+            root.get_by_path("/photos/empty/")
+                .and_then(Entry::folder_id),
+            Some(4)
+        );
+        assert!(root.get_by_path("notes.txt/foo").is_none());
+        assert!(root.get_by_path("").is_none());
+        assert_eq!(root.total_size(), 84);
+        assert_eq!(root.file_count(), 2);
+        assert_eq!(root.folder_count(), 2);
+        let photos = root.get_by_path("photos").unwrap();
+        assert_eq!(photos.size(), Some(42));
+        assert_eq!(root.get_by_path("photos/empty").unwrap().size(), None);
+    }
 }
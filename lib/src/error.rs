@@ -27,4 +27,167 @@ pub enum Error {
     /// Error while uploading a file
     #[error("unable to upload file")]
     Upload(#[source] std::io::Error),
+    /// Error while loading or saving the cursor of a change stream
+    #[error("unable to persist change cursor")]
+    Cursor(#[source] std::io::Error),
+    /// Error while writing the cassette of a recording [`HttpClient`](crate::http::HttpClient)
+    #[cfg(feature = "client-http")]
+    #[error("unable to write cassette")]
+    Cassette(#[source] std::io::Error),
+    /// A replaying [`HttpClient`](crate::http::HttpClient) has no recorded response for the request
+    #[cfg(feature = "client-http")]
+    #[error("unmatched request: {0}")]
+    Unmatched(String),
+    /// The command has been stopped by its [`CancellationToken`](crate::cancellation::CancellationToken)
+    #[error("command cancelled")]
+    Cancelled,
+    /// A transfer has been aborted by the [`HttpClient`](crate::http::HttpClient), it can be retried
+    #[cfg(feature = "client-http")]
+    #[error("transfer stalled: {0}")]
+    Stalled(#[source] crate::http::transfer::Stall),
+    /// An error with the request that caused it, see [`Error::context`]
+    #[error("{source} ({context})")]
+    Context {
+        context: Box<Context>,
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Attaches the context of the request, except to the protocol errors that are self explanatory
+    pub fn with_context(self, context: Context) -> Self {
+        match self {
+            Self::Protocol(..) | Self::Cancelled | Self::Context { .. } => self,
+            // the url of a reqwest error contains the credentials
+            #[cfg(feature = "client-http")]
+            Self::Reqwest(inner) => Self::Context {
+                context: Box::new(context),
+                source: Box::new(Self::Reqwest(inner.without_url())),
+            },
+            other => Self::Context {
+                context: Box::new(context),
+                source: Box::new(other),
+            },
+        }
+    }
+
+    /// The context of the request that failed, when known
+    pub fn context(&self) -> Option<&Context> {
+        match self {
+            Self::Context { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// The error without its context
+    pub fn inner(&self) -> &Error {
+        match self {
+            Self::Context { source, .. } => source.inner(),
+            other => other,
+        }
+    }
+}
+
+/// What was sent and received when an error happened, without the credentials
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+pub struct Context {
+    /// The pCloud method, like `listfolder`
+    pub method: String,
+    /// The parameters of the request, without the credentials nor the secrets
+    pub params: Vec<(String, String)>,
+    /// The http status of the response
+    pub status: Option<u16>,
+    /// The beginning of the body of the response
+    pub body: Option<String>,
+}
+
+impl Context {
+    /// The maximum size of the body kept in the context, in bytes
+    pub const MAX_BODY_SIZE: usize = 1024;
+
+    pub fn new(method: &str, params: &[(&str, String)]) -> Self {
+        Self {
+            method: method.to_string(),
+            params: params
+                .iter()
+                .filter(|(name, _)| !crate::credentials::is_secret(name))
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+            status: None,
+            body: None,
+        }
+    }
+
+    pub fn status(mut self, value: u16) -> Self {
+        self.status = Some(value);
+        self
+    }
+
+    /// Keeps the beginning of the body, decoded lossily
+    pub fn body(mut self, value: &[u8]) -> Self {
+        let text = String::from_utf8_lossy(value);
+        let body = if text.len() > Self::MAX_BODY_SIZE {
+            let mut end = Self::MAX_BODY_SIZE;
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            format!("{}...", &text[..end])
+        } else {
+            text.into_owned()
+        };
+        self.body = Some(body);
+        self
+    }
+}
+
+impl std::fmt::Display for Context {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "method {}", self.method)?;
+        for (index, (name, value)) in self.params.iter().enumerate() {
+            let separator = if index == 0 { " with " } else { ", " };
+            write!(f, "{separator}{name}={value:?}")?;
+        }
+        if let Some(status) = self.status {
+            write!(f, ", status {status}")?;
+        }
+        if let Some(ref body) = self.body {
+            write!(f, ", body {body:?}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Context, Error};
+
+    #[test]
+    fn should_remove_credentials_and_truncate_body() {
+        let params = vec![
+            ("access_token", "secret".to_string()),
+            ("folderid", "42".to_string()),
+        ];
+        let body = "é".repeat(Context::MAX_BODY_SIZE);
+        let context = Context::new("listfolder", &params)
+            .status(200)
+            .body(body.as_bytes());
+        assert_eq!(context.params, vec![("folderid".into(), "42".into())]);
+        let body = context.body.as_deref().unwrap();
+        assert!(body.len() <= Context::MAX_BODY_SIZE + 3);
+        assert!(body.ends_with("..."));
+        let error =
+            Error::SerdeJson(serde_json::from_str::<u8>("nope").unwrap_err()).with_context(context);
+        let message = error.to_string();
+        assert!(message.starts_with("unable to decode pcloud response (method listfolder with folderid=\"42\", status 200, body"));
+        assert!(!message.contains("secret"));
+        assert!(matches!(error.inner(), Error::SerdeJson(_)));
+        assert_eq!(error.context().unwrap().status, Some(200));
+    }
+
+    #[test]
+    fn should_not_wrap_protocol_errors() {
+        let error = Error::Protocol(2005, "Directory does not exist.".into())
+            .with_context(Context::new("listfolder", &[]));
+        assert!(matches!(error, Error::Protocol(2005, _)));
+    }
 }
@@ -62,23 +62,21 @@ pub struct CheckSumFile {
     pub metadata: File,
 }
 
-#[cfg(feature = "client-http")]
-mod http {
+mod command {
     use super::{CheckSumFile, FileCheckSumCommand};
     use crate::error::Error;
-    use crate::http::HttpClient;
-    use crate::prelude::HttpCommand;
-    use crate::request::Response;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
 
-    #[async_trait::async_trait]
-    impl HttpCommand for FileCheckSumCommand {
+    impl Command for FileCheckSumCommand {
         type Output = CheckSumFile;
 
-        async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error> {
-            let result: Response<CheckSumFile> = client
-                .get_request("checksumfile", &self.identifier.to_http_params())
-                .await?;
-            result.payload()
+        fn request(&self) -> Request<'_> {
+            Request::new("checksumfile", self.identifier.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<CheckSumFile>(body)
         }
     }
 }
@@ -98,10 +96,11 @@ mod http_tests {
         let mut server = mockito::Server::new_async().await;
         let m = server
             .mock("GET", "/checksumfile")
-            .match_query(Matcher::AllOf(vec![
-                Matcher::UrlEncoded("access_token".into(), "access-token".into()),
-                Matcher::UrlEncoded("fileid".into(), "42".into()),
-            ]))
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
+                "fileid".into(),
+                "42".into(),
+            )]))
             .with_status(200)
             .with_body(
                 r#"{
@@ -56,15 +56,13 @@ impl FileCopyCommand {
     }
 }
 
-#[cfg(feature = "client-http")]
-mod http {
+mod command {
     use super::FileCopyCommand;
     use crate::entry::File;
     use crate::error::Error;
     use crate::file::FileResponse;
-    use crate::http::HttpClient;
-    use crate::prelude::HttpCommand;
-    use crate::request::Response;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
 
     impl FileCopyCommand {
         fn to_http_params(&self) -> Vec<(&str, String)> {
@@ -75,15 +73,15 @@ mod http {
         }
     }
 
-    #[async_trait::async_trait]
-    impl HttpCommand for FileCopyCommand {
+    impl Command for FileCopyCommand {
         type Output = File;
 
-        async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error> {
-            let result: Response<FileResponse> = client
-                .get_request("copyfile", &self.to_http_params())
-                .await?;
-            result.payload().map(|item| item.metadata)
+        fn request(&self) -> Request<'_> {
+            Request::new("copyfile", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<FileResponse>(body).map(|item| item.metadata)
         }
     }
 }
@@ -55,25 +55,23 @@ impl FileDeleteCommand {
     }
 }
 
-#[cfg(feature = "client-http")]
-mod http {
+mod command {
     use super::FileDeleteCommand;
     use crate::entry::File;
     use crate::error::Error;
     use crate::file::FileResponse;
-    use crate::http::HttpClient;
-    use crate::prelude::HttpCommand;
-    use crate::request::Response;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
 
-    #[async_trait::async_trait]
-    impl HttpCommand for FileDeleteCommand {
+    impl Command for FileDeleteCommand {
         type Output = File;
 
-        async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error> {
-            let result: Response<FileResponse> = client
-                .get_request("deletefile", &self.identifier.to_http_params())
-                .await?;
-            result.payload().map(|res| res.metadata)
+        fn request(&self) -> Request<'_> {
+            Request::new("deletefile", self.identifier.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<FileResponse>(body).map(|res| res.metadata)
         }
     }
 }
@@ -93,10 +91,11 @@ mod http_tests {
         let mut server = mockito::Server::new_async().await;
         let m = server
             .mock("GET", "/deletefile")
-            .match_query(Matcher::AllOf(vec![
-                Matcher::UrlEncoded("access_token".into(), "access-token".into()),
-                Matcher::UrlEncoded("fileid".into(), "42".into()),
-            ]))
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
+                "fileid".into(),
+                "42".into(),
+            )]))
             .with_status(200)
             .with_body(
                 r#"{
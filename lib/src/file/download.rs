@@ -1,6 +1,7 @@
 //! Resources needed to download a file
 
 use super::FileIdentifier;
+use crate::cancellation::CancellationToken;
 use std::io::Write;
 
 /// Command to download a file
@@ -33,11 +34,22 @@ use std::io::Write;
 pub struct FileDownloadCommand<W> {
     pub identifier: FileIdentifier,
     pub writer: W,
+    pub cancellation: Option<CancellationToken>,
 }
 
 impl<W: Write> FileDownloadCommand<W> {
     pub fn new(identifier: FileIdentifier, writer: W) -> Self {
-        Self { identifier, writer }
+        Self {
+            identifier,
+            writer,
+            cancellation: None,
+        }
+    }
+
+    /// Stops the download once the token is cancelled
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
     }
 }
 
@@ -45,6 +57,7 @@ impl<W: Write> FileDownloadCommand<W> {
 mod http {
     use super::FileDownloadCommand;
     use crate::error::Error;
+    use crate::http::transfer::{supervise, Progress};
     use crate::http::HttpClient;
     use crate::prelude::HttpCommand;
     use crate::streaming::get_file_link::GetFileLinkCommand;
@@ -57,13 +70,22 @@ mod http {
         async fn execute(mut self, client: &HttpClient) -> Result<Self::Output, Error> {
             let link = GetFileLinkCommand::new(self.identifier)
                 .execute(client)
-                .await?;
-            let mut req = client.client.get(&link).send().await?;
-            let mut size = 0;
-            while let Some(chunk) = req.chunk().await? {
-                size += self.writer.write(chunk.as_ref()).map_err(Error::Download)?;
-            }
-            Ok(size)
+                .await?
+                .url()
+                .ok_or_else(|| Error::Protocol(0, "no host available".into()))?;
+            let progress = Progress::new();
+            let writer = &mut self.writer;
+            let transfer = async {
+                let mut req = client.get_link(&link).await?.progress(&progress);
+                let mut size = 0;
+                while let Some(chunk) = req.chunk().await? {
+                    progress.add(chunk.len());
+                    size += writer.write(chunk.as_ref()).map_err(Error::Download)?;
+                }
+                Ok(size)
+            };
+            let token = self.cancellation.as_ref();
+            supervise(client.transfer_limits(), token, &progress, transfer).await
         }
     }
 }
@@ -0,0 +1,304 @@
+//! Resources needed to import remote files from their urls
+
+use crate::folder::FolderIdentifier;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static PROGRESS_HASH_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Generates a unique value to follow the progress of an import
+/// with the [`UploadProgressCommand`](super::upload_progress::UploadProgressCommand).
+pub fn generate_progress_hash() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|value| value.as_nanos())
+        .unwrap_or_default();
+    let counter = PROGRESS_HASH_COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("pcloudrs{nanos:x}{counter:x}")
+}
+
+/// Command to make the server download files from urls into a folder
+///
+/// The server downloads the files before responding, executing this command
+/// will return a `Vec` of the created [`File`](crate::entry::File) on success.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/file/downloadfile.html)
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::file::import::FileImportCommand;
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = FileImportCommand::new(vec!["https://example.com/foo.txt".into()], 12.into());
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct FileImportCommand {
+    pub urls: Vec<String>,
+    pub folder: FolderIdentifier,
+    pub progress_hash: Option<String>,
+}
+
+impl FileImportCommand {
+    pub fn new(urls: Vec<String>, folder: FolderIdentifier) -> Self {
+        Self {
+            urls,
+            folder,
+            progress_hash: None,
+        }
+    }
+
+    /// Value to follow the progress with the [`UploadProgressCommand`](super::upload_progress::UploadProgressCommand)
+    pub fn progress_hash(mut self, value: String) -> Self {
+        self.progress_hash = Some(value);
+        self
+    }
+}
+
+/// Command to make the server download files from urls into a folder, in background
+///
+/// Executing this command will return an [`ImportJob`](ImportJob) that can be used to follow
+/// the progress and get the created files.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/file/downloadfileasync.html)
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::file::import::FileImportAsyncCommand;
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use std::time::Duration;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = FileImportAsyncCommand::new(vec!["https://example.com/foo.txt".into()], 12.into());
+/// let job = cmd.execute(&client).await.unwrap();
+/// let mut progress = job.progress(&client, Duration::from_secs(1));
+/// while let Some(item) = progress.next().await {
+///     let item = item.unwrap();
+///     println!("{}/{}", item.uploaded, item.total);
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct FileImportAsyncCommand {
+    pub urls: Vec<String>,
+    pub folder: FolderIdentifier,
+    pub progress_hash: String,
+}
+
+impl FileImportAsyncCommand {
+    pub fn new(urls: Vec<String>, folder: FolderIdentifier) -> Self {
+        Self {
+            urls,
+            folder,
+            progress_hash: generate_progress_hash(),
+        }
+    }
+
+    /// Overrides the generated value used to follow the progress
+    pub fn progress_hash(mut self, value: String) -> Self {
+        self.progress_hash = value;
+        self
+    }
+}
+
+/// The import running in background on the server
+#[derive(Debug)]
+pub struct ImportJob {
+    pub progress_hash: String,
+}
+
+mod command {
+    use super::{FileImportAsyncCommand, FileImportCommand, ImportJob};
+    use crate::entry::File;
+    use crate::error::Error;
+    use crate::folder::FolderIdentifier;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    #[derive(Debug, serde::Deserialize)]
+    struct ImportPayload {
+        #[serde(default)]
+        metadata: Vec<File>,
+    }
+
+    fn to_http_params<'a>(
+        urls: &[String],
+        folder: &'a FolderIdentifier,
+        progress_hash: Option<&String>,
+    ) -> Vec<(&'a str, String)> {
+        let mut res = vec![("url", urls.join(" ")), folder.to_http_param()];
+        if let Some(hash) = progress_hash {
+            res.push(("progresshash", hash.clone()));
+        }
+        res
+    }
+
+    impl Command for FileImportCommand {
+        type Output = Vec<File>;
+
+        fn immediate(&self) -> Option<Self::Output> {
+            self.urls.is_empty().then(Vec::new)
+        }
+
+        fn request(&self) -> Request<'_> {
+            let params = to_http_params(&self.urls, &self.folder, self.progress_hash.as_ref());
+            Request::new("downloadfile", params)
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<ImportPayload>(body).map(|item| item.metadata)
+        }
+    }
+
+    impl Command for FileImportAsyncCommand {
+        type Output = ImportJob;
+
+        fn request(&self) -> Request<'_> {
+            let params = to_http_params(&self.urls, &self.folder, Some(&self.progress_hash));
+            Request::new("downloadfileasync", params)
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<()>(body).map(|_| ImportJob {
+                progress_hash: self.progress_hash.clone(),
+            })
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::{FileImportAsyncCommand, FileImportCommand};
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use mockito::Matcher;
+    use std::time::Duration;
+
+    const FILE_METADATA: &str = r#"{
+        "name": "foo.txt",
+        "created": "Sat, 24 Jul 2021 07:38:41 +0000",
+        "thumb": false,
+        "modified": "Sat, 24 Jul 2021 07:38:41 +0000",
+        "isfolder": false,
+        "fileid": 5257731387,
+        "hash": 9403476549337371523,
+        "comments": 0,
+        "category": 0,
+        "id": "f5257731387",
+        "isshared": false,
+        "ismine": true,
+        "size": 12,
+        "parentfolderid": 42,
+        "contenttype": "text\/plain",
+        "icon": "document"
+    }"#;
+
+    #[tokio::test]
+    async fn success() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/downloadfile")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded(
+                    "url".into(),
+                    "http://example.com/foo.txt http://example.com/bar.txt".into(),
+                ),
+                Matcher::UrlEncoded("folderid".into(), "42".into()),
+            ]))
+            .with_status(200)
+            .with_body(format!(
+                r#"{{ "result": 0, "metadata": [{FILE_METADATA}] }}"#
+            ))
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let result = FileImportCommand::new(
+            vec![
+                "http://example.com/foo.txt".into(),
+                "http://example.com/bar.txt".into(),
+            ],
+            42.into(),
+        )
+        .execute(&api)
+        .await
+        .unwrap();
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].base.name, "foo.txt");
+        m.assert();
+    }
+
+    #[tokio::test]
+    async fn async_success_with_progress() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m_import = server
+            .mock("GET", "/downloadfileasync")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("url".into(), "http://example.com/foo.txt".into()),
+                Matcher::UrlEncoded("folderid".into(), "42".into()),
+                Matcher::UrlEncoded("progresshash".into(), "abcdef".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{ "result": 0 }"#)
+            .create();
+        let m_progress = server
+            .mock("GET", "/uploadprogress")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("progresshash".into(), "abcdef".into()),
+            ]))
+            .with_status(200)
+            .with_body(format!(
+                r#"{{ "result": 0, "total": 12, "uploaded": 12, "finished": true, "files": [{FILE_METADATA}] }}"#
+            ))
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let job = FileImportAsyncCommand::new(vec!["http://example.com/foo.txt".into()], 42.into())
+            .progress_hash("abcdef".into())
+            .execute(&api)
+            .await
+            .unwrap();
+        let mut progress = job.progress(&api, Duration::from_millis(1));
+        let mut files = Vec::new();
+        while let Some(item) = progress.next().await {
+            let item = item.unwrap();
+            if item.finished {
+                files = item.files;
+            }
+        }
+        assert_eq!(files.len(), 1);
+        m_import.assert();
+        m_progress.assert();
+    }
+
+    #[test]
+    fn unique_progress_hash() {
+        assert_ne!(
+            super::generate_progress_hash(),
+            super::generate_progress_hash()
+        );
+    }
+}
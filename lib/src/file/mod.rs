@@ -2,8 +2,10 @@ pub mod checksum;
 pub mod copy;
 pub mod delete;
 pub mod download;
+pub mod import;
 pub mod rename;
 pub mod upload;
+pub mod upload_progress;
 
 use crate::entry::File;
 
@@ -47,7 +49,6 @@ impl From<u64> for FileIdentifier {
     }
 }
 
-#[cfg(feature = "client-http")]
 impl FileIdentifier {
     pub fn to_named_http_param(
         &self,
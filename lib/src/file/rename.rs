@@ -67,15 +67,13 @@ impl FileRenameCommand {
     }
 }
 
-#[cfg(feature = "client-http")]
-mod http {
+mod command {
     use super::{FileMoveCommand, FileRenameCommand};
     use crate::entry::File;
     use crate::error::Error;
     use crate::file::FileResponse;
-    use crate::http::HttpClient;
-    use crate::prelude::HttpCommand;
-    use crate::request::Response;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
 
     impl FileMoveCommand {
         fn to_http_params(&self) -> Vec<(&str, String)> {
@@ -86,15 +84,15 @@ mod http {
         }
     }
 
-    #[async_trait::async_trait]
-    impl HttpCommand for FileMoveCommand {
+    impl Command for FileMoveCommand {
         type Output = File;
 
-        async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error> {
-            let result: Response<FileResponse> = client
-                .get_request("renamefile", &self.to_http_params())
-                .await?;
-            result.payload().map(|item| item.metadata)
+        fn request(&self) -> Request<'_> {
+            Request::new("renamefile", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<FileResponse>(body).map(|item| item.metadata)
         }
     }
 
@@ -107,15 +105,15 @@ mod http {
         }
     }
 
-    #[async_trait::async_trait]
-    impl HttpCommand for FileRenameCommand {
+    impl Command for FileRenameCommand {
         type Output = File;
 
-        async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error> {
-            let result: Response<FileResponse> = client
-                .get_request("renamefile", &self.to_http_params())
-                .await?;
-            result.payload().map(|item| item.metadata)
+        fn request(&self) -> Request<'_> {
+            Request::new("renamefile", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<FileResponse>(body).map(|item| item.metadata)
         }
     }
 }
@@ -1,18 +1,17 @@
 //! Resources needed to upload a file
 
+use crate::cancellation::CancellationToken;
 use std::io::Read;
 
 /// Default size for splitting into chunks
 pub const DEFAULT_PART_SIZE: usize = 10485760;
 
 #[derive(Debug)]
-#[cfg(feature = "client-http")]
 pub struct MultipartFileUploadCommand {
-    pub entries: Vec<(String, reqwest::Body)>,
+    pub entries: Vec<(String, Vec<u8>)>,
     pub folder_id: u64,
 }
 
-#[cfg(feature = "client-http")]
 impl MultipartFileUploadCommand {
     pub fn new(folder_id: u64) -> Self {
         Self {
@@ -21,15 +20,6 @@ impl MultipartFileUploadCommand {
         }
     }
 
-    /// This method doesn't work because the file size is not known and therefore
-    /// the content-length is not populated.
-    // pub fn add_tokio_file_entry(self, filename: String, file: tokio::fs::File) -> Self {
-    //     tracing::warn!(
-    //         "handle tokio file does not work, the content-length header will not be set."
-    //     );
-    //     self.add_entry(filename, Body::from(file))
-    // }
-
     pub fn add_sync_file_entry(
         self,
         filename: String,
@@ -45,11 +35,11 @@ impl MultipartFileUploadCommand {
     ) -> Result<Self, std::io::Error> {
         let mut buffer = Vec::new();
         reader.read_to_end(&mut buffer)?;
-        Ok(self.add_entry(filename, reqwest::Body::from(buffer)))
+        Ok(self.add_entry(filename, buffer))
     }
 
-    pub fn add_entry(mut self, filename: String, body: reqwest::Body) -> Self {
-        self.entries.push((filename, body));
+    pub fn add_entry(mut self, filename: String, data: Vec<u8>) -> Self {
+        self.entries.push((filename, data));
         self
     }
 }
@@ -122,6 +112,7 @@ pub struct FileUploadCommand<'a, R> {
     pub reader: R,
     pub no_partial: bool,
     pub part_size: usize,
+    pub cancellation: Option<CancellationToken>,
 }
 
 impl<'a, R: Read + Send> FileUploadCommand<'a, R> {
@@ -132,6 +123,7 @@ impl<'a, R: Read + Send> FileUploadCommand<'a, R> {
             reader,
             no_partial: false,
             part_size: DEFAULT_PART_SIZE,
+            cancellation: None,
         }
     }
 
@@ -144,84 +136,116 @@ impl<'a, R: Read + Send> FileUploadCommand<'a, R> {
         self.part_size = part_size;
         self
     }
+
+    /// Stops the upload once the token is cancelled
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
 }
 
-#[cfg(feature = "client-http")]
-mod http {
-    use super::{FileUploadCommand, MultipartFileUploadCommand, MultipartFileUploadResponse};
+mod command {
+    use super::{MultipartFileUploadCommand, MultipartFileUploadResponse};
     use crate::entry::File;
     use crate::error::Error;
-    use crate::file::FileResponse;
-    use crate::http::HttpClient;
-    use crate::prelude::HttpCommand;
-    use crate::request::Response;
-    use reqwest::multipart;
-    use std::io::Read;
+    use crate::prelude::Command;
+    use crate::request::{self, Body, Part, Request};
 
-    #[async_trait::async_trait]
-    impl HttpCommand for MultipartFileUploadCommand {
+    impl Command for MultipartFileUploadCommand {
         type Output = Vec<File>;
 
-        async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error> {
-            if self.entries.is_empty() {
-                return Ok(Vec::new());
-            }
-
-            let mut form = multipart::Form::new();
-
-            for (index, (filename, body)) in self.entries.into_iter().enumerate() {
-                let part_name = format!("f{index}");
-                let part = multipart::Part::stream(body).file_name(filename);
-                form = form.part(part_name, part);
-            }
+        fn immediate(&self) -> Option<Self::Output> {
+            self.entries.is_empty().then(Vec::new)
+        }
 
+        fn request(&self) -> Request<'_> {
+            let parts = self
+                .entries
+                .iter()
+                .enumerate()
+                .map(|(index, (filename, data))| Part {
+                    name: format!("f{index}"),
+                    filename,
+                    data,
+                })
+                .collect();
             let params = vec![("folderid", self.folder_id.to_string())];
-            let result: Response<MultipartFileUploadResponse> = client
-                .post_request_multipart("uploadfile", &params, form)
-                .await?;
+            Request::new("uploadfile", params).body(Body::Multipart(parts))
+        }
 
-            Ok(result.payload()?.metadata)
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<MultipartFileUploadResponse>(body).map(|item| item.metadata)
         }
     }
+}
+
+#[cfg(feature = "client-http")]
+mod http {
+    use super::FileUploadCommand;
+    use crate::entry::File;
+    use crate::error::Error;
+    use crate::file::FileResponse;
+    use crate::http::transfer::{supervise, Progress};
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::request::Response;
+    use std::io::Read;
 
     #[async_trait::async_trait]
     impl<'a, R: Read + Send> HttpCommand for FileUploadCommand<'a, R> {
         type Output = File;
 
         async fn execute(self, client: &HttpClient) -> Result<File, Error> {
-            let params = if self.no_partial {
-                vec![("nopartial", 1.to_string())]
-            } else {
-                Vec::new()
-            };
-            let result: Response<CreateUploadPayload> =
-                client.get_request("upload_create", &params).await?;
-            let upload_id = result.payload().map(|item| item.upload_id)?;
-
-            let mut reader = ChunkReader::new(self.reader, self.part_size);
-
-            let upload_id_str = upload_id.to_string();
-
-            while let (offset, Some(chunk)) = reader.next_chunk()? {
-                let offset = offset.to_string();
-                let params = vec![
-                    ("uploadid", upload_id_str.to_string()),
-                    ("uploadoffset", offset.to_string()),
-                ];
-                let response: Response<()> = client
-                    .put_request_data("upload_write", &params, chunk)
-                    .await?;
-                response.payload()?;
-            }
+            let progress = Progress::new();
+            let token = self.cancellation.clone();
+            let transfer = upload(self, client, &progress);
+            supervise(
+                client.transfer_limits(),
+                token.as_ref(),
+                &progress,
+                transfer,
+            )
+            .await
+        }
+    }
 
+    async fn upload<R: Read + Send>(
+        cmd: FileUploadCommand<'_, R>,
+        client: &HttpClient,
+        progress: &Progress,
+    ) -> Result<File, Error> {
+        let params = if cmd.no_partial {
+            vec![("nopartial", 1.to_string())]
+        } else {
+            Vec::new()
+        };
+        let result: Response<CreateUploadPayload> =
+            client.get_request("upload_create", &params).await?;
+        let upload_id = result.payload().map(|item| item.upload_id)?;
+
+        let mut reader = ChunkReader::new(cmd.reader, cmd.part_size);
+
+        let upload_id_str = upload_id.to_string();
+
+        while let (offset, Some(chunk)) = reader.next_chunk()? {
+            let offset = offset.to_string();
             let params = vec![
-                ("uploadid", upload_id.to_string()),
-                ("name", self.filename.to_string()),
-                ("folderid", self.folder_id.to_string()),
+                ("uploadid", upload_id_str.to_string()),
+                ("uploadoffset", offset.to_string()),
             ];
-            let result: Response<FileResponse> = client.get_request("upload_save", &params).await?;
-            result.payload().map(|item| item.metadata)
+            let response: Response<()> = client
+                .put_request_data("upload_write", &params, chunk, progress)
+                .await?;
+            response.payload()?;
         }
+
+        let params = vec![
+            ("uploadid", upload_id.to_string()),
+            ("name", cmd.filename.to_string()),
+            ("folderid", cmd.folder_id.to_string()),
+        ];
+        let result: Response<FileResponse> = client.get_request("upload_save", &params).await?;
+        result.payload().map(|item| item.metadata)
     }
 
     #[derive(Debug, serde::Deserialize)]
@@ -268,15 +292,45 @@ mod http {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::MultipartFileUploadCommand;
+    use crate::prelude::Command;
+    use crate::request::Body;
+
+    #[test]
+    fn should_describe_multipart_request() {
+        let cmd = MultipartFileUploadCommand::new(12)
+            .add_entry("a.txt".into(), b"hello".to_vec())
+            .add_entry("b.txt".into(), b"world!".to_vec());
+        let request = cmd.request();
+        assert_eq!(request.http_method(), "POST");
+        assert_eq!(request.method, "uploadfile");
+        assert_eq!(request.params, vec![("folderid", "12".to_string())]);
+        assert_eq!(request.body.len(), 11);
+        match request.body {
+            Body::Multipart(parts) => {
+                assert_eq!(parts[1].name, "f1");
+                assert_eq!(parts[1].filename, "b.txt");
+            }
+            other => panic!("unexpected body {:?}", other),
+        }
+        assert!(MultipartFileUploadCommand::new(12).immediate().is_some());
+    }
+}
+
 #[cfg(all(test, feature = "client-http"))]
 mod http_tests {
     use super::{FileUploadCommand, MultipartFileUploadCommand};
     use crate::credentials::Credentials;
-    use crate::http::HttpClient;
+    use crate::http::limiter::RateLimit;
+    use crate::http::transfer::TransferLimits;
+    use crate::http::{HttpClient, HttpClientBuilder};
     use crate::prelude::HttpCommand;
     use crate::region::Region;
     use mockito::Matcher;
     use std::fs::File;
+    use std::time::Duration;
 
     #[tokio::test]
     async fn multipart_success() {
@@ -284,10 +338,11 @@ mod http_tests {
         let mut server = mockito::Server::new_async().await;
         let m_upload = server
             .mock("POST", "/uploadfile")
-            .match_query(Matcher::AllOf(vec![
-                Matcher::UrlEncoded("access_token".into(), "access-token".into()),
-                Matcher::UrlEncoded("folderid".into(), "0".into()),
-            ]))
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
+                "folderid".into(),
+                "0".into(),
+            )]))
             .match_body(Matcher::Any)
             .match_header("accept", "*/*")
             .match_header("user-agent", crate::http::USER_AGENT)
@@ -350,17 +405,16 @@ mod http_tests {
         let mut server = mockito::Server::new_async().await;
         let m_create = server
             .mock("GET", "/upload_create")
-            .match_query(Matcher::UrlEncoded(
-                "access_token".into(),
-                "access-token".into(),
-            ))
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::UrlEncoded("nopartial".into(), "1".into()))
             .with_status(200)
             .with_body(r#"{ "result": 0, "uploadid": 42 }"#)
             .create();
         let m_write = server
             .mock("PUT", "/upload_write")
+            .match_header("authorization", "Bearer access-token")
+            .match_header("content-length", "12")
             .match_query(Matcher::AllOf(vec![
-                Matcher::UrlEncoded("access_token".into(), "access-token".into()),
                 Matcher::UrlEncoded("uploadid".into(), "42".into()),
                 Matcher::UrlEncoded("uploadoffset".into(), "0".into()),
             ]))
@@ -370,8 +424,8 @@ mod http_tests {
             .create();
         let m_save = server
             .mock("GET", "/upload_save")
+            .match_header("authorization", "Bearer access-token")
             .match_query(Matcher::AllOf(vec![
-                Matcher::UrlEncoded("access_token".into(), "access-token".into()),
                 Matcher::UrlEncoded("uploadid".into(), "42".into()),
                 Matcher::UrlEncoded("name".into(), "testing.txt".into()),
                 Matcher::UrlEncoded("folderid".into(), "0".into()),
@@ -414,4 +468,72 @@ mod http_tests {
         m_write.assert();
         m_save.assert();
     }
+
+    #[tokio::test]
+    async fn should_not_stall_when_limited_by_bandwidth() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m_create = server
+            .mock("GET", "/upload_create")
+            .with_status(200)
+            .with_body(r#"{ "result": 0, "uploadid": 42 }"#)
+            .create();
+        let m_write = server
+            .mock("PUT", "/upload_write")
+            .match_query(Matcher::UrlEncoded("uploadid".into(), "42".into()))
+            .match_header("content-length", "5000")
+            .match_body(Matcher::Any)
+            .with_status(200)
+            .with_body(r#"{ "result": 0 }"#)
+            .expect(3)
+            .create();
+        let m_save = server
+            .mock("GET", "/upload_save")
+            .match_query(Matcher::UrlEncoded("uploadid".into(), "42".into()))
+            .with_status(200)
+            .with_body(
+                r#"{
+    "result": 0,
+    "metadata": {
+        "name": "testing.bin",
+        "created": "Fri, 23 Jul 2021 19:39:14 +0000",
+        "thumb": false,
+        "modified": "Fri, 23 Jul 2021 19:39:14 +0000",
+        "isfolder": false,
+        "fileid": 5251776407,
+        "hash": 10959076480325710862,
+        "category": 0,
+        "id": "f5251776407",
+        "isshared": false,
+        "ismine": true,
+        "size": 15000,
+        "parentfolderid": 0,
+        "contenttype": "application\/octet-stream",
+        "icon": "file"
+    }
+}"#,
+            )
+            .create();
+        // the third part waits half a second for the bandwidth, longer than the idle timeout
+        let api = HttpClientBuilder::default()
+            .credentials(Credentials::access_token("access-token"))
+            .region(Region::new(server.url()))
+            .rate_limit(RateLimit::default().bandwidth(10_000))
+            .transfer_limits(TransferLimits {
+                idle_timeout: Some(Duration::from_millis(100)),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        let cursor = std::io::Cursor::new(vec![0u8; 15_000]);
+        let result = FileUploadCommand::new("testing.bin", 0, cursor)
+            .part_size(5_000)
+            .execute(&api)
+            .await
+            .unwrap();
+        assert_eq!(result.base.name, "testing.bin");
+        m_create.assert();
+        m_write.assert();
+        m_save.assert();
+    }
 }
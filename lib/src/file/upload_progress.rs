@@ -0,0 +1,109 @@
+//! Resources needed to follow the progress of an upload or an import
+
+use crate::entry::File;
+
+/// Command to get the progress of an upload, or of an import of remote files
+///
+/// Executing this command will return an [`UploadProgress`](UploadProgress) on success.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/file/uploadprogress.html)
+#[derive(Debug)]
+pub struct UploadProgressCommand {
+    pub progress_hash: String,
+}
+
+impl UploadProgressCommand {
+    pub fn new(progress_hash: String) -> Self {
+        Self { progress_hash }
+    }
+}
+
+/// The state of an upload
+#[derive(Debug, serde::Deserialize)]
+pub struct UploadProgress {
+    /// Total number of bytes to upload
+    #[serde(default)]
+    pub total: u64,
+    /// Number of bytes already uploaded
+    #[serde(default)]
+    pub uploaded: u64,
+    /// Name of the file being uploaded
+    #[serde(default, rename = "currentfile")]
+    pub current_file: Option<String>,
+    /// Number of bytes already uploaded for the current file
+    #[serde(default, rename = "currentfileuploaded")]
+    pub current_file_uploaded: Option<u64>,
+    #[serde(default)]
+    pub finished: bool,
+    /// The files already created
+    #[serde(default)]
+    pub files: Vec<File>,
+}
+
+#[cfg(feature = "client-http")]
+impl super::import::ImportJob {
+    /// Creates a stream polling the progress of the import every `interval`,
+    /// until the import is finished.
+    pub fn progress(
+        self,
+        client: &crate::http::HttpClient,
+        interval: std::time::Duration,
+    ) -> UploadProgressStream<'_> {
+        let job = UploadJob {
+            progress_hash: self.progress_hash,
+            finished: false,
+        };
+        crate::http::progress::ProgressStream::new(client, interval, job)
+    }
+}
+
+/// An upload, or an import, followed with the [`UploadProgressCommand`](UploadProgressCommand)
+#[cfg(feature = "client-http")]
+#[derive(Debug)]
+pub struct UploadJob {
+    progress_hash: String,
+    finished: bool,
+}
+
+#[cfg(feature = "client-http")]
+impl crate::http::progress::Job for UploadJob {
+    type Command = UploadProgressCommand;
+
+    fn command(&self) -> Self::Command {
+        UploadProgressCommand::new(self.progress_hash.clone())
+    }
+
+    fn update(&mut self, progress: &UploadProgress) {
+        self.finished = progress.finished;
+    }
+
+    fn is_finished(&self) -> bool {
+        self.finished
+    }
+}
+
+/// A stream of [`UploadProgress`](UploadProgress), ending when the upload is finished.
+#[cfg(feature = "client-http")]
+pub type UploadProgressStream<'a> = crate::http::progress::ProgressStream<'a, UploadJob>;
+
+mod command {
+    use super::{UploadProgress, UploadProgressCommand};
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    impl Command for UploadProgressCommand {
+        type Output = UploadProgress;
+
+        fn request(&self) -> Request<'_> {
+            Request::new(
+                "uploadprogress",
+                vec![("progresshash", self.progress_hash.clone())],
+            )
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<UploadProgress>(body)
+        }
+    }
+}
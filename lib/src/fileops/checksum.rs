@@ -0,0 +1,61 @@
+//! Resources needed to compute the checksum of a part of a file descriptor
+
+/// Command to compute the checksums of a range of bytes of an opened file
+///
+/// Executing this command will return a [`RangeChecksum`](RangeChecksum) on success.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/fileops/file_checksum.html)
+#[derive(Debug)]
+pub struct FileRangeChecksumCommand {
+    pub fd: u64,
+    pub offset: u64,
+    pub count: u64,
+}
+
+impl FileRangeChecksumCommand {
+    pub fn new(fd: u64, offset: u64, count: u64) -> Self {
+        Self { fd, offset, count }
+    }
+}
+
+/// The checksums of a range of bytes, depending on the region only some of them are provided
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct RangeChecksum {
+    #[serde(default)]
+    pub md5: Option<String>,
+    #[serde(default)]
+    pub sha1: Option<String>,
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// The number of bytes actually used for the checksums
+    pub size: u64,
+}
+
+mod command {
+    use super::{FileRangeChecksumCommand, RangeChecksum};
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    impl FileRangeChecksumCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            vec![
+                ("fd", self.fd.to_string()),
+                ("offset", self.offset.to_string()),
+                ("count", self.count.to_string()),
+            ]
+        }
+    }
+
+    impl Command for FileRangeChecksumCommand {
+        type Output = RangeChecksum;
+
+        fn request(&self) -> Request<'_> {
+            Request::new("file_checksum", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<RangeChecksum>(body)
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Resources needed to close a file descriptor
+
+/// Command to close an opened file
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/fileops/file_close.html)
+#[derive(Debug)]
+pub struct FileCloseCommand {
+    pub fd: u64,
+}
+
+impl FileCloseCommand {
+    pub fn new(fd: u64) -> Self {
+        Self { fd }
+    }
+}
+
+mod command {
+    use super::FileCloseCommand;
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    impl Command for FileCloseCommand {
+        type Output = ();
+
+        fn request(&self) -> Request<'_> {
+            Request::new("file_close", vec![("fd", self.fd.to_string())])
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<()>(body)
+        }
+    }
+}
@@ -0,0 +1,68 @@
+pub mod checksum;
+pub mod close;
+pub mod open;
+pub mod read;
+#[cfg(feature = "client-http")]
+pub mod remote_file;
+pub mod seek;
+pub mod size;
+pub mod truncate;
+pub mod write;
+
+use std::ops::{BitOr, BitOrAssign};
+
+/// The flags used when opening a file.
+///
+/// Without any flag, the file is opened for reading only.
+///
+/// ```
+/// use pcloud::fileops::OpenFlags;
+///
+/// let flags = OpenFlags::WRITE | OpenFlags::CREATE;
+/// assert!(flags.contains(OpenFlags::CREATE));
+/// assert_eq!(flags.bits(), 0x0042);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct OpenFlags(u16);
+
+impl OpenFlags {
+    /// Opens the file for reading only
+    pub const READ: Self = Self(0);
+    /// Opens the file for writing
+    pub const WRITE: Self = Self(0x0002);
+    /// Creates the file if it doesn't exist
+    pub const CREATE: Self = Self(0x0040);
+    /// With `CREATE`, fails if the file already exists
+    pub const EXCL: Self = Self(0x0080);
+    /// Truncates the file when opening it
+    pub const TRUNC: Self = Self(0x0200);
+    /// Writes always happen at the end of the file
+    pub const APPEND: Self = Self(0x0400);
+
+    /// Returns the value used by the API
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
+
+impl BitOr for OpenFlags {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for OpenFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.insert(rhs);
+    }
+}
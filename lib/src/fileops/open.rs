@@ -0,0 +1,115 @@
+//! Resources needed to open a file descriptor on a remote file
+
+use super::OpenFlags;
+use crate::file::FileIdentifier;
+use crate::folder::FolderIdentifier;
+
+/// The file to open, either an existing one or a new one in a folder
+#[derive(Debug)]
+pub enum OpenTarget {
+    File(FileIdentifier),
+    Create {
+        folder: FolderIdentifier,
+        name: String,
+    },
+}
+
+/// Command to open a file descriptor on a remote file
+///
+/// Executing this command will return an [`OpenedFile`](OpenedFile) on success.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/fileops/file_open.html)
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::fileops::OpenFlags;
+/// use pcloud::fileops::open::FileOpenCommand;
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = FileOpenCommand::create(12.into(), "foo.txt".into()).flags(OpenFlags::EXCL);
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct FileOpenCommand {
+    pub target: OpenTarget,
+    pub flags: OpenFlags,
+}
+
+impl FileOpenCommand {
+    /// Opens an existing file, for reading only by default
+    pub fn new(identifier: FileIdentifier) -> Self {
+        Self {
+            target: OpenTarget::File(identifier),
+            flags: OpenFlags::READ,
+        }
+    }
+
+    /// Opens a file in a folder for writing, creating it if it doesn't exist
+    pub fn create(folder: FolderIdentifier, name: String) -> Self {
+        Self {
+            target: OpenTarget::Create { folder, name },
+            flags: OpenFlags::WRITE | OpenFlags::CREATE,
+        }
+    }
+
+    /// Adds some flags to the ones already set
+    pub fn flags(mut self, value: OpenFlags) -> Self {
+        self.flags |= value;
+        self
+    }
+}
+
+/// Structure returned when opening a file
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+pub struct OpenedFile {
+    /// The file descriptor to use with the other file operations
+    pub fd: u64,
+    #[serde(rename = "fileid")]
+    pub file_id: u64,
+}
+
+mod command {
+    use super::{FileOpenCommand, OpenTarget, OpenedFile};
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    impl FileOpenCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let mut res = vec![("flags", self.flags.bits().to_string())];
+            match self.target {
+                OpenTarget::File(ref identifier) => res.push(identifier.to_http_param()),
+                OpenTarget::Create {
+                    ref folder,
+                    ref name,
+                } => {
+                    res.push(folder.to_http_param());
+                    res.push(("name", name.clone()));
+                }
+            }
+            res
+        }
+    }
+
+    impl Command for FileOpenCommand {
+        type Output = OpenedFile;
+
+        fn request(&self) -> Request<'_> {
+            Request::new("file_open", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<OpenedFile>(body)
+        }
+    }
+}
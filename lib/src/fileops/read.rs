@@ -0,0 +1,133 @@
+//! Resources needed to read from a file descriptor
+
+/// Command to read bytes from an opened file
+///
+/// When an offset is provided, the bytes are read at this position without moving
+/// the current position of the file descriptor, otherwise they are read at the current position.
+///
+/// Executing this command will return the bytes read, the result being empty at the end of the file.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/fileops/file_pread.html)
+#[derive(Debug)]
+pub struct FileReadCommand {
+    pub fd: u64,
+    pub count: u64,
+    pub offset: Option<u64>,
+}
+
+impl FileReadCommand {
+    pub fn new(fd: u64, count: u64) -> Self {
+        Self {
+            fd,
+            count,
+            offset: None,
+        }
+    }
+
+    pub fn offset(mut self, value: u64) -> Self {
+        self.offset = Some(value);
+        self
+    }
+}
+
+#[cfg(feature = "client-http")]
+mod http {
+    use super::FileReadCommand;
+    use crate::error::Error;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+
+    impl FileReadCommand {
+        fn method(&self) -> &'static str {
+            if self.offset.is_some() {
+                "file_pread"
+            } else {
+                "file_read"
+            }
+        }
+
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let mut res = vec![
+                ("fd", self.fd.to_string()),
+                ("count", self.count.to_string()),
+            ];
+            if let Some(offset) = self.offset {
+                res.push(("offset", offset.to_string()));
+            }
+            res
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl HttpCommand for FileReadCommand {
+        type Output = Vec<u8>;
+
+        async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error> {
+            let res = client
+                .get_request_raw(self.method(), &self.to_http_params())
+                .await?;
+            res.bytes().await
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::FileReadCommand;
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn success_with_offset() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/file_pread")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("fd".into(), "1".into()),
+                Matcher::UrlEncoded("count".into(), "5".into()),
+                Matcher::UrlEncoded("offset".into(), "6".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/octet-stream")
+            .with_body("world")
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let result = FileReadCommand::new(1, 5)
+            .offset(6)
+            .execute(&api)
+            .await
+            .unwrap();
+        assert_eq!(result, b"world");
+        m.assert();
+    }
+
+    #[tokio::test]
+    async fn error() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/file_read")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
+                "fd".into(),
+                "1".into(),
+            )]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{ "result": 5004, "error": "Invalid or closed file descriptor." }"#)
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let error = FileReadCommand::new(1, 5).execute(&api).await.unwrap_err();
+        assert!(matches!(error, crate::error::Error::Protocol(5004, _)));
+        m.assert();
+    }
+}
@@ -0,0 +1,434 @@
+//! A handle on a remote file, implementing the tokio io traits
+
+use super::checksum::{FileRangeChecksumCommand, RangeChecksum};
+use super::close::FileCloseCommand;
+use super::open::FileOpenCommand;
+use super::read::FileReadCommand;
+use super::size::FileSizeCommand;
+use super::truncate::FileTruncateCommand;
+use super::write::FileWriteCommand;
+use crate::error::Error;
+use crate::http::HttpClient;
+use crate::prelude::HttpCommand;
+use std::future::Future;
+use std::io::SeekFrom;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+use tokio::io::{AsyncRead, AsyncSeek, AsyncWrite, ReadBuf};
+use tokio::sync::Mutex;
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Send>>;
+
+enum State {
+    Idle,
+    Reading(BoxFuture<Vec<u8>>),
+    Writing(BoxFuture<u64>),
+    /// Waiting for the size of the file to seek from its end
+    Seeking(BoxFuture<u64>, i64),
+}
+
+fn into_io_error(err: Error) -> std::io::Error {
+    std::io::Error::other(err)
+}
+
+fn pending_operation() -> std::io::Error {
+    std::io::Error::other("another operation is pending on the remote file")
+}
+
+fn apply_offset(base: u64, offset: i64) -> std::io::Result<u64> {
+    let result = if offset >= 0 {
+        base.checked_add(offset as u64)
+    } else {
+        base.checked_sub(offset.unsigned_abs())
+    };
+    result.ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "invalid seek to a negative or overflowing position",
+        )
+    })
+}
+
+/// A handle on a remote file, based on the [file operations](https://docs.pcloud.com/methods/fileops/).
+///
+/// It implements [`AsyncRead`], [`AsyncSeek`] and [`AsyncWrite`] so that only the needed parts of the
+/// file are transferred. The position is tracked locally and every read or write is done at an
+/// explicit offset.
+///
+/// pCloud binds the file descriptors to the connection that opened them, so every handle uses its
+/// own copy of the client, keeping a single connection, and sends its requests one at a time.
+/// Only the connect timeout of the original client is kept on that connection.
+/// The file should be closed with [`RemoteFile::close`], dropping the handle leaves the descriptor
+/// open until the connection is closed.
+///
+/// ```no_run
+/// use pcloud::fileops::open::FileOpenCommand;
+/// use pcloud::fileops::remote_file::RemoteFile;
+/// use pcloud::http::HttpClientBuilder;
+/// use tokio::io::{AsyncReadExt, AsyncSeekExt};
+/// use std::io::SeekFrom;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let mut file = RemoteFile::open(&client, FileOpenCommand::new(42.into())).await.unwrap();
+/// file.seek(SeekFrom::End(-8)).await.unwrap();
+/// let mut footer = [0u8; 8];
+/// file.read_exact(&mut footer).await.unwrap();
+/// file.close().await.unwrap();
+/// # })
+/// ```
+pub struct RemoteFile {
+    client: HttpClient,
+    /// Held during every request, so that the connection is never shared
+    lock: Arc<Mutex<()>>,
+    fd: u64,
+    file_id: u64,
+    position: u64,
+    state: State,
+}
+
+impl std::fmt::Debug for RemoteFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteFile")
+            .field("fd", &self.fd)
+            .field("file_id", &self.file_id)
+            .field("position", &self.position)
+            .finish()
+    }
+}
+
+impl RemoteFile {
+    /// Opens the remote file described by the command
+    pub async fn open(client: &HttpClient, command: FileOpenCommand) -> Result<Self, Error> {
+        let client = client.single_connection()?;
+        let opened = command.execute(&client).await?;
+        Ok(Self {
+            client,
+            lock: Arc::default(),
+            fd: opened.fd,
+            file_id: opened.file_id,
+            position: 0,
+            state: State::Idle,
+        })
+    }
+
+    /// Executes the command once the previous requests of the handle are done
+    fn run<C>(&self, command: C) -> BoxFuture<C::Output>
+    where
+        C: HttpCommand + Send + 'static,
+        C::Output: 'static,
+    {
+        let client = self.client.clone();
+        let lock = self.lock.clone();
+        Box::pin(async move {
+            let _guard = lock.lock().await;
+            command.execute(&client).await
+        })
+    }
+
+    /// The file descriptor on the server
+    pub fn fd(&self) -> u64 {
+        self.fd
+    }
+
+    pub fn file_id(&self) -> u64 {
+        self.file_id
+    }
+
+    /// The current position in the file
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Returns the current size of the file
+    pub async fn size(&self) -> Result<u64, Error> {
+        self.run(FileSizeCommand::new(self.fd))
+            .await
+            .map(|item| item.size)
+    }
+
+    /// Computes the checksums of `count` bytes starting at `offset`, without downloading them
+    pub async fn checksum(&self, offset: u64, count: u64) -> Result<RangeChecksum, Error> {
+        self.run(FileRangeChecksumCommand::new(self.fd, offset, count))
+            .await
+    }
+
+    /// Truncates, or extends, the file to the given length
+    pub async fn truncate(&self, length: u64) -> Result<(), Error> {
+        self.run(FileTruncateCommand::new(self.fd, length)).await
+    }
+
+    /// Closes the file descriptor
+    pub async fn close(self) -> Result<(), Error> {
+        self.run(FileCloseCommand::new(self.fd)).await
+    }
+}
+
+impl AsyncRead for RemoteFile {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        if let State::Idle = this.state {
+            if buf.remaining() == 0 {
+                return Poll::Ready(Ok(()));
+            }
+            let cmd = FileReadCommand::new(this.fd, buf.remaining() as u64).offset(this.position);
+            this.state = State::Reading(this.run(cmd));
+        }
+        let fut = match this.state {
+            State::Reading(ref mut fut) => fut,
+            _ => return Poll::Ready(Err(pending_operation())),
+        };
+        let result = ready!(fut.as_mut().poll(cx));
+        this.state = State::Idle;
+        let data = result.map_err(into_io_error)?;
+        let size = data.len().min(buf.remaining());
+        buf.put_slice(&data[..size]);
+        this.position += size as u64;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for RemoteFile {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        if let State::Idle = this.state {
+            if buf.is_empty() {
+                return Poll::Ready(Ok(0));
+            }
+            let cmd = FileWriteCommand::new(this.fd, buf.to_vec()).offset(this.position);
+            this.state = State::Writing(this.run(cmd));
+        }
+        let fut = match this.state {
+            State::Writing(ref mut fut) => fut,
+            _ => return Poll::Ready(Err(pending_operation())),
+        };
+        let result = ready!(fut.as_mut().poll(cx));
+        this.state = State::Idle;
+        let size = result.map_err(into_io_error)?;
+        this.position += size;
+        Poll::Ready(Ok(size as usize))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        if let State::Writing(ref mut fut) = this.state {
+            let result = ready!(fut.as_mut().poll(cx));
+            this.state = State::Idle;
+            this.position += result.map_err(into_io_error)?;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl AsyncSeek for RemoteFile {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        let this = self.get_mut();
+        if !matches!(this.state, State::Idle) {
+            return Err(pending_operation());
+        }
+        match position {
+            SeekFrom::Start(value) => {
+                this.position = value;
+            }
+            SeekFrom::Current(offset) => {
+                this.position = apply_offset(this.position, offset)?;
+            }
+            SeekFrom::End(offset) => {
+                let size = this.run(FileSizeCommand::new(this.fd));
+                let fut = Box::pin(async move { size.await.map(|item| item.size) });
+                this.state = State::Seeking(fut, offset);
+            }
+        }
+        Ok(())
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        let this = self.get_mut();
+        if let State::Seeking(ref mut fut, offset) = this.state {
+            let result = ready!(fut.as_mut().poll(cx));
+            this.state = State::Idle;
+            let size = result.map_err(into_io_error)?;
+            this.position = apply_offset(size, offset)?;
+        }
+        Poll::Ready(Ok(this.position))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RemoteFile;
+    use crate::credentials::Credentials;
+    use crate::fileops::open::FileOpenCommand;
+    use crate::fileops::size::FileSizeCommand;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use mockito::Matcher;
+    use std::io::SeekFrom;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader};
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// Answers the requests of a connection, the descriptor only being known by the connection that opened it
+    async fn handle(stream: TcpStream, id: usize, owner: Arc<AtomicUsize>) {
+        let mut reader = BufReader::new(stream);
+        loop {
+            let mut line = String::new();
+            if reader.read_line(&mut line).await.unwrap_or(0) == 0 {
+                return;
+            }
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).await.unwrap();
+                if header == "\r\n" {
+                    break;
+                }
+            }
+            let path = line.split(' ').nth(1).unwrap();
+            let (content_type, body) = if path.starts_with("/file_open") {
+                owner.store(id, Ordering::SeqCst);
+                (
+                    "application/json",
+                    r#"{ "result": 0, "fd": 1, "fileid": 42 }"#,
+                )
+            } else if path.starts_with("/file_pread") && owner.load(Ordering::SeqCst) == id {
+                ("application/octet-stream", "PAR1")
+            } else if path.starts_with("/file_pread") {
+                (
+                    "application/json",
+                    r#"{ "result": 1007, "error": "Invalid or closed file descriptor." }"#,
+                )
+            } else {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                (
+                    "application/json",
+                    r#"{ "result": 0, "size": 8, "offset": 0 }"#,
+                )
+            };
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-type: {}\r\ncontent-length: {}\r\n\r\n{}",
+                content_type,
+                body.len(),
+                body
+            );
+            reader
+                .get_mut()
+                .write_all(response.as_bytes())
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn read_with_interleaved_request() {
+        crate::tests::init();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let owner = Arc::new(AtomicUsize::new(0));
+            let mut id = 0;
+            while let Ok((stream, _)) = listener.accept().await {
+                id += 1;
+                tokio::spawn(handle(stream, id, owner.clone()));
+            }
+        });
+        let creds = Credentials::AccessToken("access-token".into());
+        let api = HttpClient::new(creds, Region::new(format!("http://{}", address)));
+        let mut file = RemoteFile::open(&api, FileOpenCommand::new(42.into()))
+            .await
+            .unwrap();
+        let other = {
+            let api = api.clone();
+            tokio::spawn(async move { FileSizeCommand::new(2).execute(&api).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let mut footer = [0u8; 4];
+        file.read_exact(&mut footer).await.unwrap();
+        assert_eq!(&footer, b"PAR1");
+        assert_eq!(other.await.unwrap().unwrap().size, 8);
+    }
+
+    #[tokio::test]
+    async fn read_footer_and_patch() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m_open = server
+            .mock("GET", "/file_open")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("fileid".into(), "42".into()),
+                Matcher::UrlEncoded("flags".into(), "2".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{ "result": 0, "fd": 1, "fileid": 42 }"#)
+            .create();
+        let m_size = server
+            .mock("GET", "/file_size")
+            .match_query(Matcher::UrlEncoded("fd".into(), "1".into()))
+            .with_status(200)
+            .with_body(r#"{ "result": 0, "size": 100, "offset": 0 }"#)
+            .create();
+        let m_read = server
+            .mock("GET", "/file_pread")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("fd".into(), "1".into()),
+                Matcher::UrlEncoded("count".into(), "4".into()),
+                Matcher::UrlEncoded("offset".into(), "96".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/octet-stream")
+            .with_body("PAR1")
+            .create();
+        let m_write = server
+            .mock("PUT", "/file_pwrite")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("fd".into(), "1".into()),
+                Matcher::UrlEncoded("offset".into(), "10".into()),
+            ]))
+            .match_body("abc")
+            .with_status(200)
+            .with_body(r#"{ "result": 0, "bytes": 3 }"#)
+            .create();
+        let m_close = server
+            .mock("GET", "/file_close")
+            .match_query(Matcher::UrlEncoded("fd".into(), "1".into()))
+            .with_status(200)
+            .with_body(r#"{ "result": 0 }"#)
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let cmd = FileOpenCommand::new(42.into()).flags(crate::fileops::OpenFlags::WRITE);
+        let mut file = RemoteFile::open(&api, cmd).await.unwrap();
+        assert_eq!(file.seek(SeekFrom::End(-4)).await.unwrap(), 96);
+        let mut footer = [0u8; 4];
+        file.read_exact(&mut footer).await.unwrap();
+        assert_eq!(&footer, b"PAR1");
+        assert_eq!(file.position(), 100);
+        file.seek(SeekFrom::Start(10)).await.unwrap();
+        file.write_all(b"abc").await.unwrap();
+        file.flush().await.unwrap();
+        assert_eq!(file.position(), 13);
+        file.close().await.unwrap();
+        m_open.assert();
+        m_size.assert();
+        m_read.assert();
+        m_write.assert();
+        m_close.assert();
+    }
+}
@@ -0,0 +1,97 @@
+//! Resources needed to move the position of a file descriptor
+
+use std::io::SeekFrom;
+
+/// Command to move the current position of an opened file
+///
+/// Executing this command will return the new position in the file.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/fileops/file_seek.html)
+#[derive(Debug)]
+pub struct FileSeekCommand {
+    pub fd: u64,
+    pub position: SeekFrom,
+}
+
+impl FileSeekCommand {
+    pub fn new(fd: u64, position: SeekFrom) -> Self {
+        Self { fd, position }
+    }
+}
+
+mod command {
+    use super::FileSeekCommand;
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+    use std::io::SeekFrom;
+
+    #[derive(Debug, serde::Deserialize)]
+    struct SeekPayload {
+        offset: u64,
+    }
+
+    impl FileSeekCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let (offset, whence) = match self.position {
+                SeekFrom::Start(value) => (value.to_string(), 0),
+                SeekFrom::Current(value) => (value.to_string(), 1),
+                SeekFrom::End(value) => (value.to_string(), 2),
+            };
+            vec![
+                ("fd", self.fd.to_string()),
+                ("offset", offset),
+                ("whence", whence.to_string()),
+            ]
+        }
+    }
+
+    impl Command for FileSeekCommand {
+        type Output = u64;
+
+        fn request(&self) -> Request<'_> {
+            Request::new("file_seek", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<SeekPayload>(body).map(|item| item.offset)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::FileSeekCommand;
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use mockito::Matcher;
+    use std::io::SeekFrom;
+
+    #[tokio::test]
+    async fn success() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/file_seek")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("fd".into(), "1".into()),
+                Matcher::UrlEncoded("offset".into(), "-8".into()),
+                Matcher::UrlEncoded("whence".into(), "2".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{ "result": 0, "offset": 92 }"#)
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let result = FileSeekCommand::new(1, SeekFrom::End(-8))
+            .execute(&api)
+            .await
+            .unwrap();
+        assert_eq!(result, 92);
+        m.assert();
+    }
+}
@@ -0,0 +1,44 @@
+//! Resources needed to get the size of a file descriptor
+
+/// Command to get the size and the current position of an opened file
+///
+/// Executing this command will return a [`FileSize`](FileSize) on success.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/fileops/file_size.html)
+#[derive(Debug)]
+pub struct FileSizeCommand {
+    pub fd: u64,
+}
+
+impl FileSizeCommand {
+    pub fn new(fd: u64) -> Self {
+        Self { fd }
+    }
+}
+
+/// Structure returned when getting the size of an opened file
+#[derive(Clone, Copy, Debug, serde::Deserialize)]
+pub struct FileSize {
+    pub size: u64,
+    /// The current position in the file
+    pub offset: u64,
+}
+
+mod command {
+    use super::{FileSize, FileSizeCommand};
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    impl Command for FileSizeCommand {
+        type Output = FileSize;
+
+        fn request(&self) -> Request<'_> {
+            Request::new("file_size", vec![("fd", self.fd.to_string())])
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<FileSize>(body)
+        }
+    }
+}
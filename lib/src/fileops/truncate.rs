@@ -0,0 +1,44 @@
+//! Resources needed to truncate a file descriptor
+
+/// Command to truncate, or extend, an opened file to the given length
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/fileops/file_truncate.html)
+#[derive(Debug)]
+pub struct FileTruncateCommand {
+    pub fd: u64,
+    pub length: u64,
+}
+
+impl FileTruncateCommand {
+    pub fn new(fd: u64, length: u64) -> Self {
+        Self { fd, length }
+    }
+}
+
+mod command {
+    use super::FileTruncateCommand;
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    impl FileTruncateCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            vec![
+                ("fd", self.fd.to_string()),
+                ("length", self.length.to_string()),
+            ]
+        }
+    }
+
+    impl Command for FileTruncateCommand {
+        type Output = ();
+
+        fn request(&self) -> Request<'_> {
+            Request::new("file_truncate", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<()>(body)
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! Resources needed to write to a file descriptor
+
+/// Command to write bytes to an opened file
+///
+/// When an offset is provided, the bytes are written at this position without moving
+/// the current position of the file descriptor, otherwise they are written at the current position.
+///
+/// Executing this command will return the number of bytes written.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/fileops/file_pwrite.html)
+#[derive(Debug)]
+pub struct FileWriteCommand {
+    pub fd: u64,
+    pub data: Vec<u8>,
+    pub offset: Option<u64>,
+}
+
+impl FileWriteCommand {
+    pub fn new(fd: u64, data: Vec<u8>) -> Self {
+        Self {
+            fd,
+            data,
+            offset: None,
+        }
+    }
+
+    pub fn offset(mut self, value: u64) -> Self {
+        self.offset = Some(value);
+        self
+    }
+}
+
+mod command {
+    use super::FileWriteCommand;
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Body, Request};
+
+    #[derive(Debug, serde::Deserialize)]
+    struct WritePayload {
+        bytes: u64,
+    }
+
+    impl FileWriteCommand {
+        fn method(&self) -> &'static str {
+            if self.offset.is_some() {
+                "file_pwrite"
+            } else {
+                "file_write"
+            }
+        }
+
+        fn to_http_params(&self) -> Vec<(&'static str, String)> {
+            let mut res = vec![("fd", self.fd.to_string())];
+            if let Some(offset) = self.offset {
+                res.push(("offset", offset.to_string()));
+            }
+            res
+        }
+    }
+
+    impl Command for FileWriteCommand {
+        type Output = u64;
+
+        fn request(&self) -> Request<'_> {
+            Request::new(self.method(), self.to_http_params()).body(Body::Data(&self.data))
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<WritePayload>(body).map(|item| item.bytes)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::FileWriteCommand;
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn success_with_offset() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("PUT", "/file_pwrite")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("fd".into(), "1".into()),
+                Matcher::UrlEncoded("offset".into(), "6".into()),
+            ]))
+            .match_body("world")
+            .with_status(200)
+            .with_body(r#"{ "result": 0, "bytes": 5 }"#)
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let result = FileWriteCommand::new(1, b"world".to_vec())
+            .offset(6)
+            .execute(&api)
+            .await
+            .unwrap();
+        assert_eq!(result, 5);
+        m.assert();
+    }
+}
@@ -62,7 +62,6 @@ impl FolderCreateCommand {
         self
     }
 
-    #[cfg(feature = "client-http")]
     fn method(&self) -> &str {
         if self.ignore_exists {
             "createfolderifnotexists"
@@ -72,15 +71,13 @@ impl FolderCreateCommand {
     }
 }
 
-#[cfg(feature = "client-http")]
-mod http {
+mod command {
     use super::FolderCreateCommand;
     use crate::entry::Folder;
     use crate::error::Error;
     use crate::folder::FolderResponse;
-    use crate::http::HttpClient;
-    use crate::prelude::HttpCommand;
-    use crate::request::Response;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
 
     impl FolderCreateCommand {
         pub fn to_http_params(&self) -> Vec<(&str, String)> {
@@ -91,15 +88,15 @@ mod http {
         }
     }
 
-    #[async_trait::async_trait]
-    impl HttpCommand for FolderCreateCommand {
+    impl Command for FolderCreateCommand {
         type Output = Folder;
 
-        async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error> {
-            let result: Response<FolderResponse> = client
-                .get_request(self.method(), &self.to_http_params())
-                .await?;
-            result.payload().map(|item| item.metadata)
+        fn request(&self) -> Request<'_> {
+            Request::new(self.method(), self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<FolderResponse>(body).map(|item| item.metadata)
         }
     }
 }
@@ -119,8 +116,8 @@ mod http_tests {
         let mut server = mockito::Server::new_async().await;
         let m = server
             .mock("GET", "/createfolder")
+            .match_header("authorization", "Bearer access-token")
             .match_query(Matcher::AllOf(vec![
-                Matcher::UrlEncoded("access_token".into(), "access-token".into()),
                 Matcher::UrlEncoded("folderid".into(), "0".into()),
                 Matcher::UrlEncoded("name".into(), "testing".into()),
             ]))
@@ -162,8 +159,8 @@ mod http_tests {
         let mut server = mockito::Server::new_async().await;
         let m = server
             .mock("GET", "/createfolder")
+            .match_header("authorization", "Bearer access-token")
             .match_query(Matcher::AllOf(vec![
-                Matcher::UrlEncoded("access_token".into(), "access-token".into()),
                 Matcher::UrlEncoded("folderid".into(), "0".into()),
                 Matcher::UrlEncoded("name".into(), "testing".into()),
             ]))
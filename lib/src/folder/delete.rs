@@ -71,43 +71,33 @@ impl FolderDeleteCommand {
     }
 }
 
-#[cfg(feature = "client-http")]
-mod http {
+mod command {
     use super::{FolderDeleteCommand, RecursivePayload};
     use crate::error::Error;
     use crate::folder::FolderResponse;
-    use crate::http::HttpClient;
-    use crate::prelude::HttpCommand;
-    use crate::request::Response;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
 
-    impl FolderDeleteCommand {
-        async fn http_normal(&self, client: &HttpClient) -> Result<RecursivePayload, Error> {
-            let result: Response<FolderResponse> = client
-                .get_request("deletefolder", &self.identifier.to_http_params())
-                .await?;
-            result.payload().map(|_| RecursivePayload {
-                deleted_files: 0,
-                deleted_folders: 1,
-            })
-        }
+    impl Command for FolderDeleteCommand {
+        type Output = RecursivePayload;
 
-        async fn http_recursive(&self, client: &HttpClient) -> Result<RecursivePayload, Error> {
-            let result: Response<RecursivePayload> = client
-                .get_request("deletefolderrecursive", &self.identifier.to_http_params())
-                .await?;
-            result.payload()
+        fn request(&self) -> Request<'_> {
+            let method = if self.recursive {
+                "deletefolderrecursive"
+            } else {
+                "deletefolder"
+            };
+            Request::new(method, self.identifier.to_http_params())
         }
-    }
-
-    #[async_trait::async_trait]
-    impl HttpCommand for FolderDeleteCommand {
-        type Output = RecursivePayload;
 
-        async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error> {
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
             if self.recursive {
-                self.http_recursive(client).await
+                request::parse::<RecursivePayload>(body)
             } else {
-                self.http_normal(client).await
+                request::parse::<FolderResponse>(body).map(|_| RecursivePayload {
+                    deleted_files: 0,
+                    deleted_folders: 1,
+                })
             }
         }
     }
@@ -128,10 +118,11 @@ mod http_tests {
         let mut server = mockito::Server::new_async().await;
         let m = server
             .mock("GET", "/deletefolder")
-            .match_query(Matcher::AllOf(vec![
-                Matcher::UrlEncoded("access_token".into(), "access-token".into()),
-                Matcher::UrlEncoded("folderid".into(), "42".into()),
-            ]))
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
+                "folderid".into(),
+                "42".into(),
+            )]))
             .with_status(200)
             .with_body(
                 r#"{
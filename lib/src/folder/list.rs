@@ -88,15 +88,13 @@ impl FolderListCommand {
     }
 }
 
-#[cfg(feature = "client-http")]
-mod http {
+mod command {
     use super::FolderListCommand;
     use crate::entry::Folder;
     use crate::error::Error;
     use crate::folder::FolderResponse;
-    use crate::http::HttpClient;
-    use crate::prelude::HttpCommand;
-    use crate::request::Response;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
 
     impl FolderListCommand {
         fn to_http_params(&self) -> Vec<(&str, String)> {
@@ -117,15 +115,47 @@ mod http {
         }
     }
 
-    #[async_trait::async_trait]
-    impl HttpCommand for FolderListCommand {
+    impl Command for FolderListCommand {
         type Output = Folder;
 
-        async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error> {
-            let result: Response<FolderResponse> = client
-                .get_request("listfolder", &self.to_http_params())
+        fn request(&self) -> Request<'_> {
+            Request::new("listfolder", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<FolderResponse>(body).map(|item| item.metadata)
+        }
+    }
+}
+
+#[cfg(feature = "client-http")]
+mod http {
+    use super::FolderListCommand;
+    use crate::entry::{Entry, Folder};
+    use crate::error::Error;
+    use crate::folder::listing::parse_listing;
+    use crate::http::HttpClient;
+    use crate::prelude::Command;
+
+    impl FolderListCommand {
+        /// Executes the command, handing over the entries to the visitor while the response is parsed.
+        ///
+        /// The entries are given with their depth, without their content, and a folder is given
+        /// after its content. The listed folder is returned without its content.
+        /// See [`parse_listing`](crate::folder::listing::parse_listing).
+        pub async fn for_each_entry<F>(
+            self,
+            client: &HttpClient,
+            visitor: F,
+        ) -> Result<Folder, Error>
+        where
+            F: FnMut(usize, Entry),
+        {
+            let request = self.request();
+            let data = client
+                .get_request_bytes(request.method, &request.params)
                 .await?;
-            result.payload().map(|item| item.metadata)
+            parse_listing(&data, visitor)
         }
     }
 }
@@ -145,10 +175,11 @@ mod http_tests {
         let mut server = mockito::Server::new_async().await;
         let m = server
             .mock("GET", "/listfolder")
-            .match_query(Matcher::AllOf(vec![
-                Matcher::UrlEncoded("access_token".into(), "access-token".into()),
-                Matcher::UrlEncoded("folderid".into(), "0".into()),
-            ]))
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
+                "folderid".into(),
+                "0".into(),
+            )]))
             .with_status(200)
             .with_body(
                 r#"{
@@ -187,10 +218,11 @@ mod http_tests {
         let mut server = mockito::Server::new_async().await;
         let m = server
             .mock("GET", "/listfolder")
-            .match_query(Matcher::AllOf(vec![
-                Matcher::UrlEncoded("access_token".into(), "access-token".into()),
-                Matcher::UrlEncoded("folderid".into(), "0".into()),
-            ]))
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
+                "folderid".into(),
+                "0".into(),
+            )]))
             .with_status(200)
             .with_body(r#"{ "result": 1020, "error": "something went wrong" }"#)
             .create();
@@ -204,4 +236,35 @@ mod http_tests {
         assert!(matches!(error, crate::error::Error::Protocol(_, _)));
         m.assert();
     }
+
+    #[tokio::test]
+    async fn invalid_response() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/listfolder")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
+                "folderid".into(),
+                "0".into(),
+            )]))
+            .with_status(502)
+            .with_body("<html>Bad Gateway</html>")
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let error = FolderListCommand::new(0.into())
+            .execute(&api)
+            .await
+            .unwrap_err();
+        assert!(matches!(error.inner(), crate::error::Error::SerdeJson(_)));
+        let context = error.context().unwrap();
+        assert_eq!(context.method, "listfolder");
+        assert_eq!(context.params, vec![("folderid".into(), "0".into())]);
+        assert_eq!(context.status, Some(502));
+        assert_eq!(context.body.as_deref(), Some("<html>Bad Gateway</html>"));
+        assert!(!error.to_string().contains("access-token"));
+        m.assert();
+    }
 }
@@ -0,0 +1,368 @@
+//! Incremental parsing of the `listfolder` responses
+//!
+//! A recursive listing of a large account can contain hundreds of thousands of entries.
+//! Instead of building the whole [`Folder`] tree, the entries are handed over one by one
+//! while the response is being parsed.
+
+use crate::entry::{Entry, Folder};
+use crate::error::Error;
+use serde::de::value::{BorrowedStrDeserializer, MapAccessDeserializer};
+use serde::de::{DeserializeSeed, IgnoredAny, IntoDeserializer, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use std::fmt;
+
+/// Parses a `listfolder` response and calls the visitor with each entry and its depth.
+///
+/// The entries are given without their content and a folder is given after its content.
+/// The root folder is returned, without its content, and is not given to the visitor.
+///
+/// ```
+/// use pcloud::folder::listing::parse_listing;
+///
+/// let data = br#"{
+///   "result": 0,
+///   "metadata": {
+///     "name": "/", "folderid": 0, "isfolder": true, "id": "d0", "icon": "folder",
+///     "created": "Sat, 24 Jul 2021 07:38:41 +0000",
+///     "modified": "Sat, 24 Jul 2021 07:38:41 +0000",
+///     "thumb": false, "isshared": false, "ismine": true,
+///     "contents": [{
+///       "name": "a.txt", "fileid": 42, "isfolder": false, "id": "f42", "icon": "document",
+///       "created": "Sat, 24 Jul 2021 07:38:41 +0000",
+///       "modified": "Sat, 24 Jul 2021 07:38:41 +0000",
+///       "thumb": false, "isshared": false, "ismine": true
+///     }]
+///   }
+/// }"#;
+/// let mut names = Vec::new();
+/// let root = parse_listing(data, |depth, entry| {
+///     names.push((depth, entry.base().name.clone()));
+/// })
+/// .unwrap();
+/// assert_eq!(root.folder_id, 0);
+/// assert_eq!(names, vec![(1, "a.txt".to_string())]);
+/// ```
+pub fn parse_listing<F>(data: &[u8], mut visitor: F) -> Result<Folder, Error>
+where
+    F: FnMut(usize, Entry),
+{
+    let mut deserializer = serde_json::Deserializer::from_slice(data);
+    let result = ResponseSeed {
+        visitor: &mut visitor,
+    }
+    .deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    result
+}
+
+struct ResponseSeed<'a, F> {
+    visitor: &'a mut F,
+}
+
+impl<'de, 'a, F> DeserializeSeed<'de> for ResponseSeed<'a, F>
+where
+    F: FnMut(usize, Entry),
+{
+    type Value = Result<Folder, Error>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'a, F> Visitor<'de> for ResponseSeed<'a, F>
+where
+    F: FnMut(usize, Entry),
+{
+    type Value = Result<Folder, Error>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a listfolder response")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut result: Option<u16> = None;
+        let mut error: Option<String> = None;
+        let mut root: Option<Entry> = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "result" => result = Some(map.next_value()?),
+                "error" => error = Some(map.next_value()?),
+                "metadata" => {
+                    root = Some(map.next_value_seed(EntrySeed {
+                        visitor: &mut *self.visitor,
+                        depth: 0,
+                    })?)
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        let result = result.ok_or_else(|| serde::de::Error::missing_field("result"))?;
+        if result != 0 {
+            return Ok(Err(Error::Protocol(result, error.unwrap_or_default())));
+        }
+        match root {
+            Some(Entry::Folder(folder)) => Ok(Ok(folder)),
+            Some(Entry::File(_)) => Err(serde::de::Error::custom("expected a folder")),
+            None => Err(serde::de::Error::missing_field("metadata")),
+        }
+    }
+}
+
+/// Reads an entry, handing over its content to the visitor instead of keeping it
+struct EntrySeed<'a, F> {
+    visitor: &'a mut F,
+    depth: usize,
+}
+
+impl<'de, 'a, F> DeserializeSeed<'de> for EntrySeed<'a, F>
+where
+    F: FnMut(usize, Entry),
+{
+    type Value = Entry;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, 'a, F> Visitor<'de> for EntrySeed<'a, F>
+where
+    F: FnMut(usize, Entry),
+{
+    type Value = Entry;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a file or a folder")
+    }
+
+    fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        Entry::deserialize(MapAccessDeserializer::new(WithoutContents {
+            inner: map,
+            visitor: self.visitor,
+            depth: self.depth,
+        }))
+    }
+}
+
+/// Gives the fields of an entry, except its content that is handed over to the visitor
+struct WithoutContents<'a, A, F> {
+    inner: A,
+    visitor: &'a mut F,
+    depth: usize,
+}
+
+impl<'de, 'a, A, F> MapAccess<'de> for WithoutContents<'a, A, F>
+where
+    A: MapAccess<'de>,
+    F: FnMut(usize, Entry),
+{
+    type Error = A::Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        while let Some(key) = self.inner.next_key_seed(KeySeed)? {
+            match key {
+                Key::Contents => {
+                    self.inner.next_value_seed(ContentsSeed {
+                        visitor: &mut *self.visitor,
+                        depth: self.depth + 1,
+                    })?;
+                }
+                Key::Borrowed(value) => {
+                    return seed
+                        .deserialize(BorrowedStrDeserializer::new(value))
+                        .map(Some)
+                }
+                Key::Owned(value) => return seed.deserialize(value.into_deserializer()).map(Some),
+            }
+        }
+        Ok(None)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        self.inner.next_value_seed(seed)
+    }
+}
+
+/// A key of an entry, borrowed from the input when possible to avoid an allocation per field
+enum Key<'de> {
+    Contents,
+    Borrowed(&'de str),
+    Owned(String),
+}
+
+struct KeySeed;
+
+impl<'de> DeserializeSeed<'de> for KeySeed {
+    type Value = Key<'de>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de> Visitor<'de> for KeySeed {
+    type Value = Key<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a field name")
+    }
+
+    fn visit_borrowed_str<E>(self, value: &'de str) -> Result<Self::Value, E> {
+        Ok(match value {
+            "contents" => Key::Contents,
+            other => Key::Borrowed(other),
+        })
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(match value {
+            "contents" => Key::Contents,
+            other => Key::Owned(other.to_string()),
+        })
+    }
+}
+
+struct ContentsSeed<'a, F> {
+    visitor: &'a mut F,
+    depth: usize,
+}
+
+impl<'de, 'a, F> DeserializeSeed<'de> for ContentsSeed<'a, F>
+where
+    F: FnMut(usize, Entry),
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, 'a, F> Visitor<'de> for ContentsSeed<'a, F>
+where
+    F: FnMut(usize, Entry),
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of entries")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        while let Some(entry) = seq.next_element_seed(EntrySeed {
+            visitor: &mut *self.visitor,
+            depth: self.depth,
+        })? {
+            (self.visitor)(self.depth, entry);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_listing;
+
+    fn entry(kind: &str, id: u64, name: &str, contents: Option<&str>) -> String {
+        let contents = contents
+            .map(|value| format!(r#", "contents": [{value}]"#))
+            .unwrap_or_default();
+        let is_folder = kind == "folderid";
+        format!(
+            r#"{{
+    "name": "{name}",
+    "created": "Sat, 24 Jul 2021 07:38:41 +0000",
+    "modified": "Sat, 24 Jul 2021 07:38:41 +0000",
+    "thumb": false,
+    "isfolder": {is_folder},
+    "{kind}": {id},
+    "id": "x{id}",
+    "isshared": false,
+    "ismine": true,
+    "icon": "folder"{contents}
+}}"#
+        )
+    }
+
+    #[test]
+    fn should_visit_nested_entries() {
+        let photos = entry(
+            "folderid",
+            1,
+            "photos",
+            Some(&format!(
+                "{},{}",
+                entry("fileid", 10, "a.jpg", None),
+                entry("fileid", 11, "b.jpg", None)
+            )),
+        );
+        let root = entry(
+            "folderid",
+            0,
+            "/",
+            Some(&format!(
+                "{},{}",
+                photos,
+                entry("fileid", 12, "c.txt", None)
+            )),
+        );
+        let data = format!(r#"{{ "result": 0, "metadata": {root} }}"#);
+        let mut visited = Vec::new();
+        let root = parse_listing(data.as_bytes(), |depth, entry| {
+            assert!(entry
+                .as_folder()
+                .and_then(|f| f.contents.as_ref())
+                .is_none());
+            visited.push((depth, entry.base().name.clone()));
+        })
+        .unwrap();
+        assert_eq!(root.folder_id, 0);
+        assert!(root.contents.is_none());
+        assert_eq!(
+            visited,
+            vec![
+                (2, "a.jpg".to_string()),
+                (2, "b.jpg".to_string()),
+                (1, "photos".to_string()),
+                (1, "c.txt".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_return_protocol_error() {
+        let data = br#"{ "result": 2005, "error": "Directory does not exist." }"#;
+        let err = parse_listing(data, |_, _| {}).unwrap_err();
+        assert!(matches!(err, crate::error::Error::Protocol(2005, _)));
+    }
+}
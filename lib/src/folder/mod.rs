@@ -1,17 +1,19 @@
 pub mod create;
 pub mod delete;
 pub mod list;
+pub mod listing;
 pub mod rename;
+#[cfg(feature = "client-http")]
+pub mod walker;
 
 pub const ROOT: u64 = 0;
 
-#[cfg(feature = "client-http")]
 #[derive(Debug, serde::Deserialize)]
 pub(crate) struct FolderResponse {
     pub metadata: crate::entry::Folder,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum FolderIdentifier {
     Path(String),
     FolderId(u64),
@@ -41,7 +43,6 @@ impl From<u64> for FolderIdentifier {
     }
 }
 
-#[cfg(feature = "client-http")]
 impl FolderIdentifier {
     pub fn to_named_http_param(
         &self,
@@ -106,43 +106,47 @@ impl FolderMoveCommand {
     }
 }
 
-#[cfg(feature = "client-http")]
-mod http {
+mod command {
     use super::{FolderMoveCommand, FolderRenameCommand};
     use crate::entry::Folder;
     use crate::error::Error;
     use crate::folder::FolderResponse;
-    use crate::http::HttpClient;
-    use crate::prelude::HttpCommand;
-    use crate::request::Response;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
 
-    #[async_trait::async_trait]
-    impl HttpCommand for FolderRenameCommand {
+    impl Command for FolderRenameCommand {
         type Output = Folder;
 
-        async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error> {
-            let params = vec![
-                ("folderid", self.identifier.to_string()),
-                ("toname", self.name),
-            ];
-            let result: Response<FolderResponse> =
-                client.get_request("renamefolder", &params).await?;
-            result.payload().map(|item| item.metadata)
+        fn request(&self) -> Request<'_> {
+            Request::new(
+                "renamefolder",
+                vec![
+                    ("folderid", self.identifier.to_string()),
+                    ("toname", self.name.clone()),
+                ],
+            )
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<FolderResponse>(body).map(|item| item.metadata)
         }
     }
 
-    #[async_trait::async_trait]
-    impl HttpCommand for FolderMoveCommand {
+    impl Command for FolderMoveCommand {
         type Output = Folder;
 
-        async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error> {
-            let params = vec![
-                ("folderid", self.folder.to_string()),
-                ("tofolderid", self.to_folder.to_string()),
-            ];
-            let result: Response<FolderResponse> =
-                client.get_request("renamefolder", &params).await?;
-            result.payload().map(|item| item.metadata)
+        fn request(&self) -> Request<'_> {
+            Request::new(
+                "renamefolder",
+                vec![
+                    ("folderid", self.folder.to_string()),
+                    ("tofolderid", self.to_folder.to_string()),
+                ],
+            )
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<FolderResponse>(body).map(|item| item.metadata)
         }
     }
 }
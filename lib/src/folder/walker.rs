@@ -0,0 +1,388 @@
+//! Resources needed to walk lazily through a folder tree
+
+use super::list::FolderListCommand;
+use super::FolderIdentifier;
+use crate::entry::{Entry, Folder};
+use crate::error::Error;
+use crate::http::HttpClient;
+use crate::prelude::HttpCommand;
+use std::path::{Path, PathBuf};
+
+/// How the content of a folder is fetched
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ListStrategy {
+    /// One request per folder, only the needed folders are fetched
+    #[default]
+    PerFolder,
+    /// One request for the whole subtree, fewer requests but everything is held in memory
+    Recursive,
+}
+
+type PruneFn = Box<dyn Fn(&Path, &Folder) -> bool + Send + Sync>;
+type StrategyFn = Box<dyn Fn(&Path, usize) -> ListStrategy + Send + Sync>;
+
+struct PendingFolder {
+    identifier: FolderIdentifier,
+    path: PathBuf,
+    depth: usize,
+}
+
+#[allow(clippy::large_enum_variant)]
+enum Node {
+    /// An entry ready to be yielded
+    Entry {
+        path: PathBuf,
+        depth: usize,
+        entry: Entry,
+    },
+    /// A folder whose content should be fetched when reached
+    Fetch(PendingFolder),
+}
+
+/// A lazy walker yielding the entries of a folder tree with their path relative to the root.
+///
+/// The paths start with `/`, the root itself is not yielded.
+/// The tree is walked depth first, a folder being yielded before its content,
+/// and a folder is only fetched when its content is reached.
+///
+/// ```no_run
+/// use pcloud::folder::walker::{ListStrategy, Walker};
+/// use pcloud::http::HttpClientBuilder;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let mut walker = Walker::new(&client, 0.into())
+///     .max_depth(3)
+///     .exclude(glob::Pattern::new("/tmp/**").unwrap())
+///     .strategy(|path, _depth| {
+///         if path.starts_with("/photos") {
+///             ListStrategy::Recursive
+///         } else {
+///             ListStrategy::PerFolder
+///         }
+///     });
+/// while let Some(item) = walker.next().await {
+///     let (path, entry) = item.unwrap();
+///     println!("{:?} {}", path, entry.base().name);
+/// }
+/// # })
+/// ```
+pub struct Walker<'a> {
+    client: &'a HttpClient,
+    max_depth: Option<usize>,
+    include: Vec<glob::Pattern>,
+    exclude: Vec<glob::Pattern>,
+    prune: Option<PruneFn>,
+    strategy: Option<StrategyFn>,
+    retries: usize,
+    stack: Vec<Node>,
+}
+
+impl<'a> Walker<'a> {
+    pub fn new(client: &'a HttpClient, root: FolderIdentifier) -> Self {
+        Self {
+            client,
+            max_depth: None,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            prune: None,
+            strategy: None,
+            retries: 0,
+            stack: vec![Node::Fetch(PendingFolder {
+                identifier: root,
+                path: PathBuf::from("/"),
+                depth: 0,
+            })],
+        }
+    }
+
+    /// Maximum depth of the yielded entries, the content of the root being at depth 1.
+    pub fn max_depth(mut self, value: usize) -> Self {
+        self.max_depth = Some(value);
+        self
+    }
+
+    /// Only yields the files matching one of the include patterns, folders are always yielded.
+    pub fn include(mut self, value: glob::Pattern) -> Self {
+        self.include.push(value);
+        self
+    }
+
+    /// Skips the files and folders matching the pattern, with their content.
+    pub fn exclude(mut self, value: glob::Pattern) -> Self {
+        self.exclude.push(value);
+        self
+    }
+
+    /// Skips the content of the folders for which the function returns `true`.
+    /// The folders themselves are still yielded.
+    pub fn prune<F>(mut self, func: F) -> Self
+    where
+        F: Fn(&Path, &Folder) -> bool + Send + Sync + 'static,
+    {
+        self.prune = Some(Box::new(func));
+        self
+    }
+
+    /// Chooses how to fetch a folder, given its path and depth.
+    pub fn strategy<F>(mut self, func: F) -> Self
+    where
+        F: Fn(&Path, usize) -> ListStrategy + Send + Sync + 'static,
+    {
+        self.strategy = Some(Box::new(func));
+        self
+    }
+
+    /// Number of retries when fetching a folder fails.
+    pub fn retries(mut self, value: usize) -> Self {
+        self.retries = value;
+        self
+    }
+
+    fn is_excluded(&self, path: &Path) -> bool {
+        self.exclude
+            .iter()
+            .any(|pattern| pattern.matches_path(path))
+    }
+
+    fn is_included(&self, path: &Path) -> bool {
+        self.include.is_empty()
+            || self
+                .include
+                .iter()
+                .any(|pattern| pattern.matches_path(path))
+    }
+
+    fn should_descend(&self, path: &Path, depth: usize, folder: &Folder) -> bool {
+        if self.max_depth.map(|max| depth >= max).unwrap_or(false) {
+            return false;
+        }
+        !self
+            .prune
+            .as_ref()
+            .map(|func| func(path, folder))
+            .unwrap_or(false)
+    }
+
+    fn push_children(&mut self, path: &Path, depth: usize, children: Vec<Entry>) {
+        for entry in children.into_iter().rev() {
+            let child_path = path.join(entry.base().name.as_str());
+            if self.is_excluded(&child_path) {
+                tracing::debug!("{child_path:?} is matching an exclusion pattern, ignoring");
+                continue;
+            }
+            self.stack.push(Node::Entry {
+                path: child_path,
+                depth: depth + 1,
+                entry,
+            });
+        }
+    }
+
+    async fn fetch(&self, pending: &PendingFolder) -> Result<Folder, Error> {
+        let strategy = self
+            .strategy
+            .as_ref()
+            .map(|func| func(&pending.path, pending.depth))
+            .unwrap_or_default();
+        let mut retries = self.retries;
+        loop {
+            let result = FolderListCommand::new(pending.identifier.clone())
+                .recursive(strategy == ListStrategy::Recursive)
+                .execute(self.client)
+                .await;
+            match result {
+                Err(err) if retries > 0 => {
+                    tracing::warn!("unable to list folder {:?}: {:?}", pending.path, err);
+                    retries -= 1;
+                }
+                other => return other,
+            }
+        }
+    }
+
+    /// Returns the next entry with its path, fetching the next folder when needed.
+    ///
+    /// When a folder cannot be fetched, the error is returned and its content is skipped.
+    pub async fn next(&mut self) -> Option<Result<(PathBuf, Entry), Error>> {
+        loop {
+            match self.stack.pop()? {
+                Node::Entry {
+                    path,
+                    entry: Entry::File(file),
+                    ..
+                } => {
+                    if self.is_included(&path) {
+                        return Some(Ok((path, Entry::File(file))));
+                    }
+                }
+                Node::Entry {
+                    path,
+                    depth,
+                    entry: Entry::Folder(mut folder),
+                } => {
+                    let contents = folder.contents.take();
+                    if self.should_descend(&path, depth, &folder) {
+                        match contents {
+                            Some(children) => self.push_children(&path, depth, children),
+                            None => self.stack.push(Node::Fetch(PendingFolder {
+                                identifier: FolderIdentifier::FolderId(folder.folder_id),
+                                path: path.clone(),
+                                depth,
+                            })),
+                        }
+                    }
+                    return Some(Ok((path, Entry::Folder(folder))));
+                }
+                Node::Fetch(pending) => match self.fetch(&pending).await {
+                    Ok(mut folder) => {
+                        let children = folder.contents.take().unwrap_or_default();
+                        self.push_children(&pending.path, pending.depth, children);
+                    }
+                    Err(err) => return Some(Err(err)),
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ListStrategy, Walker};
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::region::Region;
+    use mockito::Matcher;
+    use std::path::PathBuf;
+
+    fn folder(id: u64, name: &str, contents: Option<&str>) -> String {
+        let contents = contents
+            .map(|value| format!(r#", "contents": [{value}]"#))
+            .unwrap_or_default();
+        format!(
+            r#"{{
+    "name": "{name}",
+    "created": "Sat, 24 Jul 2021 07:38:41 +0000",
+    "modified": "Sat, 24 Jul 2021 07:38:41 +0000",
+    "thumb": false,
+    "isfolder": true,
+    "folderid": {id},
+    "id": "d{id}",
+    "isshared": false,
+    "ismine": true,
+    "icon": "folder"{contents}
+}}"#
+        )
+    }
+
+    fn file(id: u64, name: &str) -> String {
+        format!(
+            r#"{{
+    "name": "{name}",
+    "created": "Sat, 24 Jul 2021 07:38:41 +0000",
+    "modified": "Sat, 24 Jul 2021 07:38:41 +0000",
+    "thumb": false,
+    "isfolder": false,
+    "fileid": {id},
+    "id": "f{id}",
+    "isshared": false,
+    "ismine": true,
+    "icon": "document"
+}}"#
+        )
+    }
+
+    async fn collect(mut walker: Walker<'_>) -> Vec<PathBuf> {
+        let mut result = Vec::new();
+        while let Some(item) = walker.next().await {
+            result.push(item.unwrap().0);
+        }
+        result
+    }
+
+    #[tokio::test]
+    async fn per_folder_with_filters() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let root = folder(
+            0,
+            "/",
+            Some(&format!(
+                "{},{},{},{}",
+                folder(1, "first", None),
+                folder(2, "tmp", None),
+                file(10, "foo.txt"),
+                file(11, "foo.bin")
+            )),
+        );
+        let first = folder(1, "first", Some(&file(12, "bar.txt")));
+        let m_root = server
+            .mock("GET", "/listfolder")
+            .match_query(Matcher::UrlEncoded("folderid".into(), "0".into()))
+            .with_status(200)
+            .with_body(format!(r#"{{ "result": 0, "metadata": {root} }}"#))
+            .create();
+        let m_first = server
+            .mock("GET", "/listfolder")
+            .match_query(Matcher::UrlEncoded("folderid".into(), "1".into()))
+            .with_status(200)
+            .with_body(format!(r#"{{ "result": 0, "metadata": {first} }}"#))
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let walker = Walker::new(&api, 0.into())
+            .include(glob::Pattern::new("*.txt").unwrap())
+            .exclude(glob::Pattern::new("/tmp").unwrap());
+        let paths = collect(walker).await;
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("/first"),
+                PathBuf::from("/first/bar.txt"),
+                PathBuf::from("/foo.txt"),
+            ]
+        );
+        m_root.assert();
+        m_first.assert();
+    }
+
+    #[tokio::test]
+    async fn recursive_with_depth() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let second = folder(2, "second", Some(&file(12, "bar.txt")));
+        let first = folder(
+            1,
+            "first",
+            Some(&format!("{},{}", second, file(11, "foo.txt"))),
+        );
+        let root = folder(0, "/", Some(&first));
+        let m = server
+            .mock("GET", "/listfolder")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("folderid".into(), "0".into()),
+                Matcher::UrlEncoded("recursive".into(), "1".into()),
+            ]))
+            .with_status(200)
+            .with_body(format!(r#"{{ "result": 0, "metadata": {root} }}"#))
+            .expect(1)
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let walker = Walker::new(&api, 0.into())
+            .max_depth(2)
+            .strategy(|_, _| ListStrategy::Recursive);
+        let paths = collect(walker).await;
+        assert_eq!(
+            paths,
+            vec![
+                PathBuf::from("/first"),
+                PathBuf::from("/first/second"),
+                PathBuf::from("/first/foo.txt"),
+            ]
+        );
+        m.assert();
+    }
+}
@@ -0,0 +1,204 @@
+//! A continuous stream of the events happening on the account
+
+use super::diff::{DiffCommand, DiffEntry};
+use crate::error::Error;
+use crate::http::HttpClient;
+use crate::prelude::HttpCommand;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+/// Where the [`ChangeStream`](ChangeStream) persists the id of the last event it handled
+pub trait CursorStore: Send {
+    fn load(&mut self) -> std::io::Result<Option<u64>>;
+    fn save(&mut self, diff_id: u64) -> std::io::Result<()>;
+}
+
+/// Keeps the cursor in memory, the stream starts from scratch on every run
+#[derive(Debug, Default)]
+pub struct MemoryCursor(pub Option<u64>);
+
+impl CursorStore for MemoryCursor {
+    fn load(&mut self) -> std::io::Result<Option<u64>> {
+        Ok(self.0)
+    }
+
+    fn save(&mut self, diff_id: u64) -> std::io::Result<()> {
+        self.0 = Some(diff_id);
+        Ok(())
+    }
+}
+
+/// Keeps the cursor in a file, so that the stream resumes where it stopped
+#[derive(Debug)]
+pub struct FileCursor {
+    pub path: PathBuf,
+}
+
+impl FileCursor {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl CursorStore for FileCursor {
+    fn load(&mut self) -> std::io::Result<Option<u64>> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(content) => content
+                .trim()
+                .parse::<u64>()
+                .map(Some)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn save(&mut self, diff_id: u64) -> std::io::Result<()> {
+        std::fs::write(&self.path, diff_id.to_string())
+    }
+}
+
+/// A stream yielding the events happening on the account, using the `diff` long polling.
+///
+/// Without a saved cursor, the stream starts with the events happening from now on.
+/// The cursor is saved after every yielded event.
+///
+/// ```no_run
+/// use pcloud::general::change_stream::{ChangeStream, FileCursor};
+/// use pcloud::http::HttpClientBuilder;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let mut stream = ChangeStream::new(&client, FileCursor::new("./pcloud.cursor"));
+/// while let Some(entry) = stream.next().await {
+///     println!("{:?}", entry.unwrap().event);
+/// }
+/// # })
+/// ```
+pub struct ChangeStream<'a, C> {
+    client: &'a HttpClient,
+    cursor: C,
+    diff_id: Option<u64>,
+    buffer: VecDeque<DiffEntry>,
+    limit: Option<u64>,
+}
+
+impl<'a, C: CursorStore> ChangeStream<'a, C> {
+    pub fn new(client: &'a HttpClient, cursor: C) -> Self {
+        Self {
+            client,
+            cursor,
+            diff_id: None,
+            buffer: VecDeque::new(),
+            limit: None,
+        }
+    }
+
+    /// Maximum number of events fetched at once
+    pub fn limit(mut self, value: u64) -> Self {
+        self.limit = Some(value);
+        self
+    }
+
+    /// The id of the last handled event
+    pub fn diff_id(&self) -> Option<u64> {
+        self.diff_id
+    }
+
+    async fn fetch(&mut self) -> Result<(), Error> {
+        let diff_id = match self.diff_id {
+            Some(value) => value,
+            None => match self.cursor.load().map_err(Error::Cursor)? {
+                Some(value) => value,
+                None => {
+                    // no cursor, starting from the current state
+                    let diff = DiffCommand::default().last(0).execute(self.client).await?;
+                    self.cursor.save(diff.diff_id).map_err(Error::Cursor)?;
+                    diff.diff_id
+                }
+            },
+        };
+        self.diff_id = Some(diff_id);
+        let mut cmd = DiffCommand::default().diff_id(diff_id).block(true);
+        if let Some(limit) = self.limit {
+            cmd = cmd.limit(limit);
+        }
+        let diff = cmd.execute(self.client).await?;
+        self.buffer.extend(diff.entries);
+        Ok(())
+    }
+
+    /// Waits for the next event.
+    ///
+    /// On error, the next call retries from the last handled event.
+    pub async fn next(&mut self) -> Option<Result<DiffEntry, Error>> {
+        while self.buffer.is_empty() {
+            if let Err(err) = self.fetch().await {
+                return Some(Err(err));
+            }
+        }
+        let entry = self.buffer.pop_front()?;
+        if let Err(err) = self.cursor.save(entry.diff_id) {
+            self.buffer.push_front(entry);
+            return Some(Err(Error::Cursor(err)));
+        }
+        self.diff_id = Some(entry.diff_id);
+        Some(Ok(entry))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChangeStream, CursorStore, FileCursor, MemoryCursor};
+    use crate::credentials::Credentials;
+    use crate::general::diff::Event;
+    use crate::http::HttpClient;
+    use crate::region::Region;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn resume_from_cursor() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/diff")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("diffid".into(), "10".into()),
+                Matcher::UrlEncoded("block".into(), "1".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                r#"{
+    "result": 0,
+    "diffid": 12,
+    "entries": [
+        { "diffid": 11, "time": "Sat, 24 Jul 2021 07:38:41 +0000", "event": "modifyuserinfo" },
+        { "diffid": 12, "time": "Sat, 24 Jul 2021 07:38:42 +0000", "event": "reset" }
+    ]
+}"#,
+            )
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let mut stream = ChangeStream::new(&api, MemoryCursor(Some(10)));
+        let first = stream.next().await.unwrap().unwrap();
+        assert!(matches!(first.event, Event::ModifyUserInfo));
+        let second = stream.next().await.unwrap().unwrap();
+        assert!(matches!(second.event, Event::Reset));
+        assert_eq!(stream.diff_id(), Some(12));
+        assert_eq!(stream.cursor.load().unwrap(), Some(12));
+        m.assert();
+    }
+
+    #[test]
+    fn file_cursor() {
+        let path = std::env::temp_dir().join(format!("{}.cursor", crate::tests::random_name()));
+        let mut cursor = FileCursor::new(&path);
+        assert_eq!(cursor.load().unwrap(), None);
+        cursor.save(42).unwrap();
+        assert_eq!(cursor.load().unwrap(), Some(42));
+        std::fs::remove_file(path).unwrap();
+    }
+}
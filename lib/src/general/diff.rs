@@ -0,0 +1,363 @@
+//! Resources needed to list the changes happening on the account
+
+use crate::entry::{File, Folder};
+use crate::sharing::Permissions;
+use chrono::prelude::{DateTime, Utc};
+use std::convert::TryFrom;
+
+/// Command to list the events that happened on the account
+///
+/// Without any parameter, every event since the creation of the account is returned.
+/// When `block` is set and no event is available, the server waits for a new event before responding,
+/// so the client timeout should be long enough.
+///
+/// Executing this command will return a [`Diff`](Diff) on success.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/general/diff.html)
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::general::diff::DiffCommand;
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = DiffCommand::default().diff_id(1234).block(true);
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug, Default)]
+pub struct DiffCommand {
+    /// Only returns the events after this one
+    pub diff_id: Option<u64>,
+    /// Only returns the events after this date
+    pub after: Option<DateTime<Utc>>,
+    /// Only returns the last events
+    pub last: Option<u64>,
+    /// Waits for new events if none is available
+    pub block: bool,
+    /// Maximum number of events returned
+    pub limit: Option<u64>,
+}
+
+impl DiffCommand {
+    pub fn diff_id(mut self, value: u64) -> Self {
+        self.diff_id = Some(value);
+        self
+    }
+
+    pub fn after(mut self, value: DateTime<Utc>) -> Self {
+        self.after = Some(value);
+        self
+    }
+
+    pub fn last(mut self, value: u64) -> Self {
+        self.last = Some(value);
+        self
+    }
+
+    pub fn block(mut self, value: bool) -> Self {
+        self.block = value;
+        self
+    }
+
+    pub fn limit(mut self, value: u64) -> Self {
+        self.limit = Some(value);
+        self
+    }
+}
+
+/// Structure returned when listing the events
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Diff {
+    /// The id of the last event, to use in the next call
+    #[serde(rename = "diffid")]
+    pub diff_id: u64,
+    #[serde(default)]
+    pub entries: Vec<DiffEntry>,
+}
+
+/// An event that happened on the account
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(try_from = "RawDiffEntry")]
+pub struct DiffEntry {
+    pub diff_id: u64,
+    pub time: DateTime<Utc>,
+    pub event: Event,
+}
+
+/// The kind of event related to a share
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShareEventKind {
+    RequestIn,
+    AcceptedIn,
+    DeclinedIn,
+    CancelledIn,
+    RemovedIn,
+    ModifiedIn,
+    RequestOut,
+    AcceptedOut,
+    DeclinedOut,
+    CancelledOut,
+    RemovedOut,
+    ModifiedOut,
+}
+
+impl ShareEventKind {
+    fn from_event_name(value: &str) -> Option<Self> {
+        Some(match value {
+            "requestsharein" => Self::RequestIn,
+            "acceptedsharein" => Self::AcceptedIn,
+            "declinedsharein" => Self::DeclinedIn,
+            "cancelledsharein" => Self::CancelledIn,
+            "removedsharein" => Self::RemovedIn,
+            "modifiedsharein" => Self::ModifiedIn,
+            "requestshareout" => Self::RequestOut,
+            "acceptedshareout" => Self::AcceptedOut,
+            "declinedshareout" => Self::DeclinedOut,
+            "cancelledshareout" => Self::CancelledOut,
+            "removedshareout" => Self::RemovedOut,
+            "modifiedshareout" => Self::ModifiedOut,
+            _ => return None,
+        })
+    }
+
+    /// Whether the share concerns a folder shared with the current user
+    pub fn is_incoming(&self) -> bool {
+        matches!(
+            self,
+            Self::RequestIn
+                | Self::AcceptedIn
+                | Self::DeclinedIn
+                | Self::CancelledIn
+                | Self::RemovedIn
+                | Self::ModifiedIn
+        )
+    }
+}
+
+/// The share described in a share event
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct ShareInfo {
+    #[serde(default, rename = "shareid")]
+    pub share_id: Option<u64>,
+    #[serde(default, rename = "sharerequestid")]
+    pub share_request_id: Option<u64>,
+    #[serde(default, rename = "folderid")]
+    pub folder_id: Option<u64>,
+    #[serde(default, rename = "sharename")]
+    pub name: Option<String>,
+    #[serde(default, rename = "frommail")]
+    pub from_mail: Option<String>,
+    #[serde(default, rename = "tomail")]
+    pub to_mail: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(flatten)]
+    pub permissions: Permissions,
+}
+
+/// The typed event
+#[derive(Clone, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum Event {
+    /// The state should be rebuilt from scratch
+    Reset,
+    CreateFile(File),
+    ModifyFile(File),
+    DeleteFile(File),
+    CreateFolder(Folder),
+    ModifyFolder(Folder),
+    DeleteFolder(Folder),
+    Share {
+        kind: ShareEventKind,
+        share: ShareInfo,
+    },
+    ModifyUserInfo,
+    /// An event not handled yet by this library
+    Unknown(String),
+}
+
+impl Event {
+    /// The id of the folder affected by the event, when it concerns a folder or the parent of a file
+    pub fn parent_folder_id(&self) -> Option<u64> {
+        match self {
+            Self::CreateFile(file) | Self::ModifyFile(file) | Self::DeleteFile(file) => {
+                file.base.parent_folder_id
+            }
+            Self::CreateFolder(folder)
+            | Self::ModifyFolder(folder)
+            | Self::DeleteFolder(folder) => folder.base.parent_folder_id,
+            _ => None,
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct RawDiffEntry {
+    #[serde(rename = "diffid")]
+    diff_id: u64,
+    #[serde(with = "crate::date")]
+    time: DateTime<Utc>,
+    event: String,
+    #[serde(default)]
+    metadata: Option<serde_json::Value>,
+    #[serde(default)]
+    share: Option<serde_json::Value>,
+}
+
+fn take<T: serde::de::DeserializeOwned>(
+    value: Option<serde_json::Value>,
+    event: &str,
+    field: &str,
+) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("missing {field} for event {event:?}"))?;
+    serde_json::from_value(value)
+        .map_err(|err| format!("invalid {field} for event {event:?}: {err}"))
+}
+
+impl TryFrom<RawDiffEntry> for DiffEntry {
+    type Error = String;
+
+    fn try_from(value: RawDiffEntry) -> Result<Self, Self::Error> {
+        let name = value.event.as_str();
+        let event = match name {
+            "reset" => Event::Reset,
+            "createfile" => Event::CreateFile(take(value.metadata, name, "metadata")?),
+            "modifyfile" => Event::ModifyFile(take(value.metadata, name, "metadata")?),
+            "deletefile" => Event::DeleteFile(take(value.metadata, name, "metadata")?),
+            "createfolder" => Event::CreateFolder(take(value.metadata, name, "metadata")?),
+            "modifyfolder" => Event::ModifyFolder(take(value.metadata, name, "metadata")?),
+            "deletefolder" => Event::DeleteFolder(take(value.metadata, name, "metadata")?),
+            "modifyuserinfo" => Event::ModifyUserInfo,
+            other => match ShareEventKind::from_event_name(other) {
+                Some(kind) => Event::Share {
+                    kind,
+                    share: take(value.share, name, "share")?,
+                },
+                None => Event::Unknown(other.to_string()),
+            },
+        };
+        Ok(Self {
+            diff_id: value.diff_id,
+            time: value.time,
+            event,
+        })
+    }
+}
+
+mod command {
+    use super::{Diff, DiffCommand};
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    impl DiffCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let mut res = Vec::new();
+            if let Some(value) = self.diff_id {
+                res.push(("diffid", value.to_string()));
+            }
+            if let Some(value) = self.after {
+                res.push(("after", value.timestamp().to_string()));
+            }
+            if let Some(value) = self.last {
+                res.push(("last", value.to_string()));
+            }
+            if self.block {
+                res.push(("block", 1.to_string()));
+            }
+            if let Some(value) = self.limit {
+                res.push(("limit", value.to_string()));
+            }
+            res
+        }
+    }
+
+    impl Command for DiffCommand {
+        type Output = Diff;
+
+        fn request(&self) -> Request<'_> {
+            Request::new("diff", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<Diff>(body)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Diff, Event, ShareEventKind};
+
+    #[test]
+    fn deserialize_events() {
+        let diff: Diff = serde_json::from_str(
+            r#"{
+    "diffid": 12,
+    "entries": [
+        {
+            "diffid": 10,
+            "time": "Sat, 24 Jul 2021 07:38:41 +0000",
+            "event": "createfolder",
+            "metadata": {
+                "name": "photos",
+                "created": "Sat, 24 Jul 2021 07:38:41 +0000",
+                "modified": "Sat, 24 Jul 2021 07:38:41 +0000",
+                "thumb": false,
+                "isfolder": true,
+                "folderid": 42,
+                "id": "d42",
+                "isshared": false,
+                "ismine": true,
+                "parentfolderid": 0,
+                "icon": "folder"
+            }
+        },
+        {
+            "diffid": 11,
+            "time": "Sat, 24 Jul 2021 07:38:42 +0000",
+            "event": "acceptedshareout",
+            "share": {
+                "shareid": 3,
+                "folderid": 42,
+                "sharename": "photos",
+                "tomail": "someone@example.com",
+                "cancreate": true
+            }
+        },
+        {
+            "diffid": 12,
+            "time": "Sat, 24 Jul 2021 07:38:43 +0000",
+            "event": "somethingnew"
+        }
+    ]
+}"#,
+        )
+        .unwrap();
+        assert_eq!(diff.diff_id, 12);
+        assert!(
+            matches!(diff.entries[0].event, Event::CreateFolder(ref folder) if folder.folder_id == 42)
+        );
+        assert_eq!(diff.entries[0].event.parent_folder_id(), Some(0));
+        match diff.entries[1].event {
+            Event::Share { kind, ref share } => {
+                assert_eq!(kind, ShareEventKind::AcceptedOut);
+                assert!(!kind.is_incoming());
+                assert!(share.permissions.can_create());
+            }
+            _ => panic!("expected share event"),
+        }
+        assert!(
+            matches!(diff.entries[2].event, Event::Unknown(ref name) if name == "somethingnew")
+        );
+    }
+}
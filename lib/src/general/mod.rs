@@ -1,3 +1,8 @@
+/// Continuous stream of the events happening on the account
+#[cfg(feature = "client-http")]
+pub mod change_stream;
+/// Events happening on the account
+pub mod diff;
 /// IP address of the remote device
 pub mod get_ip;
 /// Information about the current user
@@ -30,13 +30,11 @@ impl UserInfoCommand {
         self.logout = value;
     }
 }
-#[cfg(feature = "client-http")]
-mod http {
+mod command {
     use super::{UserInfo, UserInfoCommand};
     use crate::error::Error;
-    use crate::http::HttpClient;
-    use crate::prelude::HttpCommand;
-    use crate::request::Response;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
 
     impl UserInfoCommand {
         fn to_http_params(&self) -> Vec<(&str, String)> {
@@ -51,15 +49,15 @@ mod http {
         }
     }
 
-    #[async_trait::async_trait]
-    impl HttpCommand for UserInfoCommand {
+    impl Command for UserInfoCommand {
         type Output = UserInfo;
 
-        async fn execute(mut self, client: &HttpClient) -> Result<Self::Output, Error> {
-            let result: Response<UserInfo> = client
-                .get_request("userinfo", &self.to_http_params())
-                .await?;
-            result.payload()
+        fn request(&self) -> Request<'_> {
+            Request::new("userinfo", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<UserInfo>(body)
         }
     }
 }
@@ -1,9 +1,26 @@
 //! The client implementing the [HTTP Json protocol](https://docs.pcloud.com/protocols/http_json_protocol/)
 
-use crate::credentials::Credentials;
-use crate::error::Error;
+use crate::credentials::{Credentials, Secret};
+use crate::error::{Context, Error};
+use crate::prelude::Command;
 use crate::region::Region;
+use crate::request::{Body, Request};
+use cassette::{Cassette, Mode};
+use limiter::{Limiter, RateLimit};
+use observer::{Observation, Observer, Observers};
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::OwnedSemaphorePermit;
+use transfer::{Progress, Throughput, TransferLimits};
+
+pub mod cassette;
+pub mod limiter;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod observer;
+pub mod progress;
+pub mod transfer;
 
 /// The default user agent for the http client
 pub const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
@@ -15,6 +32,10 @@ pub const DEFAULT_PART_SIZE: usize = 10485760;
 pub enum HttpClientBuilderError {
     CredentialsMissing,
     Reqwest(reqwest::Error),
+    /// The cassette to replay cannot be read
+    Cassette(std::io::Error),
+    /// The runtime of a [`BlockingHttpClient`](crate::blocking::BlockingHttpClient) cannot be started
+    Runtime(std::io::Error),
 }
 
 /// A builder for the [`HttpClient`](HttpClient) structure
@@ -25,42 +46,114 @@ pub enum HttpClientBuilderError {
 /// use pcloud::region::Region;
 ///
 /// let _client = HttpClientBuilder::default()
-///    .credentials(Credentials::AccessToken("my-token".to_string()))
+///    .credentials(Credentials::access_token("my-token"))
 ///    .region(Region::eu())
 ///    .build()
 ///    .expect("unable to builder http client");
 /// ```
-#[derive(Debug, Default)]
+#[derive(Default)]
 pub struct HttpClientBuilder {
     pub client_builder: reqwest::ClientBuilder,
     pub credentials: Option<Credentials>,
     pub region: Option<Region>,
+    /// Total timeout of the api calls, the transfers are supervised with the `transfer_limits`
     pub timeout: Option<Duration>,
+    pub connect_timeout: Option<Duration>,
+    pub transfer_limits: TransferLimits,
+    pub rate_limit: RateLimit,
+    observers: Vec<Arc<dyn Observer>>,
+    cassette: Option<(Mode, PathBuf)>,
+}
+
+impl std::fmt::Debug for HttpClientBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpClientBuilder")
+            .field("client_builder", &self.client_builder)
+            .field("credentials", &self.credentials)
+            .field("region", &self.region)
+            .field("timeout", &self.timeout)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("transfer_limits", &self.transfer_limits)
+            .field("rate_limit", &self.rate_limit)
+            .field("observers", &self.observers.len())
+            .field("cassette", &self.cassette)
+            .finish()
+    }
 }
 
 // TODO handle the parsing error gracefully
-fn duration_from_env() -> Option<Duration> {
-    std::env::var("PCLOUD_TIMEOUT")
-        .ok()
-        .map(|value| {
-            value
-                .parse::<u64>()
-                .expect("invalid value for PCLOUD_TIMEOUT environment variable")
-        })
-        .map(Duration::from_millis)
+fn duration_from_env(name: &str) -> Option<Duration> {
+    number_from_env(name).map(Duration::from_millis)
+}
+
+fn number_from_env<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name).ok().map(|value| {
+        value
+            .parse::<T>()
+            .unwrap_or_else(|_| panic!("invalid value for {} environment variable", name))
+    })
+}
+
+fn transfer_limits_from_env() -> TransferLimits {
+    TransferLimits {
+        idle_timeout: duration_from_env("PCLOUD_IDLE_TIMEOUT"),
+        total_timeout: duration_from_env("PCLOUD_TRANSFER_TIMEOUT"),
+        min_throughput: number_from_env("PCLOUD_MIN_THROUGHPUT").map(|value| {
+            let throughput = Throughput::new(value);
+            match duration_from_env("PCLOUD_THROUGHPUT_WINDOW") {
+                Some(window) => throughput.window(window),
+                None => throughput,
+            }
+        }),
+    }
+}
+
+fn rate_limit_from_env() -> RateLimit {
+    RateLimit {
+        requests_per_second: number_from_env("PCLOUD_REQUESTS_PER_SECOND"),
+        burst: number_from_env("PCLOUD_REQUESTS_BURST"),
+        max_transfers: number_from_env("PCLOUD_MAX_TRANSFERS"),
+        bandwidth: number_from_env("PCLOUD_BANDWIDTH"),
+    }
+}
+
+fn cassette_from_env() -> Option<(Mode, PathBuf)> {
+    let path = std::env::var_os("PCLOUD_CASSETTE")?;
+    let mode = match std::env::var("PCLOUD_CASSETTE_MODE").as_deref() {
+        Ok("record") => Mode::Record,
+        Ok("replay") | Err(_) => Mode::Replay,
+        Ok(other) => panic!(
+            "invalid value {:?} for PCLOUD_CASSETTE_MODE environment variable",
+            other
+        ),
+    };
+    Some((mode, PathBuf::from(path)))
 }
 
 impl HttpClientBuilder {
     /// Builds a http client builder from the environment variables. See [`Credentials`](crate::credentials::Credentials) and [`Region`](crate::region::Region).
     ///
-    /// The timeout value will be the value from the `PCLOUD_TIMEOUT` environment variable, in milliseconds.
-    /// If the value is not a valid number, the function will panic.
+    /// The timeout value will be the value from the `PCLOUD_TIMEOUT` environment variable, in milliseconds,
+    /// and the connect timeout the value from `PCLOUD_CONNECT_TIMEOUT`.
+    /// The transfer limits are read from the `PCLOUD_IDLE_TIMEOUT` and `PCLOUD_TRANSFER_TIMEOUT` (in milliseconds),
+    /// `PCLOUD_MIN_THROUGHPUT` (in bytes per second) and `PCLOUD_THROUGHPUT_WINDOW` (in milliseconds) environment variables.
+    /// The rate limit is read from the `PCLOUD_REQUESTS_PER_SECOND`, `PCLOUD_REQUESTS_BURST`,
+    /// `PCLOUD_MAX_TRANSFERS` and `PCLOUD_BANDWIDTH` (in bytes per second) environment variables.
+    /// If a value is not a valid number, the function will panic.
+    ///
+    /// When `PCLOUD_CASSETTE` is set, the client records in or replays the given cassette,
+    /// depending on `PCLOUD_CASSETTE_MODE` (`record` or `replay`, the default).
     pub fn from_env() -> Self {
         Self {
             client_builder: reqwest::ClientBuilder::default(),
             credentials: Credentials::from_env(),
             region: Region::from_env(),
-            timeout: duration_from_env(),
+            timeout: duration_from_env("PCLOUD_TIMEOUT"),
+            connect_timeout: duration_from_env("PCLOUD_CONNECT_TIMEOUT"),
+            transfer_limits: transfer_limits_from_env(),
+            rate_limit: rate_limit_from_env(),
+            observers: Vec::new(),
+            cassette: cassette_from_env(),
         }
     }
 
@@ -79,11 +172,52 @@ impl HttpClientBuilder {
         self
     }
 
+    /// Total timeout of the api calls, not applied to the downloads and the uploads
     pub fn timeout(mut self, value: Duration) -> Self {
         self.timeout = Some(value);
         self
     }
 
+    pub fn connect_timeout(mut self, value: Duration) -> Self {
+        self.connect_timeout = Some(value);
+        self
+    }
+
+    /// Limits to abort the stalled downloads and uploads, see [`transfer`](crate::http::transfer)
+    pub fn transfer_limits(mut self, value: TransferLimits) -> Self {
+        self.transfer_limits = value;
+        self
+    }
+
+    /// Limits shared by the client and all its clones
+    pub fn rate_limit(mut self, value: RateLimit) -> Self {
+        self.rate_limit = value;
+        self
+    }
+
+    /// Adds an observer called around every request
+    pub fn observer(mut self, value: Arc<dyn Observer>) -> Self {
+        self.observers.push(value);
+        self
+    }
+
+    /// Records every request and its response in the cassette file, replacing its content
+    /// once the client is dropped or [`HttpClient::save_cassette`](HttpClient::save_cassette) is called
+    ///
+    /// The credentials and the secrets are redacted, see [`cassette`](crate::http::cassette).
+    pub fn record(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cassette = Some((Mode::Record, path.into()));
+        self
+    }
+
+    /// Replays the responses recorded in the cassette file instead of calling pCloud
+    ///
+    /// The credentials are not required in this mode.
+    pub fn replay(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cassette = Some((Mode::Replay, path.into()));
+        self
+    }
+
     /// Builds a client for the http protocol
     ///
     /// Returns `Ok(client)` on success, otherwise returns an error.
@@ -92,6 +226,7 @@ impl HttpClientBuilder {
     ///
     /// Returns `Err(HttpClientBuilderError::CredentialsMissing)` when the credentials are not provided.
     /// Returns `Err(HttpClientBuilderError::Reqwest)` when the reqwest client cannot be built.
+    /// Returns `Err(HttpClientBuilderError::Cassette)` when the cassette to replay cannot be read.
     ///
     /// # Example
     ///
@@ -103,22 +238,42 @@ impl HttpClientBuilder {
     ///     Ok(_client) => println!("success!"),
     ///     Err(HttpClientBuilderError::CredentialsMissing) => eprintln!("no credentials provided"),
     ///     Err(HttpClientBuilderError::Reqwest(err)) => eprintln!("unable to build reqwest client: {:?}", err),
+    ///     Err(HttpClientBuilderError::Cassette(err)) => eprintln!("unable to read cassette: {:?}", err),
+    ///     Err(HttpClientBuilderError::Runtime(err)) => eprintln!("unable to start runtime: {:?}", err),
     /// }
     /// ```
     pub fn build(self) -> Result<HttpClient, HttpClientBuilderError> {
-        let client_builder = if let Some(timeout) = self.timeout {
-            self.client_builder.timeout(timeout)
+        let client_builder = if let Some(timeout) = self.connect_timeout {
+            self.client_builder.connect_timeout(timeout)
         } else {
             self.client_builder
         };
+        let cassette = match self.cassette {
+            Some((Mode::Record, path)) => Some(Cassette::recording(path)),
+            Some((Mode::Replay, path)) => {
+                Some(Cassette::load(path).map_err(HttpClientBuilderError::Cassette)?)
+            }
+            None => None,
+        };
+        let credentials = match (self.credentials, &cassette) {
+            (Some(value), _) => value,
+            (None, Some(cassette)) if cassette.mode() == Mode::Replay => {
+                Credentials::access_token("")
+            }
+            (None, _) => return Err(HttpClientBuilderError::CredentialsMissing),
+        };
         Ok(HttpClient {
             client: client_builder
                 .build()
                 .map_err(HttpClientBuilderError::Reqwest)?,
-            credentials: self
-                .credentials
-                .ok_or(HttpClientBuilderError::CredentialsMissing)?,
+            credentials,
             region: self.region.unwrap_or_default(),
+            timeout: self.timeout,
+            connect_timeout: self.connect_timeout,
+            transfer_limits: self.transfer_limits,
+            limiter: Arc::new(Limiter::from(self.rate_limit)),
+            observers: Arc::new(self.observers),
+            cassette: cassette.map(Arc::new),
         })
     }
 }
@@ -147,6 +302,12 @@ pub struct HttpClient {
     pub(crate) client: reqwest::Client,
     credentials: Credentials,
     region: Region,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    transfer_limits: TransferLimits,
+    limiter: Arc<Limiter>,
+    observers: Observers,
+    cassette: Option<Arc<Cassette>>,
 }
 
 #[cfg(test)]
@@ -159,23 +320,121 @@ impl HttpClient {
                 .unwrap(),
             credentials,
             region,
+            timeout: None,
+            connect_timeout: None,
+            transfer_limits: TransferLimits::default(),
+            limiter: Arc::default(),
+            observers: Arc::default(),
+            cassette: None,
         }
     }
 }
 
-async fn read_response<T: serde::de::DeserializeOwned>(
+/// The code of a response, to report the protocol errors to the observers
+#[derive(serde::Deserialize)]
+struct ResultCode {
+    result: u16,
+}
+
+async fn read_body(
     action: &str,
-    method: &str,
     res: reqwest::Response,
-) -> Result<T, Error> {
+    observation: &mut Observation,
+    context: &Context,
+) -> Result<bytes::Bytes, Error> {
+    let status = res.status();
+    observation.status(status);
+    let body = res
+        .bytes()
+        .await
+        .map_err(|err| Error::from(err).with_context(context.clone().status(status.as_u16())))?;
+    observation.received(body.len());
+    if observation.is_active() {
+        if let Ok(ResultCode { result }) = serde_json::from_slice::<ResultCode>(&body) {
+            if result != 0 {
+                observation.error(observer::ErrorClass::Protocol(result));
+            }
+        }
+    }
     if cfg!(test) {
-        let body = res.text().await?;
-        println!("{} {}: {}", action, method, body);
-        Ok(serde_json::from_str(&body).unwrap())
+        println!(
+            "{} {}: {}",
+            action,
+            context.method,
+            String::from_utf8_lossy(&body)
+        );
     } else {
-        let status = res.status();
         tracing::debug!("responded with status {status:?}");
-        res.json::<T>().await.map_err(Error::from)
+    }
+    Ok(body)
+}
+
+async fn read_response<T: serde::de::DeserializeOwned>(
+    action: &str,
+    res: reqwest::Response,
+    observation: &mut Observation,
+    context: &Context,
+) -> Result<T, Error> {
+    let status = res.status().as_u16();
+    let body = read_body(action, res, observation, context).await?;
+    serde_json::from_slice::<T>(&body)
+        .map_err(|err| Error::from(err).with_context(context.clone().status(status).body(&body)))
+}
+
+/// A response whose body is read within the limits of the client, and observed
+pub(crate) struct RawResponse {
+    inner: reqwest::Response,
+    limiter: Arc<Limiter>,
+    /// The progress of the supervised transfer reading the response
+    progress: Option<Progress>,
+    observation: Observation,
+    context: Context,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl RawResponse {
+    pub(crate) fn headers(&self) -> &reqwest::header::HeaderMap {
+        self.inner.headers()
+    }
+
+    /// Pauses the supervision of the transfer while waiting for the bandwidth limit
+    pub(crate) fn progress(mut self, progress: &Progress) -> Self {
+        self.progress = Some(progress.clone());
+        self
+    }
+
+    pub(crate) async fn chunk(&mut self) -> Result<Option<bytes::Bytes>, Error> {
+        let chunk = match self.inner.chunk().await {
+            Ok(value) => value,
+            Err(err) => {
+                let context = self.context.clone().status(self.inner.status().as_u16());
+                return self
+                    .observation
+                    .finish(Err(Error::from(err).with_context(context)));
+            }
+        };
+        match chunk {
+            Some(ref value) => {
+                self.observation.received(value.len());
+                let wait = self.limiter.bytes(value.len());
+                match self.progress {
+                    Some(ref progress) => progress.throttle(wait).await,
+                    None => wait.await,
+                }
+            }
+            None => {
+                self.observation.finish(Ok(()))?;
+            }
+        }
+        Ok(chunk)
+    }
+
+    pub(crate) async fn bytes(mut self) -> Result<Vec<u8>, Error> {
+        let mut result = Vec::new();
+        while let Some(chunk) = self.chunk().await? {
+            result.extend_from_slice(&chunk);
+        }
+        Ok(result)
     }
 }
 
@@ -184,52 +443,330 @@ impl HttpClient {
         format!("{}/{}", self.region.http_url(), method)
     }
 
-    #[tracing::instrument(name = "get", skip(self, params))]
-    pub(crate) async fn get_request<T: serde::de::DeserializeOwned>(
+    /// Applies the total timeout to the requests that are not transfers
+    fn api_request(&self, req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self.timeout {
+            Some(timeout) => req.timeout(timeout),
+            None => req,
+        }
+    }
+
+    /// The http method of the calls without content, a POST when the credentials are in the body
+    fn api_http_method(&self) -> &'static str {
+        match self.credentials {
+            Credentials::AccessToken(_) => "GET",
+            Credentials::UserPassword { .. } => "POST",
+        }
+    }
+
+    /// Builds a call without content, with the credentials in the headers or in a form body,
+    /// never in the url where they could be recorded by a proxy or in the logs
+    ///
+    /// The secret parameters are sent in the form body as well.
+    fn api_call(
         &self,
         method: &str,
         params: &[(&str, String)],
-    ) -> Result<T, Error> {
-        let mut local_params = self.credentials.to_http_params();
-        local_params.extend_from_slice(params);
+        secrets: &[(&str, &Secret)],
+    ) -> reqwest::RequestBuilder {
         let uri = self.build_url(method);
         tracing::debug!("calling {uri}");
-        let res = self.client.get(uri).query(&local_params).send().await?;
-        read_response("GET", method, res).await
+        let mut form: Vec<(&str, &str)> = secrets
+            .iter()
+            .map(|(key, value)| (*key, value.expose()))
+            .collect();
+        let req = match self.credentials {
+            Credentials::AccessToken(ref token) if form.is_empty() => {
+                self.client.get(uri).bearer_auth(token.expose())
+            }
+            Credentials::AccessToken(ref token) => self
+                .client
+                .post(uri)
+                .bearer_auth(token.expose())
+                .form(&form),
+            Credentials::UserPassword { .. } => {
+                form.extend(self.credentials.to_http_params());
+                self.client.post(uri).form(&form)
+            }
+        };
+        req.query(params)
     }
 
-    #[tracing::instrument(name = "put", skip(self, params))]
-    pub(crate) async fn put_request_data<T: serde::de::DeserializeOwned>(
+    /// Adds the credentials to a call sending a content, where pCloud only accepts
+    /// the username and the password in the url
+    fn content_call(&self, req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match self.credentials {
+            Credentials::AccessToken(ref token) => req.bearer_auth(token.expose()),
+            Credentials::UserPassword { .. } => req.query(&self.credentials.to_http_params()),
+        }
+    }
+
+    pub(crate) fn transfer_limits(&self) -> TransferLimits {
+        self.transfer_limits
+    }
+
+    /// Writes the interactions recorded so far in the cassette file
+    ///
+    /// The cassette is also written when the client and all its clones are dropped.
+    /// Does nothing when the client is not recording.
+    pub fn save_cassette(&self) -> Result<(), Error> {
+        match self.cassette {
+            Some(ref cassette) => cassette.save(),
+            None => Ok(()),
+        }
+    }
+
+    /// A copy of the client with its own connection pool, keeping at most one idle connection per host
+    ///
+    /// The limits, the observers and the cassette are still shared with the original client.
+    pub(crate) fn single_connection(&self) -> Result<Self, Error> {
+        let builder = reqwest::ClientBuilder::default()
+            .user_agent(USER_AGENT)
+            .pool_max_idle_per_host(1);
+        let builder = match self.connect_timeout {
+            Some(timeout) => builder.connect_timeout(timeout),
+            None => builder,
+        };
+        Ok(Self {
+            client: builder.build()?,
+            ..self.clone()
+        })
+    }
+
+    async fn send(
+        &self,
+        req: reqwest::RequestBuilder,
+        observation: &mut Observation,
+        context: &Context,
+    ) -> Result<reqwest::Response, Error> {
+        self.send_to(req, false, observation, context).await
+    }
+
+    /// Sends the request, `link` being set for the downloads of links giving access to a file
+    async fn send_to(
+        &self,
+        req: reqwest::RequestBuilder,
+        link: bool,
+        observation: &mut Observation,
+        context: &Context,
+    ) -> Result<reqwest::Response, Error> {
+        let result = match self.cassette {
+            Some(ref cassette) if cassette.mode() == Mode::Replay => cassette.replay(req, link),
+            Some(ref cassette) => {
+                self.limiter.request().await;
+                cassette.record(&self.client, req, link).await
+            }
+            None => {
+                self.limiter.request().await;
+                req.send().await.map_err(Error::from)
+            }
+        };
+        match result {
+            Ok(res) => {
+                observation.status(res.status());
+                Ok(res)
+            }
+            Err(err) => observation.finish(Err(err.with_context(context.clone()))),
+        }
+    }
+
+    #[tracing::instrument(name = "get", skip(self, params))]
+    pub(crate) async fn get_request<T: serde::de::DeserializeOwned>(
         &self,
         method: &str,
         params: &[(&str, String)],
-        payload: Vec<u8>,
     ) -> Result<T, Error> {
-        let mut local_params = self.credentials.to_http_params();
-        local_params.extend_from_slice(params);
-        let uri = self.build_url(method);
+        let context = Context::new(method, params);
+        let http_method = self.api_http_method();
+        let mut observation = Observation::start(&self.observers, http_method, method);
+        let req = self.api_request(self.api_call(method, params, &[]));
+        let res = self.send(req, &mut observation, &context).await?;
+        let result = read_response(http_method, res, &mut observation, &context).await;
+        observation.finish(result)
+    }
+
+    /// Calls a method and returns the body without decoding it, to parse it incrementally
+    #[tracing::instrument(name = "get_bytes", skip(self, params))]
+    pub(crate) async fn get_request_bytes(
+        &self,
+        method: &str,
+        params: &[(&str, String)],
+    ) -> Result<Vec<u8>, Error> {
+        let context = Context::new(method, params);
+        let http_method = self.api_http_method();
+        let mut observation = Observation::start(&self.observers, http_method, method);
+        let req = self.api_request(self.api_call(method, params, &[]));
+        let res = self.send(req, &mut observation, &context).await?;
+        RawResponse {
+            inner: res,
+            limiter: self.limiter.clone(),
+            progress: None,
+            observation,
+            context,
+            _permit: None,
+        }
+        .bytes()
+        .await
+    }
+
+    /// Calls a method that doesn't return json, like `getthumb`, and returns the raw response
+    #[tracing::instrument(name = "get_raw", skip(self, params))]
+    pub(crate) async fn get_request_raw(
+        &self,
+        method: &str,
+        params: &[(&str, String)],
+    ) -> Result<RawResponse, Error> {
+        let permit = self.limiter.transfer().await?;
+        let context = Context::new(method, params);
+        let http_method = self.api_http_method();
+        let mut observation = Observation::start(&self.observers, http_method, method);
         let res = self
-            .client
-            .put(uri)
-            .query(&local_params)
-            .body(payload)
-            .send()
+            .send(
+                self.api_call(method, params, &[]),
+                &mut observation,
+                &context,
+            )
             .await?;
-        read_response("PUT", method, res).await
+        let is_json = res
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.contains("json"))
+            .unwrap_or(false);
+        if is_json {
+            // on failure, the api responds with a json error instead of the content
+            let result = read_response::<crate::request::Response<()>>(
+                http_method,
+                res,
+                &mut observation,
+                &context,
+            )
+            .await
+            .and_then(|result| result.payload())
+            .and_then(|_| Err(Error::Protocol(0, "unexpected json response".into())));
+            return observation.finish(result);
+        }
+        Ok(RawResponse {
+            inner: res,
+            limiter: self.limiter.clone(),
+            progress: None,
+            observation,
+            context,
+            _permit: permit,
+        })
     }
 
-    #[tracing::instrument(name = "post", skip(self, params))]
-    pub(crate) async fn post_request_multipart<T: serde::de::DeserializeOwned>(
+    /// Downloads the content behind a link returned by a streaming command
+    #[tracing::instrument(name = "get_link", skip(self, url))]
+    pub(crate) async fn get_link(&self, url: &str) -> Result<RawResponse, Error> {
+        let permit = self.limiter.transfer().await?;
+        // the path of the link is not kept, it gives access to the file
+        let context = Context::new("download", &[]);
+        let mut observation = Observation::start(&self.observers, "GET", "download");
+        let res = self
+            .send_to(self.client.get(url), true, &mut observation, &context)
+            .await?;
+        let status = res.status().as_u16();
+        let res = match res.error_for_status() {
+            Ok(res) => res,
+            Err(err) => {
+                let err = Error::from(err).with_context(context.status(status));
+                return observation.finish(Err(err));
+            }
+        };
+        Ok(RawResponse {
+            inner: res,
+            limiter: self.limiter.clone(),
+            progress: None,
+            observation,
+            context,
+            _permit: permit,
+        })
+    }
+
+    #[tracing::instrument(name = "put", skip(self, params, payload, progress))]
+    pub(crate) async fn put_request_data<T: serde::de::DeserializeOwned>(
         &self,
         method: &str,
         params: &[(&str, String)],
-        form: reqwest::multipart::Form,
+        payload: Vec<u8>,
+        progress: &Progress,
     ) -> Result<T, Error> {
-        let mut local_params = self.credentials.to_http_params();
-        local_params.extend_from_slice(params);
         let uri = self.build_url(method);
-        let req = self.client.post(uri).query(&local_params).multipart(form);
-        let res = req.send().await?;
-        read_response("POST", method, res).await
+        let _permit = self.limiter.transfer().await?;
+        progress.throttle(self.limiter.bytes(payload.len())).await;
+        let context = Context::new(method, params);
+        let mut observation = Observation::start(&self.observers, "PUT", method);
+        observation.sent(payload.len());
+        // the streamed body has no known size and pCloud rejects the uploads without a length
+        let req = self
+            .content_call(self.client.put(uri))
+            .query(params)
+            .header(reqwest::header::CONTENT_LENGTH, payload.len())
+            .body(progress.body(payload));
+        let res = self.send(req, &mut observation, &context).await?;
+        let result = read_response("PUT", res, &mut observation, &context).await;
+        observation.finish(result)
+    }
+
+    /// Executes a [`Command`] by sending its request and parsing the response
+    pub async fn send_command<C: Command + Sync>(&self, command: &C) -> Result<C::Output, Error> {
+        if let Some(output) = command.immediate() {
+            return Ok(output);
+        }
+        let request = command.request();
+        let context = Context::new(request.method, &request.params);
+        let (status, body) = self.send_request(request, &context).await?;
+        command
+            .parse(&body)
+            .map_err(|err| err.with_context(context.status(status).body(&body)))
+    }
+
+    #[tracing::instrument(name = "send", skip_all, fields(method = request.method))]
+    async fn send_request(
+        &self,
+        request: Request<'_>,
+        context: &Context,
+    ) -> Result<(u16, bytes::Bytes), Error> {
+        let http_method = match request.body {
+            Body::Empty => self.api_http_method(),
+            _ => request.http_method(),
+        };
+        let uri = self.build_url(request.method);
+        let size = request.body.len();
+        // only the requests sending a content are counted as transfers
+        let _permit = match request.body {
+            Body::Empty | Body::Form(_) => None,
+            _ => self.limiter.transfer().await?,
+        };
+        self.limiter.bytes(size).await;
+        let mut observation = Observation::start(&self.observers, http_method, request.method);
+        observation.sent(size);
+        let req = match request.body {
+            Body::Empty => self.api_request(self.api_call(request.method, &request.params, &[])),
+            Body::Form(fields) => {
+                self.api_request(self.api_call(request.method, &request.params, &fields))
+            }
+            Body::Data(data) => self
+                .content_call(self.client.put(uri))
+                .query(&request.params)
+                .body(data.to_vec()),
+            Body::Multipart(parts) => {
+                let form = parts
+                    .into_iter()
+                    .fold(reqwest::multipart::Form::new(), |form, part| {
+                        let file = reqwest::multipart::Part::bytes(part.data.to_vec())
+                            .file_name(part.filename.to_string());
+                        form.part(part.name, file)
+                    });
+                self.content_call(self.client.post(uri))
+                    .query(&request.params)
+                    .multipart(form)
+            }
+        };
+        let res = self.send(req, &mut observation, context).await?;
+        let status = res.status().as_u16();
+        let result = read_body(http_method, res, &mut observation, context).await;
+        observation.finish(result.map(|body| (status, body)))
     }
 }
@@ -0,0 +1,653 @@
+//! Recording of the calls of a [`HttpClient`](crate::http::HttpClient) in a cassette file, to replay them later
+//!
+//! In record mode, every request is sent to pCloud and kept with its response, with the credentials
+//! and the secrets redacted. The cassette is written when the client and all its clones are dropped,
+//! or earlier with [`HttpClient::save_cassette`](crate::http::HttpClient::save_cassette).
+//! In replay mode, nothing is sent and the responses are read from the cassette, matching the requests
+//! on their http method, path and parameters, whatever the credentials used to record them.
+//!
+//! The links returned by the streaming commands give access to the files, so their paths are redacted
+//! and the downloads are replayed in the recorded order. The contents bigger than
+//! [`MAX_RECORDED_SIZE`](MAX_RECORDED_SIZE), or of unknown size, are streamed without being recorded
+//! and cannot be replayed.
+//!
+//! ```no_run
+//! use pcloud::http::HttpClientBuilder;
+//!
+//! // record once with real credentials
+//! let client = HttpClientBuilder::from_env()
+//!     .record("tests/cassettes/listing.json")
+//!     .build()
+//!     .unwrap();
+//! drop(client);
+//! // and replay in CI, where no credentials are needed
+//! let client = HttpClientBuilder::default()
+//!     .replay("tests/cassettes/listing.json")
+//!     .build()
+//!     .unwrap();
+//! ```
+
+use crate::credentials::is_secret;
+use crate::error::Error;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The value replacing the secrets in a cassette
+pub const REDACTED: &str = "[REDACTED]";
+
+/// The parameters holding the credentials, left out of the recorded requests
+/// so that they match whatever the credentials of the replaying client
+const CREDENTIALS: &[&str] = &["access_token", "auth", "password", "username"];
+
+const FORM: &str = "application/x-www-form-urlencoded";
+
+/// The path replacing the one of the links, in the responses and in the downloads
+const REDACTED_LINK: &str = "/[REDACTED]";
+
+/// The maximum size of a recorded content, the bigger ones are only streamed
+pub const MAX_RECORDED_SIZE: u64 = 1024 * 1024;
+
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            // a link, given as hosts and a path
+            if map.contains_key("hosts") {
+                if let Some(path) = map.get_mut("path") {
+                    *path = Value::String(REDACTED_LINK.into());
+                }
+            }
+            for (key, item) in map.iter_mut() {
+                if is_secret(key) {
+                    *item = Value::String(REDACTED.into());
+                } else {
+                    redact_value(item);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+/// What identifies a request in a cassette
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct RecordedRequest {
+    pub http_method: String,
+    /// The path of the url, like `/listfolder`
+    pub path: String,
+    /// The query parameters, with the secrets redacted
+    pub params: BTreeMap<String, String>,
+}
+
+impl RecordedRequest {
+    fn from_request(request: &reqwest::Request, link: bool) -> Self {
+        if link {
+            return Self {
+                http_method: request.method().to_string(),
+                path: REDACTED_LINK.into(),
+                params: BTreeMap::new(),
+            };
+        }
+        // the calls without content are a POST with the credentials in a form when authenticating
+        // with a password, and a GET otherwise
+        let is_form = request
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .map(|value| value == FORM)
+            .unwrap_or(false);
+        let http_method = if is_form && request.method() == reqwest::Method::POST {
+            reqwest::Method::GET
+        } else {
+            request.method().clone()
+        };
+        Self {
+            http_method: http_method.to_string(),
+            path: request.url().path().to_string(),
+            params: request
+                .url()
+                .query_pairs()
+                .filter(|(key, _)| !CREDENTIALS.contains(&key.as_ref()))
+                .map(|(key, value)| {
+                    let value = if is_secret(&key) {
+                        REDACTED.to_string()
+                    } else {
+                        value.into_owned()
+                    };
+                    (key.into_owned(), value)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for RecordedRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.http_method, self.path)?;
+        for (index, (key, value)) in self.params.iter().enumerate() {
+            let separator = if index == 0 { '?' } else { '&' };
+            write!(f, "{separator}{key}={value}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The body of a recorded response
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RecordedBody {
+    /// A json response, kept readable and with the secrets redacted
+    Json(Value),
+    Text(String),
+    #[serde(with = "base64_bytes")]
+    Binary(Vec<u8>),
+    /// A content streamed without being recorded, too big or of unknown size
+    Omitted {
+        size: Option<u64>,
+    },
+}
+
+/// Keeps the binary contents compact in the cassette
+mod base64_bytes {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+
+    pub fn serialize<S: serde::Serializer>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(value))
+    }
+
+    pub fn deserialize<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        let value = <String as serde::Deserialize>::deserialize(deserializer)?;
+        STANDARD
+            .decode(value)
+            .map_err(<D::Error as serde::de::Error>::custom)
+    }
+}
+
+impl RecordedBody {
+    fn new(content_type: Option<&str>, data: &[u8]) -> Self {
+        let is_json = content_type
+            .map(|value| value.contains("json"))
+            .unwrap_or(false);
+        if is_json {
+            if let Ok(mut value) = serde_json::from_slice::<Value>(data) {
+                redact_value(&mut value);
+                return Self::Json(value);
+            }
+        }
+        match std::str::from_utf8(data) {
+            Ok(text) => Self::Text(text.to_string()),
+            Err(_) => Self::Binary(data.to_vec()),
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Json(value) => value.to_string().into_bytes(),
+            Self::Text(value) => value.clone().into_bytes(),
+            Self::Binary(value) => value.clone(),
+            Self::Omitted { .. } => Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    pub body: RecordedBody,
+}
+
+impl RecordedResponse {
+    fn to_response(&self) -> reqwest::Response {
+        let mut builder = http::Response::builder().status(self.status);
+        if let Some(ref value) = self.content_type {
+            builder = builder.header(reqwest::header::CONTENT_TYPE, value.as_str());
+        }
+        let response = builder
+            .body(self.body.to_bytes())
+            .expect("invalid recorded response");
+        reqwest::Response::from(response)
+    }
+}
+
+/// A request and its response
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Default, serde::Deserialize, serde::Serialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Record,
+    Replay,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    interactions: Vec<Interaction>,
+    /// Whether an interaction has already been replayed
+    replayed: Vec<bool>,
+    /// Whether some recorded interactions are not written yet
+    unsaved: bool,
+}
+
+/// Reads the interactions recorded in a cassette
+pub fn read(path: impl AsRef<Path>) -> std::io::Result<Vec<Interaction>> {
+    let file: CassetteFile = serde_json::from_slice(&std::fs::read(path)?)?;
+    Ok(file.interactions)
+}
+
+/// A file of recorded interactions, shared by a client and all its clones
+#[derive(Debug)]
+pub(crate) struct Cassette {
+    mode: Mode,
+    path: PathBuf,
+    state: Mutex<State>,
+}
+
+impl Cassette {
+    pub fn recording(path: PathBuf) -> Self {
+        Self {
+            mode: Mode::Record,
+            path,
+            state: Mutex::default(),
+        }
+    }
+
+    pub fn load(path: PathBuf) -> std::io::Result<Self> {
+        let interactions = read(&path)?;
+        let replayed = vec![false; interactions.len()];
+        Ok(Self {
+            mode: Mode::Replay,
+            path,
+            state: Mutex::new(State {
+                interactions,
+                replayed,
+                unsaved: false,
+            }),
+        })
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Sends the request and records its response, `link` being set for the downloads of links
+    pub async fn record(
+        &self,
+        client: &reqwest::Client,
+        request: reqwest::RequestBuilder,
+        link: bool,
+    ) -> Result<reqwest::Response, Error> {
+        let request = request.build()?;
+        let recorded = RecordedRequest::from_request(&request, link);
+        let response = client.execute(request).await?;
+        self.record_response(recorded, response).await
+    }
+
+    /// Replays the recorded response of the request, `link` being set for the downloads of links
+    pub fn replay(
+        &self,
+        request: reqwest::RequestBuilder,
+        link: bool,
+    ) -> Result<reqwest::Response, Error> {
+        let request = request.build()?;
+        self.replay_response(&RecordedRequest::from_request(&request, link))
+    }
+
+    /// The identical requests are replayed in the recorded order, the last one being repeated
+    fn replay_response(&self, request: &RecordedRequest) -> Result<reqwest::Response, Error> {
+        let mut state = self.lock();
+        let matching: Vec<usize> = state
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.request == *request)
+            .map(|(index, _)| index)
+            .collect();
+        let index = matching
+            .iter()
+            .find(|index| !state.replayed[**index])
+            .or_else(|| matching.last())
+            .copied()
+            .ok_or_else(|| self.unmatched(&state, request))?;
+        state.replayed[index] = true;
+        let response = &state.interactions[index].response;
+        if let RecordedBody::Omitted { size } = response.body {
+            let size = size
+                .map(|value| format!("{value} bytes"))
+                .unwrap_or_else(|| "unknown size".into());
+            let message = format!(
+                "the content of {} ({}) is not recorded in {:?}",
+                request, size, self.path
+            );
+            tracing::error!("{message}");
+            return Err(Error::Unmatched(message));
+        }
+        tracing::debug!("replaying {request}");
+        Ok(response.to_response())
+    }
+
+    fn unmatched(&self, state: &State, request: &RecordedRequest) -> Error {
+        let candidates: Vec<String> = state
+            .interactions
+            .iter()
+            .filter(|item| {
+                item.request.http_method == request.http_method && item.request.path == request.path
+            })
+            .map(|item| item.request.to_string())
+            .collect();
+        let message = if candidates.is_empty() {
+            format!("no interaction recorded for {} in {:?}", request, self.path)
+        } else {
+            format!(
+                "no interaction recorded for {} in {:?}, the recorded ones with the same path are: {}",
+                request,
+                self.path,
+                candidates.join(", ")
+            )
+        };
+        tracing::error!("{message}");
+        Error::Unmatched(message)
+    }
+
+    async fn record_response(
+        &self,
+        request: RecordedRequest,
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response, Error> {
+        let status = response.status().as_u16();
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from);
+        let is_json = content_type
+            .as_deref()
+            .map(|value| value.contains("json"))
+            .unwrap_or(false);
+        let size = response.content_length();
+        if !is_json
+            && !size
+                .map(|value| value <= MAX_RECORDED_SIZE)
+                .unwrap_or(false)
+        {
+            // a transfer, streamed to the caller and supervised as without cassette
+            self.push(
+                request,
+                RecordedResponse {
+                    status,
+                    content_type,
+                    body: RecordedBody::Omitted { size },
+                },
+            );
+            return Ok(response);
+        }
+        let data = response.bytes().await?;
+        // the caller gets the real body, only the recorded one is redacted
+        let original = RecordedResponse {
+            status,
+            content_type: content_type.clone(),
+            body: RecordedBody::Binary(data.to_vec()),
+        };
+        let recorded = RecordedResponse {
+            status,
+            body: RecordedBody::new(content_type.as_deref(), &data),
+            content_type,
+        };
+        self.push(request, recorded);
+        Ok(original.to_response())
+    }
+
+    fn push(&self, request: RecordedRequest, response: RecordedResponse) {
+        let mut state = self.lock();
+        tracing::debug!("recording {request}");
+        state.interactions.push(Interaction { request, response });
+        state.unsaved = true;
+    }
+
+    /// Writes the recorded interactions in the cassette file, when some are not written yet
+    pub fn save(&self) -> Result<(), Error> {
+        let mut state = self.lock();
+        if self.mode != Mode::Record || !state.unsaved {
+            return Ok(());
+        }
+        let file = serde_json::to_vec_pretty(&CassetteFile {
+            interactions: state.interactions.clone(),
+        })?;
+        std::fs::write(&self.path, file).map_err(Error::Cassette)?;
+        state.unsaved = false;
+        Ok(())
+    }
+}
+
+impl Drop for Cassette {
+    fn drop(&mut self) {
+        if let Err(err) = self.save() {
+            tracing::error!("unable to write cassette {:?}: {:?}", self.path, err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RecordedBody, MAX_RECORDED_SIZE, REDACTED};
+    use crate::credentials::Credentials;
+    use crate::error::Error;
+    use crate::file::download::FileDownloadCommand;
+    use crate::folder::list::FolderListCommand;
+    use crate::http::{HttpClientBuilder, HttpClientBuilderError};
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use crate::testing::FakeServer;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn should_record_and_replay() {
+        crate::tests::init();
+        let path = std::env::temp_dir().join(format!("{}.json", crate::tests::random_name()));
+        let server = FakeServer::start().await.unwrap();
+        server.add_folder(0, "foo");
+
+        let client = server.client_builder().record(&path).build().unwrap();
+        let root = FolderListCommand::new(0.into())
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(root.contents.unwrap().len(), 1);
+        let err = FolderListCommand::new(42.into())
+            .execute(&client)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Protocol(2005, _)));
+        assert!(super::read(&path).is_err());
+        client.save_cassette().unwrap();
+
+        let interactions = super::read(&path).unwrap();
+        assert_eq!(interactions.len(), 2);
+        assert_eq!(interactions[0].request.path, "/listfolder");
+        assert!(!interactions[0].request.params.contains_key("access_token"));
+        assert!(matches!(
+            interactions[0].response.body,
+            RecordedBody::Json(_)
+        ));
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains(crate::testing::ACCESS_TOKEN));
+
+        // the server is gone and the credentials are different
+        drop(server);
+        let client = HttpClientBuilder::default()
+            .credentials(Credentials::AccessToken("other".into()))
+            .replay(&path)
+            .build()
+            .unwrap();
+        let root = FolderListCommand::new(0.into())
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(root.contents.unwrap().len(), 1);
+        let err = FolderListCommand::new(42.into())
+            .execute(&client)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Protocol(2005, _)));
+
+        let err = FolderListCommand::new(1.into())
+            .execute(&client)
+            .await
+            .unwrap_err();
+        match err.inner() {
+            Error::Unmatched(message) => {
+                assert!(message.contains("GET /listfolder?folderid=1"));
+                assert!(message.contains("folderid=42"));
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn should_replay_calls_recorded_with_password() {
+        crate::tests::init();
+        let path = std::env::temp_dir().join(format!("{}.json", crate::tests::random_name()));
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("POST", "/listfolder")
+            .match_query(Matcher::UrlEncoded("folderid".into(), "0".into()))
+            .match_body(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("username".into(), "user".into()),
+                Matcher::UrlEncoded("password".into(), "secret-password".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{ "result": 2005, "error": "Directory does not exist." }"#)
+            .expect(1)
+            .create();
+        let client = HttpClientBuilder::default()
+            .credentials(Credentials::user_password("user", "secret-password"))
+            .region(Region::new(server.url()))
+            .record(&path)
+            .build()
+            .unwrap();
+        let err = FolderListCommand::new(0.into())
+            .execute(&client)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Protocol(2005, _)));
+        // written once the client is dropped
+        drop(client);
+        m.assert();
+
+        let interactions = super::read(&path).unwrap();
+        assert_eq!(interactions.len(), 1);
+        assert_eq!(interactions[0].request.http_method, "GET");
+        assert_eq!(interactions[0].request.params.len(), 1);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(!content.contains("secret-password"));
+
+        let client = HttpClientBuilder::default().replay(&path).build().unwrap();
+        let err = FolderListCommand::new(0.into())
+            .execute(&client)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Protocol(2005, _)));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn should_record_downloads_without_links() {
+        crate::tests::init();
+        let path = std::env::temp_dir().join(format!("{}.json", crate::tests::random_name()));
+        let server = FakeServer::start().await.unwrap();
+        let small_id = server.add_file(0, "small.bin", vec![0xff, 0, 1]);
+        let big = vec![0u8; MAX_RECORDED_SIZE as usize + 1];
+        let big_id = server.add_file(0, "big.bin", big.clone());
+
+        let client = server.client_builder().record(&path).build().unwrap();
+        let mut small = Vec::new();
+        FileDownloadCommand::new(small_id.into(), &mut small)
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(small, vec![0xff, 0, 1]);
+        let mut content = Vec::new();
+        FileDownloadCommand::new(big_id.into(), &mut content)
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(content, big);
+        drop(client);
+
+        let file = std::fs::read_to_string(&path).unwrap();
+        assert!(!file.contains("/download/"), "{}", file);
+        assert!(file.len() < 10_000);
+        let interactions = super::read(&path).unwrap();
+        let downloads: Vec<_> = interactions
+            .iter()
+            .filter(|item| item.request.path == "/[REDACTED]")
+            .collect();
+        assert_eq!(downloads.len(), 2);
+        assert!(matches!(
+            downloads[0].response.body,
+            RecordedBody::Binary(ref data) if data == &[0xff, 0, 1]
+        ));
+        assert!(matches!(
+            downloads[1].response.body,
+            RecordedBody::Omitted { size: Some(size) } if size == MAX_RECORDED_SIZE + 1
+        ));
+
+        drop(server);
+        let client = HttpClientBuilder::default().replay(&path).build().unwrap();
+        let mut small = Vec::new();
+        FileDownloadCommand::new(small_id.into(), &mut small)
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(small, vec![0xff, 0, 1]);
+        let err = FileDownloadCommand::new(big_id.into(), Vec::new())
+            .execute(&client)
+            .await
+            .unwrap_err();
+        assert!(matches!(err.inner(), Error::Unmatched(_)), "{:?}", err);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn should_fail_building_without_cassette() {
+        let path = std::env::temp_dir().join(format!("{}.json", crate::tests::random_name()));
+        let result = HttpClientBuilder::default().replay(path).build();
+        assert!(matches!(result, Err(HttpClientBuilderError::Cassette(_))));
+    }
+
+    #[test]
+    fn should_redact_responses() {
+        let body = RecordedBody::new(
+            Some("application/json"),
+            br#"{ "result": 0, "auth": "secret", "quota": 10, "nested": [{ "token": "secret" }] }"#,
+        );
+        let value = match body {
+            RecordedBody::Json(value) => value,
+            other => panic!("unexpected body {:?}", other),
+        };
+        assert_eq!(value["auth"], REDACTED);
+        assert_eq!(value["nested"][0]["token"], REDACTED);
+        assert_eq!(value["quota"], 10);
+    }
+}
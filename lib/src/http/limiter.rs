@@ -0,0 +1,218 @@
+//! Client side limits on the requests sent to pCloud
+
+use crate::error::Error;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// The limits applied by a [`HttpClient`](crate::http::HttpClient) and all its clones.
+///
+/// The requests are limited by a token bucket, the transfers of file contents by a number of
+/// concurrent transfers and the transferred bytes by a bandwidth cap.
+///
+/// ```
+/// use pcloud::http::limiter::RateLimit;
+///
+/// let limits = RateLimit::default()
+///     .requests_per_second(5.0)
+///     .burst(10)
+///     .max_transfers(4)
+///     .bandwidth(10 * 1024 * 1024);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RateLimit {
+    pub requests_per_second: Option<f64>,
+    /// Number of requests that can be sent at once before being limited, default to one second of requests
+    pub burst: Option<u32>,
+    pub max_transfers: Option<usize>,
+    /// Maximum number of bytes transferred per second, uploads and downloads included
+    pub bandwidth: Option<u64>,
+}
+
+impl RateLimit {
+    pub fn requests_per_second(mut self, value: f64) -> Self {
+        self.requests_per_second = Some(value);
+        self
+    }
+
+    pub fn burst(mut self, value: u32) -> Self {
+        self.burst = Some(value);
+        self
+    }
+
+    pub fn max_transfers(mut self, value: usize) -> Self {
+        self.max_transfers = Some(value);
+        self
+    }
+
+    pub fn bandwidth(mut self, value: u64) -> Self {
+        self.bandwidth = Some(value);
+        self
+    }
+}
+
+struct BucketState {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+/// A token bucket, allowing to take more than its capacity by going in debt
+struct Bucket {
+    rate: f64,
+    capacity: f64,
+    state: Mutex<BucketState>,
+}
+
+impl Bucket {
+    fn new(rate: f64, capacity: f64) -> Self {
+        Self {
+            rate,
+            capacity,
+            state: Mutex::new(BucketState {
+                tokens: capacity,
+                updated_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes the tokens or returns how long to wait before trying again
+    fn try_take(&self, amount: f64) -> Result<(), Duration> {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.updated_at).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.rate).min(self.capacity);
+        state.updated_at = now;
+        let required = amount.min(self.capacity);
+        if state.tokens >= required {
+            state.tokens -= amount;
+            Ok(())
+        } else {
+            // a tiny rate makes a wait too long to be represented
+            Err(
+                Duration::try_from_secs_f64((required - state.tokens) / self.rate)
+                    .unwrap_or(Duration::MAX),
+            )
+        }
+    }
+
+    async fn take(&self, amount: f64) {
+        while let Err(wait) = self.try_take(amount) {
+            tracing::trace!("rate limited for {wait:?}");
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// The state shared between the clones of a client
+#[derive(Default)]
+pub(crate) struct Limiter {
+    requests: Option<Bucket>,
+    transfers: Option<Arc<Semaphore>>,
+    bandwidth: Option<Bucket>,
+}
+
+impl std::fmt::Debug for Limiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Limiter")
+            .field("requests", &self.requests.as_ref().map(|b| b.rate))
+            .field("bandwidth", &self.bandwidth.as_ref().map(|b| b.rate))
+            .finish()
+    }
+}
+
+impl From<RateLimit> for Limiter {
+    fn from(value: RateLimit) -> Self {
+        Self {
+            requests: value
+                .requests_per_second
+                .filter(|rate| *rate > 0.0)
+                .map(|rate| {
+                    let burst = value.burst.map(f64::from).unwrap_or(rate).max(1.0);
+                    Bucket::new(rate, burst)
+                }),
+            transfers: value
+                .max_transfers
+                .filter(|count| *count > 0)
+                .map(|count| Arc::new(Semaphore::new(count))),
+            bandwidth: value
+                .bandwidth
+                .filter(|rate| *rate > 0)
+                .map(|rate| Bucket::new(rate as f64, rate as f64)),
+        }
+    }
+}
+
+impl Limiter {
+    /// Waits for a request to be allowed
+    pub(crate) async fn request(&self) {
+        if let Some(ref bucket) = self.requests {
+            bucket.take(1.0).await;
+        }
+    }
+
+    /// Waits for a transfer slot, released when the permit is dropped
+    pub(crate) async fn transfer(&self) -> Result<Option<OwnedSemaphorePermit>, Error> {
+        match self.transfers {
+            Some(ref semaphore) => semaphore
+                .clone()
+                .acquire_owned()
+                .await
+                .map(Some)
+                .map_err(|_| Error::Protocol(0, "transfer limiter closed".into())),
+            None => Ok(None),
+        }
+    }
+
+    /// Waits for the bytes to fit in the bandwidth
+    pub(crate) async fn bytes(&self, count: usize) {
+        if let Some(ref bucket) = self.bandwidth {
+            bucket.take(count as f64).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bucket, Limiter, RateLimit};
+    use std::time::Duration;
+
+    #[test]
+    fn bucket_should_allow_burst_then_wait() {
+        let bucket = Bucket::new(10.0, 2.0);
+        assert!(bucket.try_take(1.0).is_ok());
+        assert!(bucket.try_take(1.0).is_ok());
+        let wait = bucket.try_take(1.0).unwrap_err();
+        assert!(wait <= Duration::from_millis(100));
+        assert!(wait > Duration::from_millis(50));
+    }
+
+    #[test]
+    fn bucket_should_allow_debt() {
+        let bucket = Bucket::new(100.0, 100.0);
+        // a chunk bigger than the capacity goes through, the next one waits
+        assert!(bucket.try_take(250.0).is_ok());
+        let wait = bucket.try_take(10.0).unwrap_err();
+        assert!(wait > Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn bucket_should_not_overflow_with_tiny_rate() {
+        let bucket = Bucket::new(1e-300, 1.0);
+        assert!(bucket.try_take(1.0).is_ok());
+        assert_eq!(bucket.try_take(1.0).unwrap_err(), Duration::MAX);
+    }
+
+    #[tokio::test]
+    async fn limiter_should_limit_transfers() {
+        let limiter = Limiter::from(RateLimit::default().max_transfers(1));
+        let permit = limiter.transfer().await.unwrap();
+        assert!(permit.is_some());
+        let second = tokio::time::timeout(Duration::from_millis(10), limiter.transfer()).await;
+        assert!(second.is_err());
+        drop(permit);
+        assert!(limiter.transfer().await.unwrap().is_some());
+    }
+}
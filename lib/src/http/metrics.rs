@@ -0,0 +1,49 @@
+//! Integration with the [`metrics`] crate, enabled with the `metrics` feature
+
+use super::observer::{Observer, Outcome, Request};
+
+/// Records the requests of a client with the [`metrics`] crate.
+///
+/// The following metrics are labelled with the pCloud `method`, the http `status` and the `error` class:
+///
+/// - `pcloud_requests_total`, a counter of the requests
+/// - `pcloud_request_duration_seconds`, a histogram of the request durations
+/// - `pcloud_sent_bytes_total` and `pcloud_received_bytes_total`, counters of the transferred bytes
+///
+/// ```
+/// use pcloud::http::metrics::MetricsObserver;
+/// use pcloud::http::HttpClientBuilder;
+/// use std::sync::Arc;
+///
+/// let builder = HttpClientBuilder::from_env().observer(Arc::new(MetricsObserver));
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MetricsObserver;
+
+impl Observer for MetricsObserver {
+    fn on_response(&self, request: &Request<'_>, outcome: &Outcome) {
+        let labels = [
+            ("method", request.method.to_string()),
+            (
+                "status",
+                outcome
+                    .status
+                    .map(|status| status.to_string())
+                    .unwrap_or_default(),
+            ),
+            (
+                "error",
+                outcome
+                    .error
+                    .map(|error| error.as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+        ];
+        metrics::counter!("pcloud_requests_total", &labels).increment(1);
+        metrics::histogram!("pcloud_request_duration_seconds", &labels)
+            .record(outcome.duration.as_secs_f64());
+        metrics::counter!("pcloud_sent_bytes_total", &labels).increment(outcome.bytes_sent);
+        metrics::counter!("pcloud_received_bytes_total", &labels).increment(outcome.bytes_received);
+    }
+}
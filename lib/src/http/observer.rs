@@ -0,0 +1,275 @@
+//! Hooks called around every request sent by the [`HttpClient`](crate::http::HttpClient)
+
+use crate::error::Error;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The request about to be sent
+#[derive(Clone, Debug)]
+pub struct Request<'a> {
+    /// The http method, like `GET`
+    pub http_method: &'static str,
+    /// The name of the pCloud method, like `listfolder`, or `download` for the links
+    pub method: &'a str,
+}
+
+/// What kind of error ended a request
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorClass {
+    /// The server didn't answer in time
+    Timeout,
+    /// Unable to connect to the server
+    Connect,
+    /// Any other network error
+    Network,
+    /// The server answered with an unexpected http status
+    Status(u16),
+    /// The server answered with an error code
+    Protocol(u16),
+    /// The response couldn't be decoded
+    Decode,
+    /// Unable to read or write the local data
+    Io,
+    /// The response was dropped before being completely read
+    Aborted,
+}
+
+impl ErrorClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Timeout => "timeout",
+            Self::Connect => "connect",
+            Self::Network => "network",
+            Self::Status(_) => "status",
+            Self::Protocol(_) => "protocol",
+            Self::Decode => "decode",
+            Self::Io => "io",
+            Self::Aborted => "aborted",
+        }
+    }
+}
+
+impl From<&Error> for ErrorClass {
+    fn from(value: &Error) -> Self {
+        match value {
+            Error::Protocol(code, _) => Self::Protocol(*code),
+            Error::Reqwest(inner) if inner.is_timeout() => Self::Timeout,
+            Error::Reqwest(inner) if inner.is_connect() => Self::Connect,
+            Error::Reqwest(inner) if inner.is_decode() => Self::Decode,
+            Error::Reqwest(inner) => match inner.status() {
+                Some(status) => Self::Status(status.as_u16()),
+                None => Self::Network,
+            },
+            Error::SerdeJson(_) => Self::Decode,
+            Error::Download(_) | Error::Upload(_) | Error::Cursor(_) => Self::Io,
+            Error::Cassette(_) | Error::Unmatched(_) => Self::Io,
+            Error::Stalled(_) => Self::Timeout,
+            Error::Cancelled => Self::Aborted,
+            Error::Context { source, .. } => Self::from(source.as_ref()),
+        }
+    }
+}
+
+/// How a request went
+#[derive(Clone, Debug)]
+pub struct Outcome {
+    /// The http status, if the server answered
+    pub status: Option<u16>,
+    /// From sending the request to reading the end of the response
+    pub duration: Duration,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub error: Option<ErrorClass>,
+}
+
+/// Hooks called around the requests of a client, to log them or collect metrics.
+///
+/// ```
+/// use pcloud::http::observer::{Observer, Outcome, Request};
+/// use pcloud::http::HttpClientBuilder;
+/// use std::sync::Arc;
+///
+/// struct SlowRequests;
+///
+/// impl Observer for SlowRequests {
+///     fn on_response(&self, request: &Request<'_>, outcome: &Outcome) {
+///         if outcome.duration.as_secs() > 5 {
+///             eprintln!("{} took {:?}", request.method, outcome.duration);
+///         }
+///     }
+/// }
+///
+/// let builder = HttpClientBuilder::from_env().observer(Arc::new(SlowRequests));
+/// ```
+pub trait Observer: Send + Sync {
+    /// Called before sending the request
+    fn on_request(&self, _request: &Request<'_>) {}
+
+    /// Called once the response has been read, or dropped
+    fn on_response(&self, request: &Request<'_>, outcome: &Outcome);
+}
+
+pub(crate) type Observers = Arc<Vec<Arc<dyn Observer>>>;
+
+/// Collects the outcome of a request and gives it to the observers when dropped
+pub(crate) struct Observation {
+    observers: Observers,
+    http_method: &'static str,
+    method: String,
+    started_at: Instant,
+    status: Option<u16>,
+    bytes_sent: u64,
+    bytes_received: u64,
+    error: Option<ErrorClass>,
+    done: bool,
+}
+
+impl Observation {
+    pub(crate) fn start(observers: &Observers, http_method: &'static str, method: &str) -> Self {
+        let request = Request {
+            http_method,
+            method,
+        };
+        for observer in observers.iter() {
+            observer.on_request(&request);
+        }
+        Self {
+            observers: observers.clone(),
+            http_method,
+            method: method.to_string(),
+            started_at: Instant::now(),
+            status: None,
+            bytes_sent: 0,
+            bytes_received: 0,
+            error: None,
+            done: false,
+        }
+    }
+
+    /// Whether someone looks at the outcome, to avoid computing it for nothing
+    pub(crate) fn is_active(&self) -> bool {
+        !self.observers.is_empty()
+    }
+
+    pub(crate) fn sent(&mut self, count: usize) {
+        self.bytes_sent += count as u64;
+    }
+
+    pub(crate) fn received(&mut self, count: usize) {
+        self.bytes_received += count as u64;
+    }
+
+    pub(crate) fn status(&mut self, value: reqwest::StatusCode) {
+        self.status = Some(value.as_u16());
+    }
+
+    pub(crate) fn error(&mut self, value: ErrorClass) {
+        self.error.get_or_insert(value);
+    }
+
+    /// Records the end of the request
+    pub(crate) fn finish<T>(&mut self, result: Result<T, Error>) -> Result<T, Error> {
+        if let Err(ref err) = result {
+            self.error(ErrorClass::from(err));
+        }
+        self.done = true;
+        result
+    }
+}
+
+impl Drop for Observation {
+    fn drop(&mut self) {
+        if self.observers.is_empty() {
+            return;
+        }
+        if !self.done {
+            self.error(ErrorClass::Aborted);
+        }
+        let request = Request {
+            http_method: self.http_method,
+            method: &self.method,
+        };
+        let outcome = Outcome {
+            status: self.status,
+            duration: self.started_at.elapsed(),
+            bytes_sent: self.bytes_sent,
+            bytes_received: self.bytes_received,
+            error: self.error,
+        };
+        for observer in self.observers.iter() {
+            observer.on_response(&request, &outcome);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ErrorClass, Observer, Outcome, Request};
+    use crate::credentials::Credentials;
+    use crate::folder::list::FolderListCommand;
+    use crate::http::HttpClientBuilder;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use crate::thumbnail::get_thumb::GetThumbCommand;
+    use crate::thumbnail::ThumbSize;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<(String, Outcome)>>);
+
+    impl Observer for Recorder {
+        fn on_response(&self, request: &Request<'_>, outcome: &Outcome) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((request.method.to_string(), outcome.clone()));
+        }
+    }
+
+    #[tokio::test]
+    async fn should_observe_requests() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m_list = server
+            .mock("GET", "/listfolder")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_body(r#"{ "result": 2005, "error": "Directory does not exist." }"#)
+            .create();
+        let m_thumb = server
+            .mock("GET", "/getthumb")
+            .match_query(mockito::Matcher::Any)
+            .with_status(200)
+            .with_header("content-type", "image/jpeg")
+            .with_body("not really an image")
+            .create();
+        let recorder = Arc::new(Recorder::default());
+        let client = HttpClientBuilder::default()
+            .credentials(Credentials::AccessToken("access-token".into()))
+            .region(Region::new(server.url()))
+            .observer(recorder.clone())
+            .build()
+            .unwrap();
+        let err = FolderListCommand::new(42.into())
+            .execute(&client)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, crate::error::Error::Protocol(2005, _)));
+        let mut output = Vec::new();
+        let size = GetThumbCommand::new(12.into(), ThumbSize::new(64, 64), &mut output)
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(size, 19);
+        m_list.assert();
+        m_thumb.assert();
+
+        let records = recorder.0.lock().unwrap();
+        let methods: Vec<_> = records.iter().map(|(method, _)| method.as_str()).collect();
+        assert_eq!(methods, vec!["listfolder", "getthumb"]);
+        assert_eq!(records[0].1.status, Some(200));
+        assert_eq!(records[0].1.error, Some(ErrorClass::Protocol(2005)));
+        assert_eq!(records[1].1.bytes_received, 19);
+        assert_eq!(records[1].1.error, None);
+    }
+}
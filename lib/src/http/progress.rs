@@ -0,0 +1,134 @@
+//! Polling of the jobs running in background on the server, like the imports or the extractions
+
+use crate::error::Error;
+use crate::http::HttpClient;
+use crate::prelude::HttpCommand;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+/// A job running on the server, whose progress is fetched with a command
+pub trait Job {
+    type Command: HttpCommand + Send + 'static;
+
+    /// The command fetching the current progress
+    fn command(&self) -> Self::Command;
+
+    /// Takes a progress into account
+    fn update(&mut self, progress: &<Self::Command as HttpCommand>::Output);
+
+    fn is_finished(&self) -> bool;
+}
+
+type Fetching<'a, T> = Pin<Box<dyn Future<Output = Result<T, Error>> + Send + 'a>>;
+
+enum State<'a, T> {
+    Ready,
+    Waiting(Pin<Box<tokio::time::Sleep>>),
+    Fetching(Fetching<'a, T>),
+    Done,
+}
+
+/// A [`Stream`](futures_core::Stream) of the progress of a [`Job`](Job), fetched every `interval`.
+///
+/// It ends when the job is finished or after an error.
+pub struct ProgressStream<'a, J: Job> {
+    client: &'a HttpClient,
+    interval: Duration,
+    job: J,
+    state: State<'a, <J::Command as HttpCommand>::Output>,
+}
+
+impl<'a, J: Job + Unpin> ProgressStream<'a, J> {
+    pub fn new(client: &'a HttpClient, interval: Duration, job: J) -> Self {
+        Self {
+            client,
+            interval,
+            job,
+            state: State::Ready,
+        }
+    }
+
+    /// Waits for the next progress of the job.
+    pub async fn next(&mut self) -> Option<Result<<J::Command as HttpCommand>::Output, Error>> {
+        std::future::poll_fn(|cx| futures_core::Stream::poll_next(Pin::new(&mut *self), cx)).await
+    }
+}
+
+impl<'a, J: Job + Unpin> futures_core::Stream for ProgressStream<'a, J> {
+    type Item = Result<<J::Command as HttpCommand>::Output, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match this.state {
+                State::Done => return Poll::Ready(None),
+                State::Ready if this.job.is_finished() => this.state = State::Done,
+                State::Ready => {
+                    let fetching = this.job.command().execute(this.client);
+                    this.state = State::Fetching(fetching);
+                }
+                State::Waiting(ref mut sleep) => {
+                    if sleep.as_mut().poll(cx).is_pending() {
+                        return Poll::Pending;
+                    }
+                    this.state = State::Ready;
+                }
+                State::Fetching(ref mut fetching) => {
+                    let Poll::Ready(result) = fetching.as_mut().poll(cx) else {
+                        return Poll::Pending;
+                    };
+                    this.state = match result {
+                        Ok(ref progress) => {
+                            this.job.update(progress);
+                            if this.job.is_finished() {
+                                State::Done
+                            } else {
+                                State::Waiting(Box::pin(tokio::time::sleep(this.interval)))
+                            }
+                        }
+                        Err(_) => State::Done,
+                    };
+                    return Poll::Ready(Some(result));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::credentials::Credentials;
+    use crate::error::Error;
+    use crate::file::import::ImportJob;
+    use crate::http::HttpClient;
+    use crate::region::Region;
+    use mockito::Matcher;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn should_end_after_error() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/uploadprogress")
+            .match_query(Matcher::UrlEncoded("progresshash".into(), "abcdef".into()))
+            .with_status(200)
+            .with_body(r#"{ "result": 1900, "error": "Invalid progress hash." }"#)
+            .expect(1)
+            .create();
+        let creds = Credentials::access_token("access-token");
+        let client = HttpClient::new(creds, Region::new(server.url()));
+        let job = ImportJob {
+            progress_hash: "abcdef".into(),
+        };
+        let mut progress = job.progress(&client, Duration::from_millis(1));
+        assert!(matches!(
+            progress.next().await,
+            Some(Err(Error::Protocol(1900, _)))
+        ));
+        assert!(progress.next().await.is_none());
+        m.assert();
+    }
+}
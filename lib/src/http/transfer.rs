@@ -0,0 +1,379 @@
+//! Supervision of the transfers, to stop the ones that are cancelled or stalled
+//!
+//! The total `timeout` of the [`HttpClient`](crate::http::HttpClient) only applies to the api calls.
+//! A download or an upload can last as long as data keeps flowing, within the [`TransferLimits`](TransferLimits):
+//! it's aborted with [`Error::Stalled`](crate::error::Error::Stalled) when no data is transferred
+//! for the idle timeout, when its throughput stays below the minimum or when it exceeds its total timeout.
+//! The idle timeout and the throughput are not checked while the transfer waits for the bandwidth
+//! limit of the client, only the total timeout is.
+
+use crate::cancellation::CancellationToken;
+use crate::error::Error;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::Poll;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// The size of the pieces of an uploaded body, to follow the progress of the upload
+const PIECE_SIZE: usize = 65536;
+
+/// A minimal amount of bytes to transfer over a sliding window
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Throughput {
+    pub bytes_per_second: u64,
+    pub window: Duration,
+}
+
+impl Throughput {
+    /// The default window, long enough to ignore the short slowdowns
+    pub const DEFAULT_WINDOW: Duration = Duration::from_secs(30);
+
+    pub fn new(bytes_per_second: u64) -> Self {
+        Self {
+            bytes_per_second,
+            window: Self::DEFAULT_WINDOW,
+        }
+    }
+
+    pub fn window(mut self, value: Duration) -> Self {
+        self.window = value;
+        self
+    }
+
+    fn minimum(&self) -> u64 {
+        (self.bytes_per_second as f64 * self.window.as_secs_f64()) as u64
+    }
+}
+
+/// The limits applied to every download and upload
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransferLimits {
+    /// Maximum duration without transferring any data
+    pub idle_timeout: Option<Duration>,
+    /// Maximum duration of a transfer
+    pub total_timeout: Option<Duration>,
+    /// Minimal throughput, once the first window is elapsed
+    pub min_throughput: Option<Throughput>,
+}
+
+impl TransferLimits {
+    fn is_empty(&self) -> bool {
+        self.idle_timeout.is_none() && self.total_timeout.is_none() && self.min_throughput.is_none()
+    }
+
+    /// The delay between two checks, small enough to detect a stall quickly
+    fn period(&self) -> Duration {
+        [
+            self.idle_timeout,
+            self.total_timeout,
+            self.min_throughput.map(|value| value.window),
+        ]
+        .iter()
+        .flatten()
+        .map(|value| *value / 4)
+        .min()
+        .unwrap_or(Duration::from_secs(1))
+        .clamp(Duration::from_millis(10), Duration::from_secs(1))
+    }
+}
+
+/// Why a transfer has been aborted
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum Stall {
+    #[error("no data transferred for {0:?}")]
+    Idle(Duration),
+    #[error("less than {} bytes transferred per second over {:?}", .0.bytes_per_second, .0.window)]
+    Throughput(Throughput),
+    #[error("transfer longer than {0:?}")]
+    Total(Duration),
+}
+
+#[derive(Debug)]
+struct ProgressInner {
+    started: Instant,
+    bytes: AtomicU64,
+    /// Milliseconds between the start and the last transferred data
+    last: AtomicU64,
+    /// Number of pending waits for the bandwidth limit
+    throttled: AtomicUsize,
+    /// Milliseconds between the start and the end of the last wait for the bandwidth limit
+    resumed: AtomicU64,
+}
+
+/// Resumes the supervision when a wait for the bandwidth limit ends or is dropped
+struct Throttled<'a>(&'a ProgressInner);
+
+impl Drop for Throttled<'_> {
+    fn drop(&mut self) {
+        let elapsed = self.0.started.elapsed().as_millis() as u64;
+        self.0.last.store(elapsed, Ordering::Relaxed);
+        self.0.resumed.store(elapsed, Ordering::Relaxed);
+        self.0.throttled.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// The amount of data transferred, shared between a transfer and its supervision
+#[derive(Clone, Debug)]
+pub(crate) struct Progress(Arc<ProgressInner>);
+
+impl Progress {
+    pub fn new() -> Self {
+        Self(Arc::new(ProgressInner {
+            started: Instant::now(),
+            bytes: AtomicU64::new(0),
+            last: AtomicU64::new(0),
+            throttled: AtomicUsize::new(0),
+            resumed: AtomicU64::new(0),
+        }))
+    }
+
+    /// Waits for the bandwidth limit, the transfer not being considered as stalled meanwhile
+    pub async fn throttle<F: Future>(&self, wait: F) -> F::Output {
+        self.0.throttled.fetch_add(1, Ordering::SeqCst);
+        let _throttled = Throttled(&self.0);
+        wait.await
+    }
+
+    fn is_throttled(&self) -> bool {
+        self.0.throttled.load(Ordering::SeqCst) > 0
+    }
+
+    fn resumed(&self) -> Instant {
+        self.0.started + Duration::from_millis(self.0.resumed.load(Ordering::Relaxed))
+    }
+
+    pub fn add(&self, size: usize) {
+        if size == 0 {
+            return;
+        }
+        let elapsed = self.0.started.elapsed().as_millis() as u64;
+        self.0.bytes.fetch_add(size as u64, Ordering::Relaxed);
+        self.0.last.store(elapsed, Ordering::Relaxed);
+    }
+
+    fn bytes(&self) -> u64 {
+        self.0.bytes.load(Ordering::Relaxed)
+    }
+
+    fn idle(&self) -> Duration {
+        let last = Duration::from_millis(self.0.last.load(Ordering::Relaxed));
+        self.0.started.elapsed().saturating_sub(last)
+    }
+
+    /// Splits the data in pieces, counted when the client reads them to send them
+    ///
+    /// The body has no known size, the `Content-Length` header should be set by the caller.
+    pub fn body(&self, data: Vec<u8>) -> reqwest::Body {
+        let progress = self.clone();
+        let data = bytes::Bytes::from(data);
+        let pieces = (0..data.len()).step_by(PIECE_SIZE).map(move |offset| {
+            let piece = data.slice(offset..(offset + PIECE_SIZE).min(data.len()));
+            progress.add(piece.len());
+            Ok::<_, std::io::Error>(piece)
+        });
+        reqwest::Body::wrap_stream(Pieces(pieces))
+    }
+
+    /// Completes when the transfer exceeds one of the limits
+    async fn watch(&self, limits: TransferLimits) -> Stall {
+        if limits.is_empty() {
+            return std::future::pending().await;
+        }
+        let period = limits.period();
+        // the amount of bytes transferred at each check, over the throughput window
+        let mut history: std::collections::VecDeque<(Instant, u64)> = Default::default();
+        loop {
+            tokio::time::sleep(period).await;
+            let now = Instant::now();
+            if let Some(timeout) = limits.total_timeout {
+                if now.duration_since(self.0.started) >= timeout {
+                    return Stall::Total(timeout);
+                }
+            }
+            if self.is_throttled() {
+                continue;
+            }
+            if let Some(timeout) = limits.idle_timeout {
+                if self.idle() >= timeout {
+                    return Stall::Idle(timeout);
+                }
+            }
+            if let Some(throughput) = limits.min_throughput {
+                let bytes = self.bytes();
+                history.push_back((now, bytes));
+                let Some(start) = now.checked_sub(throughput.window) else {
+                    continue;
+                };
+                // the window should not include a wait for the bandwidth limit
+                if start < self.resumed() {
+                    continue;
+                }
+                while history.len() > 1 && history[1].0 <= start {
+                    history.pop_front();
+                }
+                let before = if history[0].0 <= start {
+                    history[0].1
+                } else {
+                    // the first check happened after the beginning of the window
+                    0
+                };
+                if bytes - before < throughput.minimum() {
+                    return Stall::Throughput(throughput);
+                }
+            }
+        }
+    }
+}
+
+/// Adapts an iterator of pieces to the stream expected by reqwest
+struct Pieces<I>(I);
+
+impl<I> futures_core::Stream for Pieces<I>
+where
+    I: Iterator<Item = Result<bytes::Bytes, std::io::Error>> + Unpin,
+{
+    type Item = I::Item;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.0.next())
+    }
+}
+
+/// Runs a transfer until its end, its cancellation or its stall
+pub(crate) async fn supervise<T, F>(
+    limits: TransferLimits,
+    token: Option<&CancellationToken>,
+    progress: &Progress,
+    transfer: F,
+) -> Result<T, Error>
+where
+    F: Future<Output = Result<T, Error>>,
+{
+    let mut transfer = pin!(transfer);
+    let mut cancelled = pin!(async {
+        match token {
+            Some(token) => token.cancelled().await,
+            None => std::future::pending().await,
+        }
+    });
+    let mut watch = pin!(progress.watch(limits));
+    std::future::poll_fn(|cx| {
+        if cancelled.as_mut().poll(cx).is_ready() {
+            tracing::debug!("transfer cancelled");
+            return Poll::Ready(Err(Error::Cancelled));
+        }
+        if let Poll::Ready(stall) = watch.as_mut().poll(cx) {
+            tracing::warn!("transfer aborted: {stall}");
+            return Poll::Ready(Err(Error::Stalled(stall)));
+        }
+        transfer.as_mut().poll(cx)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{supervise, Progress, Stall, Throughput, TransferLimits};
+    use crate::cancellation::CancellationToken;
+    use crate::error::Error;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn should_cancel() {
+        let token = CancellationToken::new();
+        let progress = Progress::new();
+        let other = token.clone();
+        let result: Result<(), Error> =
+            supervise(TransferLimits::default(), Some(&token), &progress, async {
+                other.cancel();
+                std::future::pending().await
+            })
+            .await;
+        assert!(matches!(result, Err(Error::Cancelled)));
+    }
+
+    #[tokio::test]
+    async fn should_detect_idle_transfer() {
+        let limits = TransferLimits {
+            idle_timeout: Some(Duration::from_millis(50)),
+            ..Default::default()
+        };
+        let progress = Progress::new();
+        let result: Result<(), Error> = supervise(limits, None, &progress, async {
+            progress.add(10);
+            std::future::pending().await
+        })
+        .await;
+        assert!(matches!(
+            result,
+            Err(Error::Stalled(Stall::Idle(value))) if value == Duration::from_millis(50)
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_detect_slow_transfer() {
+        let throughput = Throughput::new(1000).window(Duration::from_millis(100));
+        let limits = TransferLimits {
+            idle_timeout: Some(Duration::from_secs(10)),
+            min_throughput: Some(throughput),
+            ..Default::default()
+        };
+        let progress = Progress::new();
+        let result: Result<(), Error> = supervise(limits, None, &progress, async {
+            // 10 bytes every 10ms is 1000 bytes per second, then 1 byte every 10ms
+            for index in 0..100 {
+                progress.add(if index < 10 { 10 } else { 1 });
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            Ok(())
+        })
+        .await;
+        assert!(matches!(
+            result,
+            Err(Error::Stalled(Stall::Throughput(value))) if value == throughput
+        ));
+    }
+
+    #[tokio::test]
+    async fn should_not_stall_while_throttled() {
+        let limits = TransferLimits {
+            idle_timeout: Some(Duration::from_millis(50)),
+            ..Default::default()
+        };
+        let progress = Progress::new();
+        let result = supervise(limits, None, &progress, async {
+            progress
+                .throttle(tokio::time::sleep(Duration::from_millis(200)))
+                .await;
+            progress.add(10);
+            Ok(42)
+        })
+        .await;
+        assert_eq!(result.unwrap(), 42);
+    }
+
+    #[tokio::test]
+    async fn should_complete_transfer_within_limits() {
+        let limits = TransferLimits {
+            idle_timeout: Some(Duration::from_millis(100)),
+            total_timeout: Some(Duration::from_secs(1)),
+            min_throughput: None,
+        };
+        let progress = Progress::new();
+        let result = supervise(limits, None, &progress, async {
+            for _ in 0..5 {
+                progress.add(10);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            Ok(42)
+        })
+        .await;
+        assert_eq!(result.unwrap(), 42);
+    }
+}
@@ -1,27 +1,49 @@
 /// Utilities for parsing dates
 mod date;
+/// Utilities for parsing numbers
+mod number;
 /// The traits for implementing the commands
 pub mod prelude;
 /// The module to handle the responses from the server
 pub mod request;
 
+/// A synchronous client wrapping the [`HttpClient`](crate::http::HttpClient)
+#[cfg(feature = "client-blocking")]
+pub mod blocking;
 #[cfg(feature = "client-http")]
 pub mod http;
 
+/// Cooperative cancellation of the commands
+pub mod cancellation;
 pub mod credentials;
 pub mod region;
+/// Resolution of remote paths into ids, with a cache
+#[cfg(feature = "client-http")]
+pub mod resolver;
+
+/// An in-memory pCloud server to test without credentials
+#[cfg(any(all(test, feature = "client-http"), feature = "testing"))]
+pub mod testing;
 
 pub mod entry;
 pub mod error;
 
+/// The [archiving commands](https://docs.pcloud.com/methods/archiving/) from the PCloud documentation
+pub mod archive;
 /// The [file commands](https://docs.pcloud.com/methods/file/) from the PCloud documentation
 pub mod file;
+/// The [file operations commands](https://docs.pcloud.com/methods/fileops/) from the PCloud documentation
+pub mod fileops;
 /// The [folder commands](https://docs.pcloud.com/methods/folder/) from the PCloud documentation
 pub mod folder;
 /// The [general commands](https://docs.pcloud.com/methods/general/) from the PCloud documentation
 pub mod general;
+/// The [sharing commands](https://docs.pcloud.com/methods/sharing/) from the PCloud documentation
+pub mod sharing;
 /// The [streaming commands](https://docs.pcloud.com/methods/streaming/) from the PCloud documentation
 pub mod streaming;
+/// The [thumbnails commands](https://docs.pcloud.com/methods/thumbnails/) from the PCloud documentation
+pub mod thumbnail;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,59 @@
+// pCloud is sometimes sending decimal values as strings
+// "fps": "29.97", "duration": "60.06"
+
+/// Parses an optional decimal value sent either as a number or as a string
+pub mod optional_float {
+    use serde::{self, Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Value {
+        Number(f64),
+        Text(String),
+    }
+
+    pub fn serialize<S>(value: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(inner) => serializer.serialize_f64(*inner),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match Option::<Value>::deserialize(deserializer)? {
+            Some(Value::Number(inner)) => Ok(Some(inner)),
+            Some(Value::Text(inner)) if inner.is_empty() => Ok(None),
+            Some(Value::Text(inner)) => inner
+                .parse::<f64>()
+                .map(Some)
+                .map_err(serde::de::Error::custom),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[derive(Debug, serde::Deserialize)]
+    struct Item {
+        #[serde(default, with = "super::optional_float")]
+        value: Option<f64>,
+    }
+
+    #[test]
+    fn parse_optional_float() {
+        let item: Item = serde_json::from_str(r#"{"value":"29.97"}"#).unwrap();
+        assert_eq!(item.value, Some(29.97));
+        let item: Item = serde_json::from_str(r#"{"value":25}"#).unwrap();
+        assert_eq!(item.value, Some(25.0));
+        let item: Item = serde_json::from_str(r#"{}"#).unwrap();
+        assert_eq!(item.value, None);
+        assert!(serde_json::from_str::<Item>(r#"{"value":"foo"}"#).is_err());
+    }
+}
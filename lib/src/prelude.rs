@@ -1,7 +1,41 @@
-#[cfg(feature = "client-http")]
+#[cfg(feature = "client-blocking")]
+use crate::blocking::BlockingHttpClient;
 use crate::error::Error;
 #[cfg(feature = "client-http")]
 use crate::http::HttpClient;
+use crate::request::Request;
+
+/// A command that describes its request and parses its response, without doing any IO.
+///
+/// Any http stack can execute it: build the request from [`Command::request`], add the
+/// credentials, send it and give the body of the response to [`Command::parse`].
+///
+/// ```
+/// use pcloud::folder::create::FolderCreateCommand;
+/// use pcloud::prelude::Command;
+///
+/// let cmd = FolderCreateCommand::new("foo".into(), 0);
+/// let request = cmd.request();
+/// assert_eq!(request.http_method(), "GET");
+/// assert_eq!(request.method, "createfolder");
+///
+/// let body = br#"{ "result": 2004, "error": "File or folder alredy exists." }"#;
+/// assert!(cmd.parse(body).is_err());
+/// ```
+pub trait Command {
+    type Output;
+
+    /// The output when there is no need to send a request, like when uploading no file
+    fn immediate(&self) -> Option<Self::Output> {
+        None
+    }
+
+    /// Describes the request to send
+    fn request(&self) -> Request<'_>;
+
+    /// Parses the body of the response
+    fn parse(&self, body: &[u8]) -> Result<Self::Output, Error>;
+}
 
 #[cfg(feature = "client-http")]
 #[async_trait::async_trait]
@@ -10,3 +44,36 @@ pub trait HttpCommand {
 
     async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error>;
 }
+
+#[cfg(feature = "client-http")]
+#[async_trait::async_trait]
+impl<C> HttpCommand for C
+where
+    C: Command + Send + Sync,
+    <C as Command>::Output: Send,
+{
+    type Output = <C as Command>::Output;
+
+    async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error> {
+        client.send_command(&self).await
+    }
+}
+
+/// A command executed with the [`BlockingHttpClient`](crate::blocking::BlockingHttpClient)
+///
+/// Every [`HttpCommand`](HttpCommand) implements it.
+#[cfg(feature = "client-blocking")]
+pub trait BlockingCommand {
+    type Output;
+
+    fn execute(self, client: &BlockingHttpClient) -> Result<Self::Output, Error>;
+}
+
+#[cfg(feature = "client-blocking")]
+impl<C: HttpCommand> BlockingCommand for C {
+    type Output = <C as HttpCommand>::Output;
+
+    fn execute(self, client: &BlockingHttpClient) -> Result<Self::Output, Error> {
+        client.block_on(HttpCommand::execute(self, client.http_client()))
+    }
+}
@@ -1,3 +1,4 @@
+use crate::credentials::Secret;
 use crate::error::Error;
 
 pub const ROOT_FOLDER: usize = 0;
@@ -24,3 +25,79 @@ impl<T> Response<T> {
         }
     }
 }
+
+/// Decodes the body of a response and returns its payload, or the error returned by the server
+pub fn parse<T: serde::de::DeserializeOwned>(body: &[u8]) -> Result<T, Error> {
+    serde_json::from_slice::<Response<T>>(body)?.payload()
+}
+
+/// A request to the pCloud API, described independently of the http stack sending it.
+///
+/// The credentials are not part of the request, they are added by the client executing it.
+#[derive(Debug)]
+pub struct Request<'a> {
+    /// The name of the pCloud method, like `listfolder`
+    pub method: &'a str,
+    /// The parameters, sent in the query string
+    pub params: Vec<(&'a str, String)>,
+    pub body: Body<'a>,
+}
+
+impl<'a> Request<'a> {
+    pub fn new(method: &'a str, params: Vec<(&'a str, String)>) -> Self {
+        Self {
+            method,
+            params,
+            body: Body::Empty,
+        }
+    }
+
+    pub fn body(mut self, value: Body<'a>) -> Self {
+        self.body = value;
+        self
+    }
+
+    /// The http method expected by the API for this kind of body
+    pub fn http_method(&self) -> &'static str {
+        match self.body {
+            Body::Empty => "GET",
+            Body::Data(_) => "PUT",
+            Body::Multipart(_) | Body::Form(_) => "POST",
+        }
+    }
+}
+
+/// The content sent with a request
+#[derive(Debug)]
+pub enum Body<'a> {
+    Empty,
+    /// Raw data, sent with a `PUT`
+    Data(&'a [u8]),
+    /// Files, sent as a multipart form with a `POST`
+    Multipart(Vec<Part<'a>>),
+    /// Secret parameters, sent as a form with a `POST` to keep them out of the url
+    Form(Vec<(&'a str, &'a Secret)>),
+}
+
+impl Body<'_> {
+    /// The number of bytes of content
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Empty | Self::Form(_) => 0,
+            Self::Data(data) => data.len(),
+            Self::Multipart(parts) => parts.iter().map(|part| part.data.len()).sum(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A file of a multipart form
+#[derive(Debug)]
+pub struct Part<'a> {
+    pub name: String,
+    pub filename: &'a str,
+    pub data: &'a [u8],
+}
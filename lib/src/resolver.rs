@@ -0,0 +1,792 @@
+//! Resources needed to resolve remote paths into folder and file ids
+//!
+//! Resolving a path costs one folder listing per level, so the resolved ids are kept
+//! in a least recently used cache with a time to live.
+
+use crate::error::Error;
+use crate::file::copy::FileCopyCommand;
+use crate::file::delete::FileDeleteCommand;
+use crate::file::rename::{FileMoveCommand, FileRenameCommand};
+use crate::file::upload::{FileUploadCommand, MultipartFileUploadCommand};
+use crate::file::FileIdentifier;
+use crate::folder::create::FolderCreateCommand;
+use crate::folder::delete::FolderDeleteCommand;
+use crate::folder::list::FolderListCommand;
+use crate::folder::rename::{FolderMoveCommand, FolderRenameCommand};
+use crate::folder::{FolderIdentifier, ROOT};
+use crate::general::diff::Event;
+use crate::http::HttpClient;
+use crate::prelude::HttpCommand;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Default number of paths kept in cache
+pub const DEFAULT_CAPACITY: usize = 1024;
+/// Default duration during which a resolved path is trusted
+pub const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+/// The id a path resolves to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resolved {
+    File(u64),
+    Folder(u64),
+}
+
+impl Resolved {
+    pub fn file_id(&self) -> Option<u64> {
+        match self {
+            Self::File(id) => Some(*id),
+            Self::Folder(_) => None,
+        }
+    }
+
+    pub fn folder_id(&self) -> Option<u64> {
+        match self {
+            Self::File(_) => None,
+            Self::Folder(id) => Some(*id),
+        }
+    }
+}
+
+/// Describes the part of the cache made stale by a change
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Invalidation {
+    /// The entry at the given path and everything below it
+    Path(String),
+    /// The folder with the given id and everything below it
+    Folder(u64),
+    /// The file with the given id
+    File(u64),
+    /// The entry with the given name in the folder with the given id
+    Child { parent_id: u64, name: String },
+    /// Everything contained in the folder with the given id
+    Content(u64),
+}
+
+impl From<&FileIdentifier> for Invalidation {
+    fn from(value: &FileIdentifier) -> Self {
+        match value {
+            FileIdentifier::Path(path) => Self::Path(path.clone()),
+            FileIdentifier::FileId(id) => Self::File(*id),
+        }
+    }
+}
+
+impl From<&FolderIdentifier> for Invalidation {
+    fn from(value: &FolderIdentifier) -> Self {
+        match value {
+            FolderIdentifier::Path(path) => Self::Path(path.clone()),
+            FolderIdentifier::FolderId(id) => Self::Folder(*id),
+        }
+    }
+}
+
+/// A command changing the tree, with the parts of the cache it makes stale
+pub trait Mutation {
+    fn invalidations(&self) -> Vec<Invalidation>;
+}
+
+impl Mutation for FolderCreateCommand {
+    fn invalidations(&self) -> Vec<Invalidation> {
+        vec![Invalidation::Child {
+            parent_id: self.parent_id,
+            name: self.name.clone(),
+        }]
+    }
+}
+
+impl Mutation for FolderDeleteCommand {
+    fn invalidations(&self) -> Vec<Invalidation> {
+        vec![Invalidation::from(&self.identifier)]
+    }
+}
+
+impl Mutation for FolderRenameCommand {
+    fn invalidations(&self) -> Vec<Invalidation> {
+        vec![Invalidation::Folder(self.identifier)]
+    }
+}
+
+impl Mutation for FolderMoveCommand {
+    fn invalidations(&self) -> Vec<Invalidation> {
+        vec![Invalidation::Folder(self.folder)]
+    }
+}
+
+impl Mutation for FileDeleteCommand {
+    fn invalidations(&self) -> Vec<Invalidation> {
+        vec![Invalidation::from(&self.identifier)]
+    }
+}
+
+impl Mutation for FileRenameCommand {
+    fn invalidations(&self) -> Vec<Invalidation> {
+        vec![Invalidation::from(&self.identifier)]
+    }
+}
+
+impl Mutation for FileMoveCommand {
+    fn invalidations(&self) -> Vec<Invalidation> {
+        vec![Invalidation::from(&self.from), Invalidation::from(&self.to)]
+    }
+}
+
+impl Mutation for FileCopyCommand {
+    fn invalidations(&self) -> Vec<Invalidation> {
+        // the copy can replace a file with the same name in the target folder
+        vec![Invalidation::Content(self.to_folder_id)]
+    }
+}
+
+impl Mutation for MultipartFileUploadCommand {
+    fn invalidations(&self) -> Vec<Invalidation> {
+        self.entries
+            .iter()
+            .map(|(name, _)| Invalidation::Child {
+                parent_id: self.folder_id,
+                name: name.clone(),
+            })
+            .collect()
+    }
+}
+
+impl<'a, R: Read + Send> Mutation for FileUploadCommand<'a, R> {
+    fn invalidations(&self) -> Vec<Invalidation> {
+        vec![Invalidation::Child {
+            parent_id: self.folder_id,
+            name: self.filename.to_string(),
+        }]
+    }
+}
+
+fn not_found_folder() -> Error {
+    Error::Protocol(2005, "Directory does not exist.".into())
+}
+
+fn not_found_file() -> Error {
+    Error::Protocol(2009, "File not found.".into())
+}
+
+fn already_exists() -> Error {
+    Error::Protocol(2004, "File or folder already exists.".into())
+}
+
+fn components(path: &str) -> Vec<&str> {
+    path.split('/').filter(|item| !item.is_empty()).collect()
+}
+
+fn to_key(names: &[&str]) -> String {
+    format!("/{}", names.join("/"))
+}
+
+fn normalize(path: &str) -> String {
+    to_key(&components(path))
+}
+
+fn is_below(key: &str, parent: &str) -> bool {
+    key == parent
+        || (key.starts_with(parent)
+            && (parent.ends_with('/') || key.as_bytes().get(parent.len()) == Some(&b'/')))
+}
+
+struct Slot {
+    target: Resolved,
+    expires_at: Instant,
+    last_used: u64,
+}
+
+/// A least recently used cache with entries expiring after a time to live
+struct Cache {
+    capacity: usize,
+    ttl: Duration,
+    counter: u64,
+    slots: HashMap<String, Slot>,
+}
+
+impl Cache {
+    fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            counter: 0,
+            slots: HashMap::new(),
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<Resolved> {
+        if key == "/" {
+            return Some(Resolved::Folder(ROOT));
+        }
+        self.counter += 1;
+        let now = Instant::now();
+        let slot = self.slots.get_mut(key)?;
+        if slot.expires_at <= now {
+            self.slots.remove(key);
+            return None;
+        }
+        slot.last_used = self.counter;
+        Some(slot.target)
+    }
+
+    fn insert(&mut self, key: String, target: Resolved) {
+        if self.capacity == 0 {
+            return;
+        }
+        if !self.slots.contains_key(&key) && self.slots.len() >= self.capacity {
+            self.evict();
+        }
+        self.counter += 1;
+        self.slots.insert(
+            key,
+            Slot {
+                target,
+                expires_at: Instant::now() + self.ttl,
+                last_used: self.counter,
+            },
+        );
+    }
+
+    fn evict(&mut self) {
+        let now = Instant::now();
+        self.slots.retain(|_, slot| slot.expires_at > now);
+        if self.slots.len() < self.capacity {
+            return;
+        }
+        let oldest = self
+            .slots
+            .iter()
+            .min_by_key(|(_, slot)| slot.last_used)
+            .map(|(key, _)| key.clone());
+        if let Some(key) = oldest {
+            self.slots.remove(&key);
+        }
+    }
+
+    fn keys_of(&self, target: Resolved) -> Vec<String> {
+        self.slots
+            .iter()
+            .filter(|(_, slot)| slot.target == target)
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    fn remove_below(&mut self, parent: &str, include_parent: bool) {
+        self.slots
+            .retain(|key, _| !is_below(key, parent) || (!include_parent && key == parent));
+    }
+
+    fn invalidate(&mut self, invalidation: &Invalidation) {
+        match invalidation {
+            Invalidation::Path(path) => self.remove_below(&normalize(path), true),
+            Invalidation::Folder(ROOT) => self.slots.clear(),
+            Invalidation::Folder(id) => {
+                for key in self.keys_of(Resolved::Folder(*id)) {
+                    self.remove_below(&key, true);
+                }
+            }
+            Invalidation::File(id) => {
+                for key in self.keys_of(Resolved::File(*id)) {
+                    self.slots.remove(&key);
+                }
+            }
+            Invalidation::Child { parent_id, name } => {
+                let parents = if *parent_id == ROOT {
+                    vec!["/".to_string()]
+                } else {
+                    self.keys_of(Resolved::Folder(*parent_id))
+                };
+                for parent in parents {
+                    let key = format!("{}/{name}", parent.trim_end_matches('/'));
+                    self.remove_below(&key, true);
+                }
+            }
+            Invalidation::Content(ROOT) => self.slots.clear(),
+            Invalidation::Content(id) => {
+                for key in self.keys_of(Resolved::Folder(*id)) {
+                    self.remove_below(&key, false);
+                }
+            }
+        }
+    }
+}
+
+/// Resolves remote paths into folder and file ids, keeping the results in cache.
+///
+/// The cache is only aware of the changes made through [`PathResolver::execute`].
+/// The changes made by other clients can be applied with [`PathResolver::apply_event`]
+/// using the events from the [`diff`](crate::general::diff) command, otherwise they
+/// are picked up once the entries expire.
+///
+/// ```no_run
+/// use pcloud::file::delete::FileDeleteCommand;
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::resolver::PathResolver;
+/// use std::time::Duration;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let resolver = PathResolver::new(client)
+///     .capacity(512)
+///     .ttl(Duration::from_secs(30));
+/// let folder_id = resolver.create_dir_all("/photos/2021/summer").await.unwrap();
+/// let file_id = resolver.resolve_file("/photos/2021/cover.jpg").await.unwrap();
+/// resolver.execute(FileDeleteCommand::new(file_id.into())).await.unwrap();
+/// # })
+/// ```
+pub struct PathResolver {
+    client: HttpClient,
+    cache: Mutex<Cache>,
+}
+
+impl std::fmt::Debug for PathResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let cache = self.lock();
+        f.debug_struct("PathResolver")
+            .field("capacity", &cache.capacity)
+            .field("ttl", &cache.ttl)
+            .field("len", &cache.slots.len())
+            .finish()
+    }
+}
+
+impl PathResolver {
+    pub fn new(client: HttpClient) -> Self {
+        Self {
+            client,
+            cache: Mutex::new(Cache::new(DEFAULT_CAPACITY, DEFAULT_TTL)),
+        }
+    }
+
+    /// Maximum number of paths kept in cache
+    pub fn capacity(self, value: usize) -> Self {
+        self.lock().capacity = value;
+        self
+    }
+
+    /// Duration during which a resolved path is trusted
+    pub fn ttl(self, value: Duration) -> Self {
+        self.lock().ttl = value;
+        self
+    }
+
+    pub fn client(&self) -> &HttpClient {
+        &self.client
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Cache> {
+        // the cache is always left in a consistent state, a poisoned lock can be reused
+        self.cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn cached(&self, names: &[&str]) -> Option<Resolved> {
+        self.lock().get(&to_key(names))
+    }
+
+    /// Lists the folder and keeps all its children in cache
+    async fn fetch(&self, names: &[&str], folder_id: u64) -> Result<(), Error> {
+        let folder = FolderListCommand::new(folder_id.into())
+            .execute(&self.client)
+            .await?;
+        let mut cache = self.lock();
+        cache.remove_below(&to_key(names), false);
+        for entry in folder.contents.unwrap_or_default() {
+            let target = match (entry.folder_id(), entry.file_id()) {
+                (Some(id), _) => Resolved::Folder(id),
+                (None, Some(id)) => Resolved::File(id),
+                (None, None) => continue,
+            };
+            let mut child = names.to_vec();
+            child.push(entry.base().name.as_str());
+            cache.insert(to_key(&child), target);
+        }
+        Ok(())
+    }
+
+    /// Walks the path from its deepest cached ancestor.
+    ///
+    /// Returns `None` when the last component doesn't exist and is not created.
+    async fn walk(&self, path: &str, create: bool) -> Result<Option<Resolved>, Error> {
+        let names = components(path);
+        if let Some(found) = self.cached(&names) {
+            return Ok(Some(found));
+        }
+        let (mut depth, mut current) = (0, ROOT);
+        // a folder created during the walk is known to be empty
+        let mut created = false;
+        for index in (1..names.len()).rev() {
+            if let Some(Resolved::Folder(id)) = self.cached(&names[..index]) {
+                depth = index;
+                current = id;
+                break;
+            }
+        }
+        while depth < names.len() {
+            let child = &names[..=depth];
+            let found = match self.cached(child) {
+                Some(found) => Some(found),
+                None if created => None,
+                None => {
+                    self.fetch(&names[..depth], current).await?;
+                    self.cached(child)
+                }
+            };
+            let is_last = depth + 1 == names.len();
+            let found = match found {
+                Some(found) => found,
+                None if create => {
+                    let folder = FolderCreateCommand::new(names[depth].to_string(), current)
+                        .ignore_exists(true)
+                        .execute(&self.client)
+                        .await?;
+                    let found = Resolved::Folder(folder.folder_id);
+                    self.lock().insert(to_key(child), found);
+                    created = true;
+                    found
+                }
+                None if is_last => return Ok(None),
+                None => return Err(not_found_folder()),
+            };
+            if is_last {
+                return Ok(Some(found));
+            }
+            current = match found {
+                Resolved::Folder(id) => id,
+                Resolved::File(_) if create => return Err(already_exists()),
+                Resolved::File(_) => return Err(not_found_folder()),
+            };
+            depth += 1;
+        }
+        Ok(Some(Resolved::Folder(current)))
+    }
+
+    /// Resolves the path, returns `None` if nothing exists at this path
+    pub async fn resolve(&self, path: &str) -> Result<Option<Resolved>, Error> {
+        self.walk(path, false).await
+    }
+
+    /// Resolves the path of a folder into its id
+    pub async fn resolve_folder(&self, path: &str) -> Result<u64, Error> {
+        match self.walk(path, false).await? {
+            Some(Resolved::Folder(id)) => Ok(id),
+            _ => Err(not_found_folder()),
+        }
+    }
+
+    /// Resolves the path of a file into its id
+    pub async fn resolve_file(&self, path: &str) -> Result<u64, Error> {
+        match self.walk(path, false).await? {
+            Some(Resolved::File(id)) => Ok(id),
+            _ => Err(not_found_file()),
+        }
+    }
+
+    /// Resolves the path of a folder, creating it and all its missing parents, and returns its id
+    pub async fn create_dir_all(&self, path: &str) -> Result<u64, Error> {
+        match self.walk(path, true).await? {
+            Some(Resolved::Folder(id)) => Ok(id),
+            _ => Err(already_exists()),
+        }
+    }
+
+    /// Executes the command and invalidates the paths it changes, even if it fails
+    pub async fn execute<C>(&self, command: C) -> Result<C::Output, Error>
+    where
+        C: HttpCommand + Mutation + Send,
+    {
+        let invalidations = command.invalidations();
+        let result = command.execute(&self.client).await;
+        let mut cache = self.lock();
+        for invalidation in invalidations.iter() {
+            cache.invalidate(invalidation);
+        }
+        result
+    }
+
+    /// Removes the stale entries from the cache
+    pub fn invalidate(&self, invalidation: &Invalidation) {
+        self.lock().invalidate(invalidation);
+    }
+
+    /// Removes everything from the cache
+    pub fn clear(&self) {
+        self.lock().slots.clear();
+    }
+
+    /// Removes the entries made stale by an event from the [`diff`](crate::general::diff) command
+    pub fn apply_event(&self, event: &Event) {
+        let mut cache = self.lock();
+        let (stale, parent_id, name) = match event {
+            Event::Reset => {
+                cache.slots.clear();
+                return;
+            }
+            Event::CreateFile(file) | Event::ModifyFile(file) | Event::DeleteFile(file) => (
+                Invalidation::File(file.file_id),
+                file.base.parent_folder_id,
+                &file.base.name,
+            ),
+            Event::CreateFolder(folder)
+            | Event::ModifyFolder(folder)
+            | Event::DeleteFolder(folder) => (
+                Invalidation::Folder(folder.folder_id),
+                folder.base.parent_folder_id,
+                &folder.base.name,
+            ),
+            _ => return,
+        };
+        cache.invalidate(&stale);
+        if let Some(parent_id) = parent_id {
+            cache.invalidate(&Invalidation::Child {
+                parent_id,
+                name: name.clone(),
+            });
+        }
+    }
+
+    /// Number of paths currently in cache
+    pub fn len(&self) -> usize {
+        self.lock().slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cache, Invalidation, PathResolver, Resolved};
+    use crate::credentials::Credentials;
+    use crate::file::delete::FileDeleteCommand;
+    use crate::http::HttpClient;
+    use crate::region::Region;
+    use mockito::Matcher;
+    use std::time::Duration;
+
+    fn folder(id: u64, parent: u64, name: &str, contents: &str) -> String {
+        format!(
+            r#"{{
+    "result": 0,
+    "metadata": {{
+        "name": "{name}",
+        "created": "Sat, 24 Jul 2021 07:38:41 +0000",
+        "modified": "Sat, 24 Jul 2021 07:38:41 +0000",
+        "thumb": false,
+        "isfolder": true,
+        "folderid": {id},
+        "parentfolderid": {parent},
+        "id": "d{id}",
+        "isshared": false,
+        "ismine": true,
+        "icon": "folder",
+        "contents": [{contents}]
+    }}
+}}"#
+        )
+    }
+
+    fn child(id: u64, name: &str, is_folder: bool) -> String {
+        let (kind, prefix) = if is_folder {
+            ("folderid", "d")
+        } else {
+            ("fileid", "f")
+        };
+        format!(
+            r#"{{
+    "name": "{name}",
+    "created": "Sat, 24 Jul 2021 07:38:41 +0000",
+    "modified": "Sat, 24 Jul 2021 07:38:41 +0000",
+    "thumb": false,
+    "isfolder": {is_folder},
+    "{kind}": {id},
+    "id": "{prefix}{id}",
+    "isshared": false,
+    "ismine": true,
+    "icon": "folder"
+}}"#
+        )
+    }
+
+    #[test]
+    fn cache_should_evict_least_recently_used() {
+        let mut cache = Cache::new(2, Duration::from_secs(60));
+        cache.insert("/a".into(), Resolved::Folder(1));
+        cache.insert("/b".into(), Resolved::Folder(2));
+        assert_eq!(cache.get("/a"), Some(Resolved::Folder(1)));
+        cache.insert("/c".into(), Resolved::Folder(3));
+        assert_eq!(cache.get("/b"), None);
+        assert_eq!(cache.get("/a"), Some(Resolved::Folder(1)));
+        assert_eq!(cache.get("/c"), Some(Resolved::Folder(3)));
+    }
+
+    #[test]
+    fn cache_should_expire() {
+        let mut cache = Cache::new(2, Duration::ZERO);
+        cache.insert("/a".into(), Resolved::Folder(1));
+        assert_eq!(cache.get("/a"), None);
+        assert_eq!(cache.get("/"), Some(Resolved::Folder(0)));
+    }
+
+    #[test]
+    fn cache_should_invalidate_descendants() {
+        let mut cache = Cache::new(10, Duration::from_secs(60));
+        cache.insert("/a".into(), Resolved::Folder(1));
+        cache.insert("/a/b".into(), Resolved::File(2));
+        cache.insert("/ab".into(), Resolved::Folder(3));
+        cache.insert("/c".into(), Resolved::Folder(4));
+        cache.insert("/c/d".into(), Resolved::File(5));
+        cache.invalidate(&Invalidation::Folder(1));
+        assert_eq!(cache.get("/a/b"), None);
+        assert_eq!(cache.get("/ab"), Some(Resolved::Folder(3)));
+        cache.invalidate(&Invalidation::Content(4));
+        assert_eq!(cache.get("/c"), Some(Resolved::Folder(4)));
+        assert_eq!(cache.get("/c/d"), None);
+        cache.invalidate(&Invalidation::Child {
+            parent_id: 0,
+            name: "ab".into(),
+        });
+        assert_eq!(cache.get("/ab"), None);
+    }
+
+    #[tokio::test]
+    async fn resolve_and_invalidate() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m_root = server
+            .mock("GET", "/listfolder")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
+                "folderid".into(),
+                "0".into(),
+            )]))
+            .with_status(200)
+            .with_body(folder(0, 0, "/", &child(1, "photos", true)))
+            .expect(1)
+            .create();
+        let m_photos = server
+            .mock("GET", "/listfolder")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
+                "folderid".into(),
+                "1".into(),
+            )]))
+            .with_status(200)
+            .with_body(folder(
+                1,
+                0,
+                "photos",
+                &format!(
+                    "{},{}",
+                    child(10, "a.jpg", false),
+                    child(11, "b.jpg", false)
+                ),
+            ))
+            .expect(2)
+            .create();
+        let m_delete = server
+            .mock("GET", "/deletefile")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
+                "fileid".into(),
+                "10".into(),
+            )]))
+            .with_status(200)
+            .with_body(
+                r#"{
+    "result": 0,
+    "metadata": {
+        "name": "a.jpg",
+        "created": "Sat, 24 Jul 2021 07:38:41 +0000",
+        "modified": "Sat, 24 Jul 2021 07:38:41 +0000",
+        "thumb": false,
+        "isfolder": false,
+        "fileid": 10,
+        "id": "f10",
+        "isshared": false,
+        "ismine": true,
+        "icon": "image"
+    }
+}"#,
+            )
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let resolver = PathResolver::new(HttpClient::new(creds, dc));
+        assert_eq!(resolver.resolve_file("/photos/a.jpg").await.unwrap(), 10);
+        assert_eq!(resolver.resolve_file("photos//b.jpg").await.unwrap(), 11);
+        assert_eq!(resolver.resolve_folder("/photos/").await.unwrap(), 1);
+        assert!(matches!(
+            resolver.resolve_folder("/photos/a.jpg").await.unwrap_err(),
+            crate::error::Error::Protocol(2005, _)
+        ));
+        assert_eq!(resolver.len(), 3);
+        resolver
+            .execute(FileDeleteCommand::new(10.into()))
+            .await
+            .unwrap();
+        assert_eq!(resolver.len(), 2);
+        // the deleted path is fetched again
+        assert_eq!(
+            resolver.resolve("/photos/a.jpg").await.unwrap(),
+            Some(Resolved::File(10))
+        );
+        m_root.assert();
+        m_photos.assert();
+        m_delete.assert();
+    }
+
+    #[tokio::test]
+    async fn create_dir_all() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m_root = server
+            .mock("GET", "/listfolder")
+            .match_query(Matcher::UrlEncoded("folderid".into(), "0".into()))
+            .with_status(200)
+            .with_body(folder(0, 0, "/", &child(1, "photos", true)))
+            .create();
+        let m_photos = server
+            .mock("GET", "/listfolder")
+            .match_query(Matcher::UrlEncoded("folderid".into(), "1".into()))
+            .with_status(200)
+            .with_body(folder(1, 0, "photos", ""))
+            .create();
+        let m_create_year = server
+            .mock("GET", "/createfolderifnotexists")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("name".into(), "2021".into()),
+                Matcher::UrlEncoded("folderid".into(), "1".into()),
+            ]))
+            .with_status(200)
+            .with_body(folder(2, 1, "2021", ""))
+            .create();
+        let m_create_summer = server
+            .mock("GET", "/createfolderifnotexists")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("name".into(), "summer".into()),
+                Matcher::UrlEncoded("folderid".into(), "2".into()),
+            ]))
+            .with_status(200)
+            .with_body(folder(3, 2, "summer", ""))
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let resolver = PathResolver::new(HttpClient::new(creds, dc));
+        assert_eq!(
+            resolver
+                .create_dir_all("/photos/2021/summer")
+                .await
+                .unwrap(),
+            3
+        );
+        assert_eq!(resolver.resolve_folder("/photos/2021").await.unwrap(), 2);
+        m_root.assert();
+        m_photos.assert();
+        m_create_year.assert();
+        m_create_summer.assert();
+    }
+}
@@ -0,0 +1,121 @@
+//! Resources needed to accept a share request
+
+/// Command to accept a share request
+///
+/// Executing this command will return nothing on success.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/sharing/acceptshare.html).
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use pcloud::sharing::accept_share::AcceptShareCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = AcceptShareCommand::new(1234).folder_id(42);
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct AcceptShareCommand {
+    pub share_request_id: u64,
+    pub folder_id: Option<u64>,
+    pub name: Option<String>,
+}
+
+impl AcceptShareCommand {
+    pub fn new(share_request_id: u64) -> Self {
+        Self {
+            share_request_id,
+            folder_id: None,
+            name: None,
+        }
+    }
+
+    /// Folder in which the shared folder will be mounted, default to the root folder.
+    pub fn folder_id(mut self, value: u64) -> Self {
+        self.folder_id = Some(value);
+        self
+    }
+
+    /// Name of the mounted folder, default to the name of the share.
+    pub fn name(mut self, value: String) -> Self {
+        self.name = Some(value);
+        self
+    }
+}
+
+mod command {
+    use super::AcceptShareCommand;
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    impl AcceptShareCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let mut res = vec![("sharerequestid", self.share_request_id.to_string())];
+            if let Some(folder_id) = self.folder_id {
+                res.push(("folderid", folder_id.to_string()));
+            }
+            if let Some(ref name) = self.name {
+                res.push(("name", name.clone()));
+            }
+            res
+        }
+    }
+
+    impl Command for AcceptShareCommand {
+        type Output = ();
+
+        fn request(&self) -> Request<'_> {
+            Request::new("acceptshare", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<()>(body)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::AcceptShareCommand;
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn success() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/acceptshare")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("sharerequestid".into(), "1234".into()),
+                Matcher::UrlEncoded("folderid".into(), "42".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{ "result": 0 }"#)
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        AcceptShareCommand::new(1234)
+            .folder_id(42)
+            .execute(&api)
+            .await
+            .unwrap();
+        m.assert();
+    }
+}
@@ -0,0 +1,58 @@
+//! Resources needed to cancel a share request
+
+/// Command to cancel a share request sent by the current user
+///
+/// Executing this command will return nothing on success.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/sharing/cancelsharerequest.html).
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use pcloud::sharing::cancel_share_request::CancelShareRequestCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = CancelShareRequestCommand::new(1234);
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct CancelShareRequestCommand {
+    pub share_request_id: u64,
+}
+
+impl CancelShareRequestCommand {
+    pub fn new(share_request_id: u64) -> Self {
+        Self { share_request_id }
+    }
+}
+
+mod command {
+    use super::CancelShareRequestCommand;
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    impl Command for CancelShareRequestCommand {
+        type Output = ();
+
+        fn request(&self) -> Request<'_> {
+            Request::new(
+                "cancelsharerequest",
+                vec![("sharerequestid", self.share_request_id.to_string())],
+            )
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<()>(body)
+        }
+    }
+}
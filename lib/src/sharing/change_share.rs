@@ -0,0 +1,106 @@
+//! Resources needed to change the permissions of an active share
+
+use super::Permissions;
+
+/// Command to change the permissions of an active share
+///
+/// Executing this command will return nothing on success.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/sharing/changeshare.html).
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use pcloud::sharing::Permissions;
+/// use pcloud::sharing::change_share::ChangeShareCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = ChangeShareCommand::new(12, Permissions::ALL);
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct ChangeShareCommand {
+    pub share_id: u64,
+    pub permissions: Permissions,
+}
+
+impl ChangeShareCommand {
+    pub fn new(share_id: u64, permissions: Permissions) -> Self {
+        Self {
+            share_id,
+            permissions,
+        }
+    }
+}
+
+mod command {
+    use super::ChangeShareCommand;
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    impl ChangeShareCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            vec![
+                ("shareid", self.share_id.to_string()),
+                ("permissions", self.permissions.bits().to_string()),
+            ]
+        }
+    }
+
+    impl Command for ChangeShareCommand {
+        type Output = ();
+
+        fn request(&self) -> Request<'_> {
+            Request::new("changeshare", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<()>(body)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::ChangeShareCommand;
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use crate::sharing::Permissions;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn success() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/changeshare")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("shareid".into(), "12".into()),
+                Matcher::UrlEncoded("permissions".into(), "7".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{ "result": 0 }"#)
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        ChangeShareCommand::new(12, Permissions::ALL)
+            .execute(&api)
+            .await
+            .unwrap();
+        m.assert();
+    }
+}
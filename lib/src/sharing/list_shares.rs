@@ -0,0 +1,180 @@
+//! Resources needed to list the shares and share requests
+
+use super::{Directional, Share, ShareRequest};
+
+/// Command to list the active shares and the pending share requests
+///
+/// Executing this command will return a [`ShareList`](ShareList) on success.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/sharing/listshares.html).
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use pcloud::sharing::list_shares::ListSharesCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = ListSharesCommand::default().no_requests(true);
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug, Default)]
+pub struct ListSharesCommand {
+    pub no_shares: bool,
+    pub no_requests: bool,
+    pub no_incoming: bool,
+    pub no_outgoing: bool,
+}
+
+impl ListSharesCommand {
+    /// If set, the active shares will not be returned.
+    pub fn no_shares(mut self, value: bool) -> Self {
+        self.no_shares = value;
+        self
+    }
+
+    /// If set, the pending share requests will not be returned.
+    pub fn no_requests(mut self, value: bool) -> Self {
+        self.no_requests = value;
+        self
+    }
+
+    /// If set, the items shared with the current user will not be returned.
+    pub fn no_incoming(mut self, value: bool) -> Self {
+        self.no_incoming = value;
+        self
+    }
+
+    /// If set, the items shared by the current user will not be returned.
+    pub fn no_outgoing(mut self, value: bool) -> Self {
+        self.no_outgoing = value;
+        self
+    }
+}
+
+/// Structure returned when listing the shares
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+pub struct ShareList {
+    #[serde(default)]
+    pub shares: Directional<Share>,
+    #[serde(default)]
+    pub requests: Directional<ShareRequest>,
+}
+
+mod command {
+    use super::{ListSharesCommand, ShareList};
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    impl ListSharesCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let mut res = Vec::new();
+            if self.no_shares {
+                res.push(("noshares", "1".to_string()));
+            }
+            if self.no_requests {
+                res.push(("norequests", "1".to_string()));
+            }
+            if self.no_incoming {
+                res.push(("noincoming", "1".to_string()));
+            }
+            if self.no_outgoing {
+                res.push(("nooutgoing", "1".to_string()));
+            }
+            res
+        }
+    }
+
+    impl Command for ListSharesCommand {
+        type Output = ShareList;
+
+        fn request(&self) -> Request<'_> {
+            Request::new("listshares", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<ShareList>(body)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::ListSharesCommand;
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use crate::sharing::Permissions;
+
+    #[tokio::test]
+    async fn success() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/listshares")
+            .match_header("authorization", "Bearer access-token")
+            .with_status(200)
+            .with_body(
+                r#"{
+    "result": 0,
+    "shares": {
+        "incoming": [],
+        "outgoing": [
+            {
+                "shareid": 12,
+                "folderid": 42,
+                "sharename": "photos",
+                "tomail": "someone@example.com",
+                "created": "Sat, 24 Jul 2021 07:38:41 +0000",
+                "canread": true,
+                "cancreate": true,
+                "canmodify": true,
+                "candelete": false
+            }
+        ]
+    },
+    "requests": {
+        "incoming": [
+            {
+                "sharerequestid": 1234,
+                "sharename": "documents",
+                "frommail": "other@example.com",
+                "message": "here you go",
+                "created": "Sat, 24 Jul 2021 07:38:41 +0000",
+                "expires": "Sat, 31 Jul 2021 07:38:41 +0000",
+                "canread": true,
+                "cancreate": false,
+                "canmodify": false,
+                "candelete": false
+            }
+        ],
+        "outgoing": []
+    }
+}"#,
+            )
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let result = ListSharesCommand::default().execute(&api).await.unwrap();
+        assert!(result.shares.incoming.is_empty());
+        assert_eq!(result.shares.outgoing.len(), 1);
+        assert_eq!(
+            result.shares.outgoing[0].permissions,
+            Permissions::CREATE | Permissions::MODIFY
+        );
+        assert_eq!(result.requests.incoming[0].share_request_id, 1234);
+        assert!(result.requests.incoming[0].expires.is_some());
+        m.assert();
+    }
+}
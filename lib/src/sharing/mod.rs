@@ -0,0 +1,249 @@
+pub mod accept_share;
+pub mod cancel_share_request;
+pub mod change_share;
+pub mod decline_share;
+pub mod list_shares;
+pub mod remove_share;
+pub mod share_folder;
+
+use chrono::prelude::{DateTime, Utc};
+use std::ops::{BitOr, BitOrAssign};
+
+/// The permissions given to the receiver of a shared folder.
+///
+/// Reading is always granted, the other permissions can be combined.
+///
+/// ```
+/// use pcloud::sharing::Permissions;
+///
+/// let perms = Permissions::CREATE | Permissions::MODIFY;
+/// assert!(perms.can_create());
+/// assert!(!perms.can_delete());
+/// assert_eq!(perms.bits(), 3);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Permissions(u8);
+
+impl Permissions {
+    /// Only allowed to read the content of the folder
+    pub const READ: Self = Self(0);
+    /// Allowed to create files and folders
+    pub const CREATE: Self = Self(1);
+    /// Allowed to modify existing files and folders
+    pub const MODIFY: Self = Self(2);
+    /// Allowed to delete files and folders
+    pub const DELETE: Self = Self(4);
+    /// All the permissions at once
+    pub const ALL: Self = Self(7);
+
+    /// Builds the permissions from the value used by the API, ignoring the unknown bits.
+    pub fn from_bits_truncate(value: u8) -> Self {
+        Self(value & Self::ALL.0)
+    }
+
+    /// Returns the value used by the API
+    pub fn bits(&self) -> u8 {
+        self.0
+    }
+
+    pub fn contains(&self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn insert(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+
+    pub fn remove(&mut self, other: Self) {
+        self.0 &= !other.0;
+    }
+
+    pub fn can_create(&self) -> bool {
+        self.contains(Self::CREATE)
+    }
+
+    pub fn can_modify(&self) -> bool {
+        self.contains(Self::MODIFY)
+    }
+
+    pub fn can_delete(&self) -> bool {
+        self.contains(Self::DELETE)
+    }
+}
+
+impl BitOr for Permissions {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Permissions {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.insert(rhs);
+    }
+}
+
+impl std::fmt::Display for Permissions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("r")?;
+        f.write_str(if self.can_create() { "c" } else { "-" })?;
+        f.write_str(if self.can_modify() { "m" } else { "-" })?;
+        f.write_str(if self.can_delete() { "d" } else { "-" })
+    }
+}
+
+/// The way the API returns the permissions of a share
+#[derive(Default, serde::Deserialize, serde::Serialize)]
+struct PermissionFlags {
+    #[serde(default, rename = "cancreate")]
+    can_create: bool,
+    #[serde(default, rename = "canmodify")]
+    can_modify: bool,
+    #[serde(default, rename = "candelete")]
+    can_delete: bool,
+}
+
+impl From<PermissionFlags> for Permissions {
+    fn from(value: PermissionFlags) -> Self {
+        let mut res = Self::READ;
+        if value.can_create {
+            res.insert(Self::CREATE);
+        }
+        if value.can_modify {
+            res.insert(Self::MODIFY);
+        }
+        if value.can_delete {
+            res.insert(Self::DELETE);
+        }
+        res
+    }
+}
+
+impl From<Permissions> for PermissionFlags {
+    fn from(value: Permissions) -> Self {
+        Self {
+            can_create: value.can_create(),
+            can_modify: value.can_modify(),
+            can_delete: value.can_delete(),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Permissions {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        PermissionFlags::deserialize(deserializer).map(Self::from)
+    }
+}
+
+impl serde::Serialize for Permissions {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        PermissionFlags::from(*self).serialize(serializer)
+    }
+}
+
+/// A structure representing an accepted share of a folder
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct Share {
+    #[serde(rename = "shareid")]
+    pub share_id: u64,
+    #[serde(rename = "folderid")]
+    pub folder_id: u64,
+    #[serde(rename = "sharename")]
+    pub name: String,
+    #[serde(default, rename = "frommail")]
+    pub from_mail: Option<String>,
+    #[serde(default, rename = "tomail")]
+    pub to_mail: Option<String>,
+    #[serde(with = "crate::date")]
+    pub created: DateTime<Utc>,
+    #[serde(flatten)]
+    pub permissions: Permissions,
+}
+
+/// A structure representing a share request waiting to be accepted or declined
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct ShareRequest {
+    #[serde(rename = "sharerequestid")]
+    pub share_request_id: u64,
+    #[serde(default, rename = "folderid")]
+    pub folder_id: Option<u64>,
+    #[serde(rename = "sharename")]
+    pub name: String,
+    #[serde(default, rename = "frommail")]
+    pub from_mail: Option<String>,
+    #[serde(default, rename = "tomail")]
+    pub to_mail: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(with = "crate::date")]
+    pub created: DateTime<Utc>,
+    #[serde(default, with = "crate::date::optional")]
+    pub expires: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub permissions: Permissions,
+}
+
+/// A list of items split by direction
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct Directional<T> {
+    /// Items shared with the current user
+    #[serde(default = "Vec::new")]
+    pub incoming: Vec<T>,
+    /// Items shared by the current user
+    #[serde(default = "Vec::new")]
+    pub outgoing: Vec<T>,
+}
+
+impl<T> Default for Directional<T> {
+    fn default() -> Self {
+        Self {
+            incoming: Vec::new(),
+            outgoing: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Permissions, Share};
+
+    #[test]
+    fn permissions_combine() {
+        let mut perms = Permissions::READ;
+        assert_eq!(perms.to_string(), "r---");
+        perms |= Permissions::CREATE;
+        perms.insert(Permissions::DELETE);
+        assert_eq!(perms.bits(), 5);
+        assert_eq!(perms.to_string(), "rc-d");
+        perms.remove(Permissions::CREATE);
+        assert_eq!(perms, Permissions::DELETE);
+        assert_eq!(Permissions::from_bits_truncate(255), Permissions::ALL);
+    }
+
+    #[test]
+    fn share_deserialize() {
+        let share: Share = serde_json::from_str(
+            r#"{
+    "shareid": 12,
+    "folderid": 42,
+    "sharename": "photos",
+    "tomail": "someone@example.com",
+    "created": "Sat, 24 Jul 2021 07:38:41 +0000",
+    "canread": true,
+    "cancreate": true,
+    "canmodify": false,
+    "candelete": true
+}"#,
+        )
+        .unwrap();
+        assert_eq!(share.permissions, Permissions::CREATE | Permissions::DELETE);
+    }
+}
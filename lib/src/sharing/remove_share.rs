@@ -0,0 +1,87 @@
+//! Resources needed to remove an active share
+
+/// Command to remove an active share
+///
+/// Executing this command will return nothing on success.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/sharing/removeshare.html).
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use pcloud::sharing::remove_share::RemoveShareCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = RemoveShareCommand::new(12);
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct RemoveShareCommand {
+    pub share_id: u64,
+}
+
+impl RemoveShareCommand {
+    pub fn new(share_id: u64) -> Self {
+        Self { share_id }
+    }
+}
+
+mod command {
+    use super::RemoveShareCommand;
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    impl Command for RemoveShareCommand {
+        type Output = ();
+
+        fn request(&self) -> Request<'_> {
+            Request::new("removeshare", vec![("shareid", self.share_id.to_string())])
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<()>(body)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::RemoveShareCommand;
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn error() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/removeshare")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
+                "shareid".into(),
+                "12".into(),
+            )]))
+            .with_status(200)
+            .with_body(r#"{ "result": 2025, "error": "Invalid 'shareid' provided." }"#)
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let error = RemoveShareCommand::new(12).execute(&api).await.unwrap_err();
+        assert!(matches!(error, crate::error::Error::Protocol(2025, _)));
+        m.assert();
+    }
+}
@@ -0,0 +1,172 @@
+//! Resources needed to share a folder with another user
+
+use super::Permissions;
+use crate::folder::FolderIdentifier;
+
+/// Command to share a folder with another user
+///
+/// Executing this command will return the id of the created share request on success.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/sharing/sharefolder.html).
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use pcloud::sharing::Permissions;
+/// use pcloud::sharing::share_folder::ShareFolderCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = ShareFolderCommand::new(12.into(), "someone@example.com".into())
+///     .permissions(Permissions::CREATE | Permissions::MODIFY);
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct ShareFolderCommand {
+    pub identifier: FolderIdentifier,
+    pub mail: String,
+    pub permissions: Permissions,
+    pub name: Option<String>,
+    pub message: Option<String>,
+}
+
+impl ShareFolderCommand {
+    pub fn new(identifier: FolderIdentifier, mail: String) -> Self {
+        Self {
+            identifier,
+            mail,
+            permissions: Permissions::READ,
+            name: None,
+            message: None,
+        }
+    }
+
+    /// Permissions given to the receiver, reading is always allowed.
+    pub fn permissions(mut self, value: Permissions) -> Self {
+        self.permissions = value;
+        self
+    }
+
+    /// Name of the share, default to the folder name.
+    pub fn name(mut self, value: String) -> Self {
+        self.name = Some(value);
+        self
+    }
+
+    /// Message sent to the receiver with the invitation.
+    pub fn message(mut self, value: String) -> Self {
+        self.message = Some(value);
+        self
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct ShareFolderResponse {
+    #[serde(rename = "sharerequestid")]
+    pub share_request_id: u64,
+}
+
+mod command {
+    use super::{ShareFolderCommand, ShareFolderResponse};
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    impl ShareFolderCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let mut res = self.identifier.to_http_params();
+            res.push(("mail", self.mail.clone()));
+            res.push(("permissions", self.permissions.bits().to_string()));
+            if let Some(ref name) = self.name {
+                res.push(("name", name.clone()));
+            }
+            if let Some(ref message) = self.message {
+                res.push(("message", message.clone()));
+            }
+            res
+        }
+    }
+
+    impl Command for ShareFolderCommand {
+        type Output = u64;
+
+        fn request(&self) -> Request<'_> {
+            Request::new("sharefolder", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<ShareFolderResponse>(body).map(|item| item.share_request_id)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::ShareFolderCommand;
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use crate::sharing::Permissions;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn success() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/sharefolder")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("folderid".into(), "42".into()),
+                Matcher::UrlEncoded("mail".into(), "someone@example.com".into()),
+                Matcher::UrlEncoded("permissions".into(), "5".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{ "result": 0, "sharerequestid": 1234 }"#)
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let result = ShareFolderCommand::new(42.into(), "someone@example.com".into())
+            .permissions(Permissions::CREATE | Permissions::DELETE)
+            .execute(&api)
+            .await
+            .unwrap();
+        assert_eq!(result, 1234);
+        m.assert();
+    }
+
+    #[tokio::test]
+    async fn error() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/sharefolder")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
+                "folderid".into(),
+                "42".into(),
+            )]))
+            .with_status(200)
+            .with_body(r#"{ "result": 2005, "error": "Directory does not exist." }"#)
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let error = ShareFolderCommand::new(42.into(), "someone@example.com".into())
+            .execute(&api)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, crate::error::Error::Protocol(2005, _)));
+        m.assert();
+    }
+}
@@ -1,7 +1,32 @@
+//! Resources needed to get a link to an audio file in order to stream it
+
 use crate::file::FileIdentifier;
 
-#[derive(Debug)]
 /// Command to get the audio link for streaming
+///
+/// Executing this command will return a [`StreamingLink`](super::StreamingLink) to the audio file.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/streaming/getaudiolink.html)
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use pcloud::streaming::get_audio_link::GetAudioLinkCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = GetAudioLinkCommand::new("/foo/bar.mp3".into()).audio_bit_rate(128);
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
 pub struct GetAudioLinkCommand {
     /// File identifier
     pub identifier: FileIdentifier,
@@ -20,33 +45,23 @@ impl GetAudioLinkCommand {
         }
     }
 
-    pub fn set_audio_bit_rate(&mut self, value: Option<u16>) {
-        self.audio_bit_rate = value;
-    }
-
     pub fn audio_bit_rate(mut self, value: u16) -> Self {
         self.audio_bit_rate = Some(value);
         self
     }
 
-    pub fn set_force_download(&mut self, value: bool) {
-        self.force_download = value;
-    }
-
     pub fn force_download(mut self, value: bool) -> Self {
         self.force_download = value;
         self
     }
 }
 
-#[cfg(feature = "client-http")]
-mod http {
+mod command {
     use super::GetAudioLinkCommand;
     use crate::error::Error;
-    use crate::http::HttpClient;
-    use crate::prelude::HttpCommand;
-    use crate::request::Response;
-    use crate::streaming::Payload;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+    use crate::streaming::StreamingLink;
 
     impl GetAudioLinkCommand {
         fn to_http_params(&self) -> Vec<(&str, String)> {
@@ -61,15 +76,15 @@ mod http {
         }
     }
 
-    #[async_trait::async_trait]
-    impl HttpCommand for GetAudioLinkCommand {
-        type Output = String;
+    impl Command for GetAudioLinkCommand {
+        type Output = StreamingLink;
+
+        fn request(&self) -> Request<'_> {
+            Request::new("getaudiolink", self.to_http_params())
+        }
 
-        async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error> {
-            let result: Response<Payload> = client
-                .get_request("getaudiolink", &self.to_http_params())
-                .await?;
-            result.payload().map(|item| item.to_url())
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<StreamingLink>(body)
         }
     }
 }
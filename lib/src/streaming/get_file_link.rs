@@ -2,9 +2,10 @@
 
 use crate::file::FileIdentifier;
 
-/// Command to a file streaming link
+/// Command to get a file streaming link
 ///
-/// Executing this command with return the url to the file.
+/// Executing this command will return a [`StreamingLink`](super::StreamingLink) to the file,
+/// with its expiration date.
 ///
 /// [More about it on the documentation](https://docs.pcloud.com/methods/streaming/getfilelink.html)
 ///
@@ -19,7 +20,9 @@ use crate::file::FileIdentifier;
 ///
 /// # tokio_test::block_on(async {
 /// let client = HttpClientBuilder::from_env().build().unwrap();
-/// let cmd = GetFileLinkCommand::new("/foo/bar.txt".into());
+/// let cmd = GetFileLinkCommand::new("/foo/bar.txt".into())
+///     .force_download(true)
+///     .max_speed(1024 * 1024);
 /// match cmd.execute(&client).await {
 ///   Ok(res) => println!("success"),
 ///   Err(err) => eprintln!("error: {:?}", err),
@@ -29,32 +32,94 @@ use crate::file::FileIdentifier;
 #[derive(Debug)]
 pub struct GetFileLinkCommand {
     pub identifier: FileIdentifier,
+    /// Download with Content-Type = application/octet-stream
+    pub force_download: bool,
+    /// Content-Type of the response
+    pub content_type: Option<String>,
+    /// Maximum download speed, in bytes per second
+    pub max_speed: Option<u64>,
+    /// If set, the file name will not be appended to the link
+    pub skip_filename: bool,
+    /// Revision of the file to link to, default to the current one
+    pub revision_id: Option<u64>,
 }
 
 impl GetFileLinkCommand {
     pub fn new(identifier: FileIdentifier) -> Self {
-        Self { identifier }
+        Self {
+            identifier,
+            force_download: false,
+            content_type: None,
+            max_speed: None,
+            skip_filename: false,
+            revision_id: None,
+        }
+    }
+
+    pub fn force_download(mut self, value: bool) -> Self {
+        self.force_download = value;
+        self
+    }
+
+    pub fn content_type(mut self, value: String) -> Self {
+        self.content_type = Some(value);
+        self
+    }
+
+    pub fn max_speed(mut self, value: u64) -> Self {
+        self.max_speed = Some(value);
+        self
+    }
+
+    pub fn skip_filename(mut self, value: bool) -> Self {
+        self.skip_filename = value;
+        self
+    }
+
+    pub fn revision_id(mut self, value: u64) -> Self {
+        self.revision_id = Some(value);
+        self
     }
 }
 
-#[cfg(feature = "client-http")]
-mod http {
+mod command {
     use super::GetFileLinkCommand;
     use crate::error::Error;
-    use crate::http::HttpClient;
-    use crate::prelude::HttpCommand;
-    use crate::request::Response;
-    use crate::streaming::Payload;
-
-    #[async_trait::async_trait]
-    impl HttpCommand for GetFileLinkCommand {
-        type Output = String;
-
-        async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error> {
-            let result: Response<Payload> = client
-                .get_request("getfilelink", &self.identifier.to_http_params())
-                .await?;
-            result.payload().map(|res| res.to_url())
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+    use crate::streaming::StreamingLink;
+
+    impl GetFileLinkCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let mut res = self.identifier.to_http_params();
+            if self.force_download {
+                res.push(("forcedownload", 1.to_string()));
+            }
+            if let Some(ref value) = self.content_type {
+                res.push(("contenttype", value.clone()));
+            }
+            if let Some(value) = self.max_speed {
+                res.push(("maxspeed", value.to_string()));
+            }
+            if self.skip_filename {
+                res.push(("skipfilename", 1.to_string()));
+            }
+            if let Some(value) = self.revision_id {
+                res.push(("revisionid", value.to_string()));
+            }
+            res
+        }
+    }
+
+    impl Command for GetFileLinkCommand {
+        type Output = StreamingLink;
+
+        fn request(&self) -> Request<'_> {
+            Request::new("getfilelink", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<StreamingLink>(body)
         }
     }
 }
@@ -73,9 +138,9 @@ mod http_tests {
         crate::tests::init();
         let mut server = mockito::Server::new_async().await;
         let m = server.mock("GET", "/getfilelink")
+            .match_header("authorization", "Bearer access-token")
             .match_query(Matcher::AllOf(vec![
-                                        Matcher::UrlEncoded("access_token".into(), "access-token".into()),
-                                        Matcher::UrlEncoded("fileid".into(), "42".into()),
+                Matcher::UrlEncoded("fileid".into(), "42".into()),
             ]))
             .with_status(200)
             .with_body(r#"{
@@ -98,7 +163,45 @@ mod http_tests {
             .execute(&api)
             .await
             .unwrap();
-        assert_eq!(result, "https://edef2.pcloud.com/DLZCAt2vXZejNfL5ZruLVZZTk2ev7Z2ZZNR5ZZdoz6ZXZQZZErw4bH0PfzBQt3LlgXMliXVtietX/SAkdyBjkA7mQABbT.bin");
+        assert_eq!(result.url().unwrap(), "https://edef2.pcloud.com/DLZCAt2vXZejNfL5ZruLVZZTk2ev7Z2ZZNR5ZZdoz6ZXZQZZErw4bH0PfzBQt3LlgXMliXVtietX/SAkdyBjkA7mQABbT.bin");
+        assert!(result.expires.is_some());
+        m.assert();
+    }
+
+    #[tokio::test]
+    async fn success_with_options() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/getfilelink")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("fileid".into(), "42".into()),
+                Matcher::UrlEncoded("forcedownload".into(), "1".into()),
+                Matcher::UrlEncoded("maxspeed".into(), "1024".into()),
+                Matcher::UrlEncoded("revisionid".into(), "3".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                r#"{
+        "result": 0,
+        "path": "\/foo.bin",
+        "hosts": ["edef2.pcloud.com"]
+}"#,
+            )
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let result = GetFileLinkCommand::new(42.into())
+            .force_download(true)
+            .max_speed(1024)
+            .revision_id(3)
+            .execute(&api)
+            .await
+            .unwrap();
+        assert_eq!(result.url().unwrap(), "https://edef2.pcloud.com/foo.bin");
+        assert!(result.expires.is_none());
         m.assert();
     }
 }
@@ -0,0 +1,162 @@
+//! Resources needed to get a HLS link to a video file
+
+use crate::file::FileIdentifier;
+
+/// Command to get a HTTP Live Streaming link to a video file
+///
+/// Executing this command will return a [`StreamingLink`](super::StreamingLink) to the m3u8 playlist.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/streaming/gethlslink.html)
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use pcloud::streaming::get_hls_link::GetHlsLinkCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = GetHlsLinkCommand::new("/foo/bar.mp4".into()).resolution("1280x720".into());
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct GetHlsLinkCommand {
+    pub identifier: FileIdentifier,
+    /// Maximum audio bit rate in kilobits, from 16 to 320
+    pub audio_bit_rate: Option<u16>,
+    /// Maximum video bit rate in kilobits, from 16 to 4000
+    pub video_bit_rate: Option<u32>,
+    /// Maximum resolution in pixels, from 64x64 to 1280x960, WIDTHxHEIGHT
+    pub resolution: Option<String>,
+    /// If set, the file name will not be appended to the link
+    pub skip_filename: bool,
+}
+
+impl GetHlsLinkCommand {
+    pub fn new(identifier: FileIdentifier) -> Self {
+        Self {
+            identifier,
+            audio_bit_rate: None,
+            video_bit_rate: None,
+            resolution: None,
+            skip_filename: false,
+        }
+    }
+
+    pub fn audio_bit_rate(mut self, value: u16) -> Self {
+        self.audio_bit_rate = Some(value);
+        self
+    }
+
+    pub fn video_bit_rate(mut self, value: u32) -> Self {
+        self.video_bit_rate = Some(value);
+        self
+    }
+
+    pub fn resolution(mut self, value: String) -> Self {
+        self.resolution = Some(value);
+        self
+    }
+
+    pub fn skip_filename(mut self, value: bool) -> Self {
+        self.skip_filename = value;
+        self
+    }
+}
+
+mod command {
+    use super::GetHlsLinkCommand;
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+    use crate::streaming::StreamingLink;
+
+    impl GetHlsLinkCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let mut res = self.identifier.to_http_params();
+            if let Some(abitrate) = self.audio_bit_rate {
+                res.push(("abitrate", abitrate.to_string()));
+            }
+            if let Some(vbitrate) = self.video_bit_rate {
+                res.push(("vbitrate", vbitrate.to_string()));
+            }
+            if let Some(ref resolution) = self.resolution {
+                res.push(("resolution", resolution.to_string()));
+            }
+            if self.skip_filename {
+                res.push(("skipfilename", 1.to_string()));
+            }
+            res
+        }
+    }
+
+    impl Command for GetHlsLinkCommand {
+        type Output = StreamingLink;
+
+        fn request(&self) -> Request<'_> {
+            Request::new("gethlslink", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<StreamingLink>(body)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::GetHlsLinkCommand;
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn success() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/gethlslink")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("fileid".into(), "42".into()),
+                Matcher::UrlEncoded("resolution".into(), "1280x720".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                r#"{
+        "result": 0,
+        "expires": "Sat, 24 Jul 2021 03:18:31 +0000",
+        "path": "\/DLZCAt2vXZejNfL5ZruLVZZTk2ev7Z2ZZNR5ZZdoz6ZXZQZZErw4bH0PfzBQt3LlgXMliXVtietX\/video.m3u8",
+        "hosts": [
+                "edef2.pcloud.com",
+                "eu3.pcloud.com"
+        ]
+}"#,
+            )
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let result = GetHlsLinkCommand::new(42.into())
+            .resolution("1280x720".into())
+            .execute(&api)
+            .await
+            .unwrap();
+        assert_eq!(
+            result.url().unwrap(),
+            "https://edef2.pcloud.com/DLZCAt2vXZejNfL5ZruLVZZTk2ev7Z2ZZNR5ZZdoz6ZXZQZZErw4bH0PfzBQt3LlgXMliXVtietX/video.m3u8"
+        );
+        assert_eq!(result.urls().count(), 2);
+        assert!(result.expires.is_some());
+        m.assert();
+    }
+}
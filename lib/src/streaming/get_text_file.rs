@@ -0,0 +1,179 @@
+//! Resources needed to read the content of a text file
+
+use crate::file::FileIdentifier;
+
+/// Command to read the content of a text file, converting its encoding if needed
+///
+/// Executing this command will return a [`TextFile`](TextFile) on success.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/streaming/gettextfile.html)
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use pcloud::streaming::get_text_file::GetTextFileCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = GetTextFileCommand::new("/foo/bar.txt".into()).from_encoding("latin1".into());
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("{}", res.as_str().unwrap()),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct GetTextFileCommand {
+    pub identifier: FileIdentifier,
+    /// Encoding of the file, guessed by the server when not set
+    pub from_encoding: Option<String>,
+    /// Encoding of the returned content, default to utf-8
+    pub to_encoding: Option<String>,
+    /// Download with Content-Type = application/octet-stream
+    pub force_download: bool,
+    /// Content-Type of the response
+    pub content_type: Option<String>,
+}
+
+impl GetTextFileCommand {
+    pub fn new(identifier: FileIdentifier) -> Self {
+        Self {
+            identifier,
+            from_encoding: None,
+            to_encoding: None,
+            force_download: false,
+            content_type: None,
+        }
+    }
+
+    pub fn from_encoding(mut self, value: String) -> Self {
+        self.from_encoding = Some(value);
+        self
+    }
+
+    pub fn to_encoding(mut self, value: String) -> Self {
+        self.to_encoding = Some(value);
+        self
+    }
+
+    pub fn force_download(mut self, value: bool) -> Self {
+        self.force_download = value;
+        self
+    }
+
+    pub fn content_type(mut self, value: String) -> Self {
+        self.content_type = Some(value);
+        self
+    }
+}
+
+/// The content of a text file
+#[derive(Clone, Debug)]
+pub struct TextFile {
+    /// The Content-Type of the response
+    pub content_type: Option<String>,
+    /// The content, in the requested encoding
+    pub content: Vec<u8>,
+}
+
+impl TextFile {
+    /// The content as a string, if it was converted to utf-8
+    pub fn as_str(&self) -> Result<&str, std::str::Utf8Error> {
+        std::str::from_utf8(&self.content)
+    }
+}
+
+#[cfg(feature = "client-http")]
+mod http {
+    use super::{GetTextFileCommand, TextFile};
+    use crate::error::Error;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+
+    impl GetTextFileCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let mut res = self.identifier.to_http_params();
+            if let Some(ref value) = self.from_encoding {
+                res.push(("fromencoding", value.clone()));
+            }
+            if let Some(ref value) = self.to_encoding {
+                res.push(("toencoding", value.clone()));
+            }
+            if self.force_download {
+                res.push(("forcedownload", 1.to_string()));
+            }
+            if let Some(ref value) = self.content_type {
+                res.push(("contenttype", value.clone()));
+            }
+            res
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl HttpCommand for GetTextFileCommand {
+        type Output = TextFile;
+
+        async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error> {
+            let res = client
+                .get_request_raw("gettextfile", &self.to_http_params())
+                .await?;
+            let content_type = res
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(String::from);
+            let content = res.bytes().await?;
+            Ok(TextFile {
+                content_type,
+                content,
+            })
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::GetTextFileCommand;
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn success() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/gettextfile")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("fileid".into(), "42".into()),
+                Matcher::UrlEncoded("fromencoding".into(), "latin1".into()),
+                Matcher::UrlEncoded("toencoding".into(), "utf-8".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "text/plain; charset=utf-8")
+            .with_body("déjà vu")
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let result = GetTextFileCommand::new(42.into())
+            .from_encoding("latin1".into())
+            .to_encoding("utf-8".into())
+            .execute(&api)
+            .await
+            .unwrap();
+        assert_eq!(result.as_str().unwrap(), "déjà vu");
+        assert_eq!(
+            result.content_type.as_deref(),
+            Some("text/plain; charset=utf-8")
+        );
+        m.assert();
+    }
+}
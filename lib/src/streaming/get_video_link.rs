@@ -1,7 +1,34 @@
+//! Resources needed to get a link to a video file in order to stream it
+
 use crate::file::FileIdentifier;
 
-#[derive(Debug)]
 /// Command to get video link for streaming
+///
+/// Executing this command will return a [`StreamingLink`](super::StreamingLink) to the video file.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/streaming/getvideolink.html)
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use pcloud::streaming::get_video_link::GetVideoLinkCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = GetVideoLinkCommand::new("/foo/bar.mp4".into())
+///     .resolution("1280x720".into())
+///     .fixed_bit_rate(true);
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
 pub struct GetVideoLinkCommand {
     /// File identifier
     pub identifier: FileIdentifier,
@@ -13,6 +40,8 @@ pub struct GetVideoLinkCommand {
     pub resolution: Option<String>,
     /// if set, turns off adaptive streaming and the stream will be with a constant bitrate.
     pub fixed_bit_rate: bool,
+    /// Download with Content-Type = application/octet-stream
+    pub force_download: bool,
 }
 
 impl GetVideoLinkCommand {
@@ -23,54 +52,42 @@ impl GetVideoLinkCommand {
             video_bit_rate: None,
             resolution: None,
             fixed_bit_rate: false,
+            force_download: false,
         }
     }
 
-    pub fn set_audio_bit_rate(&mut self, value: Option<u16>) {
-        self.audio_bit_rate = value;
-    }
-
     pub fn audio_bit_rate(mut self, value: u16) -> Self {
         self.audio_bit_rate = Some(value);
         self
     }
 
-    pub fn set_video_bit_rate(mut self, value: Option<u32>) {
-        self.video_bit_rate = value;
-    }
-
     pub fn video_bit_rate(mut self, value: u32) -> Self {
         self.video_bit_rate = Some(value);
         self
     }
 
-    pub fn set_resolution(mut self, value: Option<String>) {
-        self.resolution = value;
-    }
-
     pub fn resolution(mut self, value: String) -> Self {
         self.resolution = Some(value);
         self
     }
 
-    pub fn set_fixed_bit_rate(&mut self, value: bool) {
+    pub fn fixed_bit_rate(mut self, value: bool) -> Self {
         self.fixed_bit_rate = value;
+        self
     }
 
-    pub fn fixed_bit_rate(mut self, value: bool) -> Self {
-        self.fixed_bit_rate = value;
+    pub fn force_download(mut self, value: bool) -> Self {
+        self.force_download = value;
         self
     }
 }
 
-#[cfg(feature = "client-http")]
-mod http {
+mod command {
     use super::GetVideoLinkCommand;
     use crate::error::Error;
-    use crate::http::HttpClient;
-    use crate::prelude::HttpCommand;
-    use crate::request::Response;
-    use crate::streaming::Payload;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+    use crate::streaming::StreamingLink;
 
     impl GetVideoLinkCommand {
         fn to_http_params(&self) -> Vec<(&str, String)> {
@@ -87,19 +104,60 @@ mod http {
             if self.fixed_bit_rate {
                 res.push(("fixedbitrate", 1.to_string()));
             }
+            if self.force_download {
+                res.push(("forcedownload", 1.to_string()));
+            }
             res
         }
     }
 
-    #[async_trait::async_trait]
-    impl HttpCommand for GetVideoLinkCommand {
-        type Output = String;
+    impl Command for GetVideoLinkCommand {
+        type Output = StreamingLink;
 
-        async fn execute(self, client: &HttpClient) -> Result<Self::Output, Error> {
-            let result: Response<Payload> = client
-                .get_request("getvideolink", &self.to_http_params())
-                .await?;
-            result.payload().map(|item| item.to_url())
+        fn request(&self) -> Request<'_> {
+            Request::new("getvideolink", self.to_http_params())
         }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<StreamingLink>(body)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::GetVideoLinkCommand;
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn success() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/getvideolink")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("fileid".into(), "42".into()),
+                Matcher::UrlEncoded("resolution".into(), "640x480".into()),
+                Matcher::UrlEncoded("fixedbitrate".into(), "1".into()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{ "result": 0, "path": "\/video.mp4", "hosts": ["edef2.pcloud.com"] }"#)
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let result = GetVideoLinkCommand::new(42.into())
+            .resolution("640x480".into())
+            .fixed_bit_rate(true)
+            .execute(&api)
+            .await
+            .unwrap();
+        assert_eq!(result.url().unwrap(), "https://edef2.pcloud.com/video.mp4");
+        m.assert();
     }
 }
@@ -0,0 +1,196 @@
+//! Resources needed to get the links to every variant of a video file
+
+use super::StreamingLink;
+use crate::file::FileIdentifier;
+
+/// Command to get the links to the original video file and to its transcoded variants
+///
+/// Executing this command will return a `Vec` of [`VideoVariant`](VideoVariant) on success.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/streaming/getvideolinks.html)
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use pcloud::streaming::get_video_links::GetVideoLinksCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = GetVideoLinksCommand::new("/foo/bar.mp4".into());
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct GetVideoLinksCommand {
+    pub identifier: FileIdentifier,
+    /// Download with Content-Type = application/octet-stream
+    pub force_download: bool,
+    /// If set, the file name will not be appended to the links
+    pub skip_filename: bool,
+}
+
+impl GetVideoLinksCommand {
+    pub fn new(identifier: FileIdentifier) -> Self {
+        Self {
+            identifier,
+            force_download: false,
+            skip_filename: false,
+        }
+    }
+
+    pub fn force_download(mut self, value: bool) -> Self {
+        self.force_download = value;
+        self
+    }
+
+    pub fn skip_filename(mut self, value: bool) -> Self {
+        self.skip_filename = value;
+        self
+    }
+}
+
+/// A variant of a video file, with its link and metadata
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct VideoVariant {
+    #[serde(flatten)]
+    pub link: StreamingLink,
+    /// Set when the variant is not the original file
+    #[serde(default)]
+    pub transcoded: bool,
+    #[serde(default)]
+    pub width: Option<u32>,
+    #[serde(default)]
+    pub height: Option<u32>,
+    #[serde(default, with = "crate::number::optional_float")]
+    pub fps: Option<f64>,
+    /// Duration of the video, in seconds
+    #[serde(default, with = "crate::number::optional_float")]
+    pub duration: Option<f64>,
+    #[serde(default)]
+    pub rotate: Option<u16>,
+    #[serde(default, rename = "videocodec")]
+    pub video_codec: Option<String>,
+    /// Video bit rate in kilobits
+    #[serde(default, rename = "videobitrate")]
+    pub video_bit_rate: Option<u32>,
+    #[serde(default, rename = "audiocodec")]
+    pub audio_codec: Option<String>,
+    /// Audio bit rate in kilobits
+    #[serde(default, rename = "audiobitrate")]
+    pub audio_bit_rate: Option<u32>,
+    /// Audio sample rate in hertz
+    #[serde(default, rename = "audiosamplerate")]
+    pub audio_sample_rate: Option<u32>,
+}
+
+mod command {
+    use super::{GetVideoLinksCommand, VideoVariant};
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    #[derive(Debug, serde::Deserialize)]
+    struct VideoLinksPayload {
+        variants: Vec<VideoVariant>,
+    }
+
+    impl GetVideoLinksCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let mut res = self.identifier.to_http_params();
+            if self.force_download {
+                res.push(("forcedownload", 1.to_string()));
+            }
+            if self.skip_filename {
+                res.push(("skipfilename", 1.to_string()));
+            }
+            res
+        }
+    }
+
+    impl Command for GetVideoLinksCommand {
+        type Output = Vec<VideoVariant>;
+
+        fn request(&self) -> Request<'_> {
+            Request::new("getvideolinks", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<VideoLinksPayload>(body).map(|item| item.variants)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::GetVideoLinksCommand;
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn success() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/getvideolinks")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("fileid".into(), "42".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                r#"{
+        "result": 0,
+        "variants": [
+            {
+                "transcoded": false,
+                "expires": "Sat, 24 Jul 2021 03:18:31 +0000",
+                "path": "\/DLZCAt2vXZejNfL5ZruLVZZTk2ev7Z2ZZNR5ZZdoz6ZXZQZZErw4bH0PfzBQt3LlgXMliXVtietX\/video.mp4",
+                "hosts": ["edef2.pcloud.com"],
+                "width": 1920,
+                "height": 1080,
+                "fps": "29.97",
+                "duration": "60.06",
+                "videocodec": "h264",
+                "videobitrate": 4500,
+                "audiocodec": "aac",
+                "audiobitrate": 128,
+                "audiosamplerate": 44100
+            },
+            {
+                "transcoded": true,
+                "path": "\/DLZCAt2vXZejNfL5ZruLVZZTk2ev7Z2ZZNR5ZZdoz6ZXZQZZErw4bH0PfzBQt3LlgXMliXVtietX\/video-720.mp4",
+                "hosts": ["edef2.pcloud.com"],
+                "width": 1280,
+                "height": 720,
+                "videobitrate": 2000
+            }
+        ]
+}"#,
+            )
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let result = GetVideoLinksCommand::new(42.into())
+            .execute(&api)
+            .await
+            .unwrap();
+        assert_eq!(result.len(), 2);
+        assert!(!result[0].transcoded);
+        assert_eq!(result[0].fps, Some(29.97));
+        assert_eq!(result[0].video_codec.as_deref(), Some("h264"));
+        assert!(result[1].transcoded);
+        assert_eq!(result[1].height, Some(720));
+        m.assert();
+    }
+}
@@ -1,6 +1,11 @@
 pub mod get_audio_link;
 pub mod get_file_link;
+pub mod get_hls_link;
+pub mod get_text_file;
 pub mod get_video_link;
+pub mod get_video_links;
+
+use chrono::prelude::{DateTime, Utc};
 
 #[derive(Debug, serde::Deserialize)]
 pub struct Payload {
@@ -9,10 +14,35 @@ pub struct Payload {
     pub path: String,
 }
 
-#[cfg(feature = "client-http")]
-impl Payload {
-    fn to_url(&self) -> String {
-        let host = self.hosts.first().unwrap();
-        format!("https://{}{}", host, self.path)
+/// The hosts are given without scheme by pCloud, but can have one when served by a test server
+fn host_url(host: &str, path: &str) -> String {
+    if host.contains("://") {
+        format!("{}{}", host, path)
+    } else {
+        format!("https://{}{}", host, path)
+    }
+}
+
+/// A link to a content, served by several hosts
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+pub struct StreamingLink {
+    /// The hosts serving the content, the first one being the closest.
+    pub hosts: Vec<String>,
+    pub path: String,
+    #[serde(default, with = "crate::date::optional")]
+    pub expires: Option<DateTime<Utc>>,
+}
+
+impl StreamingLink {
+    /// The url to the content using the closest host
+    pub fn url(&self) -> Option<String> {
+        self.urls().next()
+    }
+
+    /// The urls to the content using every host
+    pub fn urls(&self) -> impl Iterator<Item = String> + '_ {
+        self.hosts
+            .iter()
+            .map(move |host| host_url(host, &self.path))
     }
 }
@@ -0,0 +1,1103 @@
+//! An in-memory pCloud server, to test without credentials nor network access
+//!
+//! The [`FakeServer`] listens on a local port and implements the main methods of the API
+//! on an in-memory file tree, with the error codes of the real service.
+//!
+//! ```
+//! use pcloud::folder::create::FolderCreateCommand;
+//! use pcloud::folder::list::FolderListCommand;
+//! use pcloud::prelude::HttpCommand;
+//! use pcloud::testing::FakeServer;
+//!
+//! # tokio_test::block_on(async {
+//! let server = FakeServer::start().await.unwrap();
+//! let client = server.client();
+//! let folder = FolderCreateCommand::new("foo".into(), 0)
+//!     .execute(&client)
+//!     .await
+//!     .unwrap();
+//! let root = FolderListCommand::new(0.into()).execute(&client).await.unwrap();
+//! assert_eq!(root.contents.unwrap().len(), 1);
+//! # })
+//! ```
+
+use crate::credentials::Credentials;
+use crate::http::{HttpClient, HttpClientBuilder};
+use crate::region::Region;
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, Form, FromRequest, Multipart, Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{any, get};
+use axum::Router;
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// The access token accepted by the fake server
+pub const ACCESS_TOKEN: &str = "fake-access-token";
+/// The username accepted by the fake server, with [`PASSWORD`]
+pub const USERNAME: &str = "fake@example.com";
+/// The password accepted by the fake server, with [`USERNAME`]
+pub const PASSWORD: &str = "fake-password";
+
+type Params = HashMap<String, String>;
+
+/// An error as returned by the API, with its code and message
+#[derive(Debug)]
+struct ApiError(u16, &'static str);
+
+const LOGIN_REQUIRED: ApiError = ApiError(1000, "Log in required.");
+const NO_FOLDER: ApiError = ApiError(1002, "No full path or folderid provided.");
+const NO_FILE: ApiError = ApiError(1004, "No fileid or path provided.");
+const NO_NAME: ApiError = ApiError(1001, "No full path or name/folderid provided.");
+const NO_UPLOAD: ApiError = ApiError(1900, "Invalid upload id.");
+const LOGIN_FAILED: ApiError = ApiError(2000, "Log in failed.");
+const ACCESS_DENIED: ApiError = ApiError(
+    2003,
+    "Access denied. You do not have permissions to preform this operation.",
+);
+const ALREADY_EXISTS: ApiError = ApiError(2004, "File or folder alredy exists.");
+const FOLDER_NOT_FOUND: ApiError = ApiError(2005, "Directory does not exist.");
+const FOLDER_NOT_EMPTY: ApiError = ApiError(2006, "Folder is not empty.");
+const FILE_NOT_FOUND: ApiError = ApiError(2009, "File not found.");
+const UNKNOWN_METHOD: ApiError = ApiError(5000, "Method not implemented by the fake server.");
+const INTO_ITSELF: ApiError = ApiError(2023, "Can not move a folder to a subfolder of itself.");
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        json_response(json!({ "result": self.0, "error": self.1 }))
+    }
+}
+
+fn json_response(value: Value) -> Response {
+    (
+        [(header::CONTENT_TYPE, "application/json; charset=utf-8")],
+        value.to_string(),
+    )
+        .into_response()
+}
+
+struct FolderNode {
+    name: String,
+    parent_id: Option<u64>,
+    created: DateTime<Utc>,
+    modified: DateTime<Utc>,
+}
+
+struct FileNode {
+    name: String,
+    parent_id: u64,
+    content: Vec<u8>,
+    created: DateTime<Utc>,
+    modified: DateTime<Utc>,
+}
+
+impl FileNode {
+    fn hash(&self) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.content.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn content_type(&self) -> (&'static str, u8) {
+        match self.name.rsplit_once('.').map(|(_, ext)| ext) {
+            Some("txt") | Some("md") => ("text/plain", 4),
+            Some("jpg") | Some("jpeg") => ("image/jpeg", 1),
+            Some("png") => ("image/png", 1),
+            Some("zip") => ("application/zip", 5),
+            _ => ("application/octet-stream", 0),
+        }
+    }
+
+    fn checksums(&self) -> Value {
+        use sha2::Digest;
+
+        json!({
+            "sha1": sha1_smol::Sha1::from(&self.content).digest().to_string(),
+            "sha256": format!("{:x}", sha2::Sha256::digest(&self.content)),
+        })
+    }
+}
+
+/// The account emulated by the fake server
+struct Account {
+    address: SocketAddr,
+    access_token: String,
+    next_id: u64,
+    folders: BTreeMap<u64, FolderNode>,
+    files: BTreeMap<u64, FileNode>,
+    uploads: HashMap<u64, Vec<u8>>,
+}
+
+fn flag(params: &Params, name: &str) -> bool {
+    params
+        .get(name)
+        .map(|value| value != "0" && value != "false")
+        .unwrap_or(false)
+}
+
+fn number(params: &Params, name: &str) -> Option<u64> {
+    params.get(name).and_then(|value| value.parse().ok())
+}
+
+/// Splits a path in its parent and its name
+fn split_path(path: &str) -> Option<(&str, &str)> {
+    let path = path.trim_end_matches('/');
+    let (parent, name) = path.rsplit_once('/')?;
+    (!name.is_empty()).then_some((parent, name))
+}
+
+impl Account {
+    fn new(address: SocketAddr) -> Self {
+        let now = Utc::now();
+        let mut folders = BTreeMap::new();
+        folders.insert(
+            0,
+            FolderNode {
+                name: "/".into(),
+                parent_id: None,
+                created: now,
+                modified: now,
+            },
+        );
+        Self {
+            address,
+            access_token: ACCESS_TOKEN.into(),
+            next_id: 1,
+            folders,
+            files: BTreeMap::new(),
+            uploads: HashMap::new(),
+        }
+    }
+
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn authenticate(&self, params: &Params) -> Result<(), ApiError> {
+        if let Some(username) = params.get("username") {
+            return match params.get("password") {
+                Some(password) if username == USERNAME && password == PASSWORD => Ok(()),
+                _ => Err(LOGIN_FAILED),
+            };
+        }
+        match params.get("access_token").or_else(|| params.get("auth")) {
+            Some(token) if *token == self.access_token => Ok(()),
+            Some(_) => Err(LOGIN_FAILED),
+            None => Err(LOGIN_REQUIRED),
+        }
+    }
+
+    // lookups
+
+    fn child_folder(&self, parent_id: u64, name: &str) -> Option<u64> {
+        self.folders
+            .iter()
+            .find(|(_, folder)| folder.parent_id == Some(parent_id) && folder.name == name)
+            .map(|(id, _)| *id)
+    }
+
+    fn child_file(&self, parent_id: u64, name: &str) -> Option<u64> {
+        self.files
+            .iter()
+            .find(|(_, file)| file.parent_id == parent_id && file.name == name)
+            .map(|(id, _)| *id)
+    }
+
+    fn folder_by_path(&self, path: &str) -> Option<u64> {
+        path.split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(0, |parent_id, name| self.child_folder(parent_id, name))
+    }
+
+    fn file_by_path(&self, path: &str) -> Option<u64> {
+        let (parent, name) = split_path(path)?;
+        self.child_file(self.folder_by_path(parent)?, name)
+    }
+
+    fn folder_param(&self, params: &Params, id: &str, path: &str) -> Result<u64, ApiError> {
+        let found = if let Some(value) = params.get(id) {
+            value
+                .parse()
+                .ok()
+                .filter(|id| self.folders.contains_key(id))
+        } else if let Some(value) = params.get(path) {
+            self.folder_by_path(value)
+        } else {
+            return Err(NO_FOLDER);
+        };
+        found.ok_or(FOLDER_NOT_FOUND)
+    }
+
+    fn file_param(&self, params: &Params) -> Result<u64, ApiError> {
+        let found = if let Some(value) = params.get("fileid") {
+            value.parse().ok().filter(|id| self.files.contains_key(id))
+        } else if let Some(value) = params.get("path") {
+            self.file_by_path(value)
+        } else {
+            return Err(NO_FILE);
+        };
+        found.ok_or(FILE_NOT_FOUND)
+    }
+
+    /// The folder and name of the target of a creation, either from a path or a folder and a name
+    fn target_param(&self, params: &Params) -> Result<(u64, String), ApiError> {
+        if let Some(name) = params.get("name") {
+            let parent_id = self.folder_param(params, "folderid", "path")?;
+            Ok((parent_id, name.clone()))
+        } else if let Some(path) = params.get("path") {
+            let (parent, name) = split_path(path).ok_or(NO_NAME)?;
+            let parent_id = self.folder_by_path(parent).ok_or(FOLDER_NOT_FOUND)?;
+            Ok((parent_id, name.to_string()))
+        } else {
+            Err(NO_NAME)
+        }
+    }
+
+    /// The folder and name of the destination of a move or a copy, keeping the name by default
+    fn destination_param(&self, params: &Params, name: &str) -> Result<(u64, String), ApiError> {
+        if let Some(path) = params.get("topath") {
+            if path.ends_with('/') {
+                let folder_id = self.folder_by_path(path).ok_or(FOLDER_NOT_FOUND)?;
+                return Ok((folder_id, name.to_string()));
+            }
+            let (parent, name) = split_path(path).ok_or(NO_NAME)?;
+            let parent_id = self.folder_by_path(parent).ok_or(FOLDER_NOT_FOUND)?;
+            return Ok((parent_id, name.to_string()));
+        }
+        let folder_id = match number(params, "tofolderid") {
+            Some(id) if self.folders.contains_key(&id) => Some(id),
+            Some(_) => return Err(FOLDER_NOT_FOUND),
+            None => None,
+        };
+        let name = params.get("toname").map(String::as_str).unwrap_or(name);
+        Ok((folder_id.unwrap_or_default(), name.to_string()))
+    }
+
+    fn is_taken(&self, parent_id: u64, name: &str) -> bool {
+        self.child_folder(parent_id, name).is_some() || self.child_file(parent_id, name).is_some()
+    }
+
+    fn folder_path(&self, id: u64) -> String {
+        let mut names = Vec::new();
+        let mut current = Some(id);
+        while let Some(folder) = current.and_then(|id| self.folders.get(&id)) {
+            if folder.parent_id.is_some() {
+                names.push(folder.name.as_str());
+            }
+            current = folder.parent_id;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    fn is_inside(&self, id: u64, ancestor_id: u64) -> bool {
+        let mut current = Some(id);
+        while let Some(id) = current {
+            if id == ancestor_id {
+                return true;
+            }
+            current = self.folders.get(&id).and_then(|folder| folder.parent_id);
+        }
+        false
+    }
+
+    // serialization
+
+    fn file_json(&self, id: u64) -> Value {
+        let file = &self.files[&id];
+        let (content_type, category) = file.content_type();
+        let parent_path = self.folder_path(file.parent_id);
+        json!({
+            "name": file.name,
+            "path": format!("{}/{}", parent_path.trim_end_matches('/'), file.name),
+            "created": file.created.to_rfc2822(),
+            "modified": file.modified.to_rfc2822(),
+            "isfolder": false,
+            "fileid": id,
+            "id": format!("f{id}"),
+            "parentfolderid": file.parent_id,
+            "size": file.content.len(),
+            "hash": file.hash(),
+            "contenttype": content_type,
+            "category": category,
+            "icon": "file",
+            "thumb": false,
+            "isshared": false,
+            "ismine": true,
+        })
+    }
+
+    fn folder_json(&self, id: u64, depth: Option<usize>, no_files: bool) -> Value {
+        let folder = &self.folders[&id];
+        let mut value = json!({
+            "name": folder.name,
+            "path": self.folder_path(id),
+            "created": folder.created.to_rfc2822(),
+            "modified": folder.modified.to_rfc2822(),
+            "isfolder": true,
+            "folderid": id,
+            "id": format!("d{id}"),
+            "icon": "folder",
+            "thumb": false,
+            "isshared": false,
+            "ismine": true,
+        });
+        if let Some(parent_id) = folder.parent_id {
+            value["parentfolderid"] = parent_id.into();
+        }
+        if let Some(depth) = depth {
+            let mut contents: Vec<Value> = self
+                .folders
+                .iter()
+                .filter(|(_, child)| child.parent_id == Some(id))
+                .map(|(child_id, _)| self.folder_json(*child_id, depth.checked_sub(1), no_files))
+                .collect();
+            if !no_files {
+                contents.extend(
+                    self.files
+                        .iter()
+                        .filter(|(_, file)| file.parent_id == id)
+                        .map(|(file_id, _)| self.file_json(*file_id)),
+                );
+            }
+            value["contents"] = contents.into();
+        }
+        value
+    }
+
+    fn deleted(mut value: Value) -> Value {
+        value["isdeleted"] = true.into();
+        value
+    }
+
+    // mutations
+
+    fn insert_folder(&mut self, parent_id: u64, name: String) -> u64 {
+        let id = self.next_id();
+        let now = Utc::now();
+        self.folders.insert(
+            id,
+            FolderNode {
+                name,
+                parent_id: Some(parent_id),
+                created: now,
+                modified: now,
+            },
+        );
+        id
+    }
+
+    /// Writes a file, replacing the content of an existing one with the same name
+    fn write_file(&mut self, parent_id: u64, name: String, content: Vec<u8>) -> u64 {
+        let now = Utc::now();
+        if let Some(id) = self.child_file(parent_id, &name) {
+            let file = self.files.get_mut(&id).unwrap();
+            file.content = content;
+            file.modified = now;
+            return id;
+        }
+        let id = self.next_id();
+        self.files.insert(
+            id,
+            FileNode {
+                name,
+                parent_id,
+                content,
+                created: now,
+                modified: now,
+            },
+        );
+        id
+    }
+
+    fn remove_folder(&mut self, id: u64) -> (usize, usize) {
+        let children: Vec<u64> = self
+            .folders
+            .iter()
+            .filter(|(_, folder)| folder.parent_id == Some(id))
+            .map(|(child_id, _)| *child_id)
+            .collect();
+        let mut counts = children.into_iter().fold((0, 0), |acc, child_id| {
+            let (files, folders) = self.remove_folder(child_id);
+            (acc.0 + files, acc.1 + folders)
+        });
+        let before = self.files.len();
+        self.files.retain(|_, file| file.parent_id != id);
+        counts.0 += before - self.files.len();
+        self.folders.remove(&id);
+        counts.1 += 1;
+        counts
+    }
+
+    // methods
+
+    fn call(&mut self, method: &str, params: &Params, body: Body) -> Result<Value, ApiError> {
+        match method {
+            "listfolder" => self.list_folder(params),
+            "createfolder" => self.create_folder(params, false),
+            "createfolderifnotexists" => self.create_folder(params, true),
+            "deletefolder" => self.delete_folder(params, false),
+            "deletefolderrecursive" => self.delete_folder(params, true),
+            "renamefolder" => self.rename_folder(params),
+            "uploadfile" => self.upload_file(params, body.files),
+            "upload_create" => self.upload_create(),
+            "upload_write" => self.upload_write(params, body.data),
+            "upload_info" => self.upload_info(params),
+            "upload_save" => self.upload_save(params),
+            "upload_delete" => self.upload_delete(params),
+            "checksumfile" => self.checksum_file(params),
+            "getfilelink" => self.get_file_link(params),
+            "copyfile" => self.copy_file(params),
+            "renamefile" => self.rename_file(params),
+            "deletefile" => self.delete_file(params),
+            _ => Err(UNKNOWN_METHOD),
+        }
+    }
+
+    fn list_folder(&self, params: &Params) -> Result<Value, ApiError> {
+        let id = self.folder_param(params, "folderid", "path")?;
+        let depth = if flag(params, "recursive") {
+            usize::MAX
+        } else {
+            0
+        };
+        let no_files = flag(params, "nofiles") || flag(params, "no_files");
+        Ok(json!({ "metadata": self.folder_json(id, Some(depth), no_files) }))
+    }
+
+    fn create_folder(&mut self, params: &Params, if_not_exists: bool) -> Result<Value, ApiError> {
+        let (parent_id, name) = self.target_param(params)?;
+        if let Some(id) = self.child_folder(parent_id, &name) {
+            if if_not_exists {
+                return Ok(
+                    json!({ "created": false, "metadata": self.folder_json(id, None, false) }),
+                );
+            }
+            return Err(ALREADY_EXISTS);
+        }
+        if self.child_file(parent_id, &name).is_some() {
+            return Err(ALREADY_EXISTS);
+        }
+        let id = self.insert_folder(parent_id, name);
+        Ok(json!({ "created": true, "metadata": self.folder_json(id, None, false) }))
+    }
+
+    fn delete_folder(&mut self, params: &Params, recursive: bool) -> Result<Value, ApiError> {
+        let id = self.folder_param(params, "folderid", "path")?;
+        if id == 0 {
+            return Err(ACCESS_DENIED);
+        }
+        if recursive {
+            let (files, folders) = self.remove_folder(id);
+            return Ok(json!({ "deletedfiles": files, "deletedfolders": folders }));
+        }
+        let is_empty = !self.folders.values().any(|f| f.parent_id == Some(id))
+            && !self.files.values().any(|f| f.parent_id == id);
+        if !is_empty {
+            return Err(FOLDER_NOT_EMPTY);
+        }
+        let metadata = Self::deleted(self.folder_json(id, None, false));
+        self.folders.remove(&id);
+        Ok(json!({ "metadata": metadata }))
+    }
+
+    fn rename_folder(&mut self, params: &Params) -> Result<Value, ApiError> {
+        let id = self.folder_param(params, "folderid", "path")?;
+        if id == 0 {
+            return Err(ACCESS_DENIED);
+        }
+        let folder = &self.folders[&id];
+        let mut destination = (folder.parent_id.unwrap_or_default(), folder.name.clone());
+        if params.contains_key("topath") || params.contains_key("tofolderid") {
+            destination = self.destination_param(params, &folder.name)?;
+        } else if let Some(name) = params.get("toname") {
+            destination.1 = name.clone();
+        }
+        let (parent_id, name) = destination;
+        if self.is_inside(parent_id, id) {
+            return Err(INTO_ITSELF);
+        }
+        if self.is_taken(parent_id, &name) && self.child_folder(parent_id, &name) != Some(id) {
+            return Err(ALREADY_EXISTS);
+        }
+        let folder = self.folders.get_mut(&id).unwrap();
+        folder.parent_id = Some(parent_id);
+        folder.name = name;
+        folder.modified = Utc::now();
+        Ok(json!({ "metadata": self.folder_json(id, None, false) }))
+    }
+
+    fn upload_file(
+        &mut self,
+        params: &Params,
+        files: Vec<(String, Vec<u8>)>,
+    ) -> Result<Value, ApiError> {
+        let parent_id = self.folder_param(params, "folderid", "path")?;
+        let ids: Vec<u64> = files
+            .into_iter()
+            .map(|(name, content)| self.write_file(parent_id, name, content))
+            .collect();
+        Ok(json!({
+            "fileids": ids,
+            "metadata": ids.iter().map(|id| self.file_json(*id)).collect::<Vec<_>>(),
+            "checksums": ids.iter().map(|id| self.files[id].checksums()).collect::<Vec<_>>(),
+        }))
+    }
+
+    fn upload_create(&mut self) -> Result<Value, ApiError> {
+        let id = self.next_id();
+        self.uploads.insert(id, Vec::new());
+        Ok(json!({ "uploadid": id }))
+    }
+
+    fn upload_param(&self, params: &Params) -> Result<u64, ApiError> {
+        number(params, "uploadid")
+            .filter(|id| self.uploads.contains_key(id))
+            .ok_or(NO_UPLOAD)
+    }
+
+    fn upload_write(&mut self, params: &Params, data: Bytes) -> Result<Value, ApiError> {
+        let id = self.upload_param(params)?;
+        let upload = self.uploads.get_mut(&id).unwrap();
+        let offset = number(params, "uploadoffset").unwrap_or(upload.len() as u64) as usize;
+        if upload.len() < offset + data.len() {
+            upload.resize(offset + data.len(), 0);
+        }
+        upload[offset..offset + data.len()].copy_from_slice(&data);
+        Ok(json!({}))
+    }
+
+    fn upload_info(&self, params: &Params) -> Result<Value, ApiError> {
+        let id = self.upload_param(params)?;
+        Ok(json!({ "size": self.uploads[&id].len() }))
+    }
+
+    fn upload_save(&mut self, params: &Params) -> Result<Value, ApiError> {
+        let id = self.upload_param(params)?;
+        let (parent_id, name) = self.target_param(params)?;
+        let content = self.uploads.remove(&id).unwrap_or_default();
+        let file_id = self.write_file(parent_id, name, content);
+        Ok(json!({ "metadata": self.file_json(file_id) }))
+    }
+
+    fn upload_delete(&mut self, params: &Params) -> Result<Value, ApiError> {
+        let id = self.upload_param(params)?;
+        self.uploads.remove(&id);
+        Ok(json!({}))
+    }
+
+    fn checksum_file(&self, params: &Params) -> Result<Value, ApiError> {
+        let id = self.file_param(params)?;
+        let mut value = self.files[&id].checksums();
+        value["metadata"] = self.file_json(id);
+        Ok(value)
+    }
+
+    fn get_file_link(&self, params: &Params) -> Result<Value, ApiError> {
+        let id = self.file_param(params)?;
+        let file = &self.files[&id];
+        let path = if flag(params, "skipfilename") {
+            format!("/download/{id}")
+        } else {
+            format!("/download/{id}/{}", file.name)
+        };
+        Ok(json!({
+            "path": path,
+            "hosts": [format!("http://{}", self.address)],
+            "expires": (Utc::now() + Duration::hours(6)).to_rfc2822(),
+        }))
+    }
+
+    fn copy_file(&mut self, params: &Params) -> Result<Value, ApiError> {
+        let id = self.file_param(params)?;
+        let (parent_id, name) = self.destination_param(params, &self.files[&id].name)?;
+        if self.child_folder(parent_id, &name).is_some() {
+            return Err(ALREADY_EXISTS);
+        }
+        if self.child_file(parent_id, &name).is_some() && flag(params, "noover") {
+            return Err(ALREADY_EXISTS);
+        }
+        let content = self.files[&id].content.clone();
+        let copy_id = self.write_file(parent_id, name, content);
+        Ok(json!({ "metadata": self.file_json(copy_id) }))
+    }
+
+    fn rename_file(&mut self, params: &Params) -> Result<Value, ApiError> {
+        let id = self.file_param(params)?;
+        let file = &self.files[&id];
+        let (parent_id, name) =
+            if params.contains_key("topath") || params.contains_key("tofolderid") {
+                self.destination_param(params, &file.name)?
+            } else {
+                let name = params.get("toname").unwrap_or(&file.name);
+                (file.parent_id, name.clone())
+            };
+        if self.is_taken(parent_id, &name) && self.child_file(parent_id, &name) != Some(id) {
+            return Err(ALREADY_EXISTS);
+        }
+        let file = self.files.get_mut(&id).unwrap();
+        file.parent_id = parent_id;
+        file.name = name;
+        file.modified = Utc::now();
+        Ok(json!({ "metadata": self.file_json(id) }))
+    }
+
+    fn delete_file(&mut self, params: &Params) -> Result<Value, ApiError> {
+        let id = self.file_param(params)?;
+        let metadata = Self::deleted(self.file_json(id));
+        self.files.remove(&id);
+        Ok(json!({ "metadata": metadata }))
+    }
+}
+
+/// The content sent with a request
+#[derive(Default)]
+struct Body {
+    data: Bytes,
+    files: Vec<(String, Vec<u8>)>,
+    form: Params,
+}
+
+type SharedAccount = Arc<Mutex<Account>>;
+
+fn lock(account: &SharedAccount) -> MutexGuard<'_, Account> {
+    account
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+async fn read_body(request: Request) -> Result<Body, Response> {
+    let content_type = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(String::from)
+        .unwrap_or_default();
+    if content_type.starts_with("application/x-www-form-urlencoded") {
+        let Form(form) = Form::<Params>::from_request(request, &())
+            .await
+            .map_err(IntoResponse::into_response)?;
+        return Ok(Body {
+            form,
+            ..Default::default()
+        });
+    }
+    if !content_type.starts_with("multipart/form-data") {
+        let data = Bytes::from_request(request, &())
+            .await
+            .map_err(IntoResponse::into_response)?;
+        return Ok(Body {
+            data,
+            ..Default::default()
+        });
+    }
+    let mut multipart = Multipart::from_request(request, &())
+        .await
+        .map_err(IntoResponse::into_response)?;
+    let mut files = Vec::new();
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(IntoResponse::into_response)?
+    {
+        let name = field.file_name().unwrap_or_default().to_string();
+        let data = field.bytes().await.map_err(IntoResponse::into_response)?;
+        files.push((name, data.to_vec()));
+    }
+    Ok(Body {
+        files,
+        ..Default::default()
+    })
+}
+
+/// The token sent in the `Authorization` header, like the http client does
+fn bearer_token(request: &Request) -> Option<String> {
+    request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(String::from)
+}
+
+async fn handle_method(
+    State(account): State<SharedAccount>,
+    Path(method): Path<String>,
+    Query(mut params): Query<Params>,
+    request: Request,
+) -> Response {
+    if let Some(token) = bearer_token(&request) {
+        params.insert("access_token".into(), token);
+    }
+    let mut body = match read_body(request).await {
+        Ok(value) => value,
+        Err(response) => return response,
+    };
+    // the secrets can be sent in a form instead of the url
+    params.extend(std::mem::take(&mut body.form));
+    let mut account = lock(&account);
+    let result = account
+        .authenticate(&params)
+        .and_then(|_| account.call(&method, &params, body));
+    match result {
+        Ok(Value::Object(payload)) => {
+            let mut value = Map::new();
+            value.insert("result".into(), 0.into());
+            value.extend(payload);
+            json_response(Value::Object(value))
+        }
+        Ok(other) => json_response(other),
+        Err(error) => error.into_response(),
+    }
+}
+
+async fn handle_download(
+    State(account): State<SharedAccount>,
+    Path(path): Path<String>,
+) -> Response {
+    let account = lock(&account);
+    let file = path
+        .split('/')
+        .next()
+        .and_then(|id| id.parse::<u64>().ok())
+        .and_then(|id| account.files.get(&id));
+    match file {
+        Some(file) => (
+            [(header::CONTENT_TYPE, file.content_type().0)],
+            file.content.clone(),
+        )
+            .into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// An in-memory pCloud server listening on a local port, stopped when dropped.
+///
+/// The accounts starts empty and can be filled with [`FakeServer::add_folder`] and
+/// [`FakeServer::add_file`] or through the API.
+pub struct FakeServer {
+    address: SocketAddr,
+    account: SharedAccount,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl std::fmt::Debug for FakeServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FakeServer")
+            .field("address", &self.address)
+            .finish()
+    }
+}
+
+impl FakeServer {
+    /// Starts a server on a random local port
+    pub async fn start() -> std::io::Result<Self> {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        let account = Arc::new(Mutex::new(Account::new(address)));
+        let router = Router::new()
+            .route("/download/*path", get(handle_download))
+            .route("/:method", any(handle_method))
+            // the upload parts are bigger than the default limit of axum
+            .layer(DefaultBodyLimit::disable())
+            .with_state(account.clone());
+        let task = tokio::spawn(async move {
+            if let Err(error) = axum::serve(listener, router).await {
+                tracing::error!("fake server stopped: {error:?}");
+            }
+        });
+        Ok(Self {
+            address,
+            account,
+            task,
+        })
+    }
+
+    /// The url of the server, like `http://127.0.0.1:1234`
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    pub fn region(&self) -> Region {
+        Region::new(self.url())
+    }
+
+    /// The credentials accepted by the server
+    pub fn credentials(&self) -> Credentials {
+        Credentials::access_token(lock(&self.account).access_token.clone())
+    }
+
+    /// The username and password accepted by the server
+    pub fn user_password(&self) -> Credentials {
+        Credentials::user_password(USERNAME, PASSWORD)
+    }
+
+    /// A client builder targeting the server with valid credentials
+    pub fn client_builder(&self) -> HttpClientBuilder {
+        HttpClientBuilder::default()
+            .credentials(self.credentials())
+            .region(self.region())
+    }
+
+    /// A client targeting the server with valid credentials
+    pub fn client(&self) -> HttpClient {
+        self.client_builder()
+            .build()
+            .expect("unable to build the http client")
+    }
+
+    /// Changes the access token accepted by the server
+    pub fn set_access_token(&self, value: impl Into<String>) {
+        lock(&self.account).access_token = value.into();
+    }
+
+    /// Creates a folder, or returns the existing one with the same name
+    pub fn add_folder(&self, parent_id: u64, name: &str) -> u64 {
+        let mut account = lock(&self.account);
+        match account.child_folder(parent_id, name) {
+            Some(id) => id,
+            None => account.insert_folder(parent_id, name.to_string()),
+        }
+    }
+
+    /// Creates or replaces a file
+    pub fn add_file(&self, parent_id: u64, name: &str, content: impl Into<Vec<u8>>) -> u64 {
+        lock(&self.account).write_file(parent_id, name.to_string(), content.into())
+    }
+
+    /// The content of a file, if it exists
+    pub fn file_content(&self, file_id: u64) -> Option<Vec<u8>> {
+        lock(&self.account)
+            .files
+            .get(&file_id)
+            .map(|file| file.content.clone())
+    }
+
+    /// The id of a folder from its path, like `/foo/bar`
+    pub fn folder_id(&self, path: &str) -> Option<u64> {
+        lock(&self.account).folder_by_path(path)
+    }
+
+    /// The id of a file from its path, like `/foo/bar.txt`
+    pub fn file_id(&self, path: &str) -> Option<u64> {
+        lock(&self.account).file_by_path(path)
+    }
+}
+
+impl Drop for FakeServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FakeServer;
+    use crate::credentials::Credentials;
+    use crate::error::Error;
+    use crate::file::checksum::FileCheckSumCommand;
+    use crate::file::copy::FileCopyCommand;
+    use crate::file::delete::FileDeleteCommand;
+    use crate::file::download::FileDownloadCommand;
+    use crate::file::rename::{FileMoveCommand, FileRenameCommand};
+    use crate::file::upload::{FileUploadCommand, MultipartFileUploadCommand};
+    use crate::folder::create::FolderCreateCommand;
+    use crate::folder::delete::FolderDeleteCommand;
+    use crate::folder::list::FolderListCommand;
+    use crate::folder::rename::{FolderMoveCommand, FolderRenameCommand};
+    use crate::http::HttpClientBuilder;
+    use crate::prelude::HttpCommand;
+
+    fn error_code(error: Error) -> u16 {
+        match error {
+            Error::Protocol(code, _) => code,
+            other => panic!("unexpected error {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_manage_folders() {
+        crate::tests::init();
+        let server = FakeServer::start().await.unwrap();
+        let client = server.client();
+
+        let foo = FolderCreateCommand::new("foo".into(), 0)
+            .execute(&client)
+            .await
+            .unwrap();
+        let err = FolderCreateCommand::new("foo".into(), 0)
+            .execute(&client)
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), 2004);
+        let same = FolderCreateCommand::new("foo".into(), 0)
+            .ignore_exists(true)
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(same.folder_id, foo.folder_id);
+        let bar = FolderCreateCommand::new("bar".into(), foo.folder_id)
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(bar.base.path.as_deref(), Some("/foo/bar"));
+
+        let err = FolderMoveCommand::new(foo.folder_id, bar.folder_id)
+            .execute(&client)
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), 2023);
+        let renamed = FolderRenameCommand::new(bar.folder_id, "baz".into())
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(renamed.base.name, "baz");
+        assert_eq!(server.folder_id("/foo/baz"), Some(bar.folder_id));
+
+        let err = FolderDeleteCommand::new(foo.folder_id.into())
+            .execute(&client)
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), 2006);
+        server.add_file(bar.folder_id, "a.txt", "hello");
+        let deleted = FolderDeleteCommand::new(foo.folder_id.into())
+            .recursive(true)
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(deleted.deleted_files, 1);
+        assert_eq!(deleted.deleted_folders, 2);
+
+        let err = FolderListCommand::new(foo.folder_id.into())
+            .execute(&client)
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), 2005);
+    }
+
+    #[tokio::test]
+    async fn should_manage_files() {
+        crate::tests::init();
+        let server = FakeServer::start().await.unwrap();
+        let client = server.client();
+        let folder_id = server.add_folder(0, "docs");
+
+        let uploaded = MultipartFileUploadCommand::new(folder_id)
+            .add_entry("a.txt".into(), b"hello".to_vec())
+            .add_entry("b.bin".into(), vec![0, 1, 2])
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(uploaded.len(), 2);
+        let chunked = FileUploadCommand::new("c.txt", folder_id, std::io::Cursor::new("world!"))
+            .part_size(4)
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(chunked.size, Some(6));
+        assert_eq!(server.file_content(chunked.file_id).unwrap(), b"world!");
+
+        let listed = FolderListCommand::new("/docs".into())
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(listed.contents.unwrap().len(), 3);
+
+        let checksum = FileCheckSumCommand::new(uploaded[0].file_id.into())
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(checksum.sha1, "aaf4c61ddcc5e8a2dabede0f3b482cd9aea9434d");
+
+        let copy = FileCopyCommand::new(uploaded[0].file_id, 0)
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_ne!(copy.file_id, uploaded[0].file_id);
+        assert_eq!(server.file_id("/a.txt"), Some(copy.file_id));
+
+        let renamed = FileRenameCommand::new(copy.file_id.into(), "b.bin".into())
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(renamed.base.name, "b.bin");
+        let err = FileMoveCommand::new(copy.file_id.into(), folder_id.into())
+            .execute(&client)
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), 2004);
+
+        let mut output = Vec::new();
+        let size = FileDownloadCommand::new(chunked.file_id.into(), &mut output)
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(size, 6);
+        assert_eq!(output, b"world!");
+
+        FileDeleteCommand::new(copy.file_id.into())
+            .execute(&client)
+            .await
+            .unwrap();
+        let err = FileDeleteCommand::new(copy.file_id.into())
+            .execute(&client)
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), 2009);
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_credentials() {
+        crate::tests::init();
+        let server = FakeServer::start().await.unwrap();
+        let client = HttpClientBuilder::default()
+            .credentials(Credentials::AccessToken("wrong".into()))
+            .region(server.region())
+            .build()
+            .unwrap();
+        let err = FolderListCommand::new(0.into())
+            .execute(&client)
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), 2000);
+    }
+
+    #[tokio::test]
+    async fn should_accept_user_password() {
+        crate::tests::init();
+        let server = FakeServer::start().await.unwrap();
+        let client = server
+            .client_builder()
+            .credentials(server.user_password())
+            .build()
+            .unwrap();
+        // the api calls send the credentials in a form, the uploads in the url
+        let folder = FolderCreateCommand::new("foo".into(), 0)
+            .execute(&client)
+            .await
+            .unwrap();
+        let file = FileUploadCommand::new("a.txt", folder.folder_id, std::io::Cursor::new("hello"))
+            .execute(&client)
+            .await
+            .unwrap();
+        assert_eq!(server.file_content(file.file_id).unwrap(), b"hello");
+
+        let client = server
+            .client_builder()
+            .credentials(Credentials::user_password(super::USERNAME, "wrong"))
+            .build()
+            .unwrap();
+        let err = FolderListCommand::new(0.into())
+            .execute(&client)
+            .await
+            .unwrap_err();
+        assert_eq!(error_code(err), 2000);
+    }
+}
@@ -0,0 +1,161 @@
+//! Resources needed to download the thumbnail of a file
+
+use super::{ThumbFormat, ThumbOptions, ThumbSize};
+use crate::file::FileIdentifier;
+use std::io::Write;
+
+/// Command to download the thumbnail of a file
+///
+/// Executing this command with return the size of the downloaded thumbnail as a `usize`.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/thumbnails/getthumb.html)
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use pcloud::thumbnail::ThumbSize;
+/// use pcloud::thumbnail::get_thumb::GetThumbCommand;
+/// use std::fs::File;
+///
+/// # tokio_test::block_on(async {
+/// let file = File::create("./thumb.jpg").unwrap();
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = GetThumbCommand::new("/foo/bar.jpg".into(), ThumbSize::new(128, 128), file);
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct GetThumbCommand<W> {
+    pub identifier: FileIdentifier,
+    pub options: ThumbOptions,
+    pub writer: W,
+}
+
+impl<W: Write> GetThumbCommand<W> {
+    pub fn new(identifier: FileIdentifier, size: ThumbSize, writer: W) -> Self {
+        Self {
+            identifier,
+            options: size.into(),
+            writer,
+        }
+    }
+
+    pub fn crop(mut self, value: bool) -> Self {
+        self.options.crop = value;
+        self
+    }
+
+    pub fn format(mut self, value: ThumbFormat) -> Self {
+        self.options.format = value;
+        self
+    }
+}
+
+#[cfg(feature = "client-http")]
+mod http {
+    use super::GetThumbCommand;
+    use crate::error::Error;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use std::io::Write;
+
+    impl<W> GetThumbCommand<W> {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let mut res = self.identifier.to_http_params();
+            res.extend(self.options.to_http_params());
+            res
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl<W: Write + Send> HttpCommand for GetThumbCommand<W> {
+        type Output = usize;
+
+        async fn execute(mut self, client: &HttpClient) -> Result<Self::Output, Error> {
+            let mut req = client
+                .get_request_raw("getthumb", &self.to_http_params())
+                .await?;
+            let mut size = 0;
+            while let Some(chunk) = req.chunk().await? {
+                self.writer
+                    .write_all(chunk.as_ref())
+                    .map_err(Error::Download)?;
+                size += chunk.len();
+            }
+            Ok(size)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::GetThumbCommand;
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use crate::thumbnail::ThumbSize;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn success() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/getthumb")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("fileid".into(), "42".into()),
+                Matcher::UrlEncoded("size".into(), "64x64".into()),
+            ]))
+            .with_status(200)
+            .with_header("content-type", "image/jpeg")
+            .with_body("not really an image")
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let mut buffer: Vec<u8> = Vec::new();
+        let size = GetThumbCommand::new(42.into(), ThumbSize::new(64, 64), &mut buffer)
+            .execute(&api)
+            .await
+            .unwrap();
+        assert_eq!(size, 19);
+        assert_eq!(buffer, b"not really an image");
+        m.assert();
+    }
+
+    #[tokio::test]
+    async fn error() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/getthumb")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![Matcher::UrlEncoded(
+                "fileid".into(),
+                "42".into(),
+            )]))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{ "result": 2009, "error": "File not found." }"#)
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let mut buffer: Vec<u8> = Vec::new();
+        let error = GetThumbCommand::new(42.into(), ThumbSize::new(64, 64), &mut buffer)
+            .execute(&api)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, crate::error::Error::Protocol(2009, _)));
+        m.assert();
+    }
+}
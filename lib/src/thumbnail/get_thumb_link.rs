@@ -0,0 +1,154 @@
+//! Resources needed to get a link to the thumbnail of a file
+
+use super::{ThumbFormat, ThumbOptions, ThumbSize};
+use crate::file::FileIdentifier;
+
+/// Command to get a link to the thumbnail of a file
+///
+/// Executing this command will return a [`StreamingLink`](crate::streaming::StreamingLink) to the thumbnail.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/thumbnails/getthumblink.html)
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use pcloud::thumbnail::ThumbSize;
+/// use pcloud::thumbnail::get_thumb_link::GetThumbLinkCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = GetThumbLinkCommand::new("/foo/bar.jpg".into(), ThumbSize::new(64, 64)).crop(true);
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct GetThumbLinkCommand {
+    pub identifier: FileIdentifier,
+    pub options: ThumbOptions,
+}
+
+impl GetThumbLinkCommand {
+    pub fn new(identifier: FileIdentifier, size: ThumbSize) -> Self {
+        Self {
+            identifier,
+            options: size.into(),
+        }
+    }
+
+    pub fn crop(mut self, value: bool) -> Self {
+        self.options.crop = value;
+        self
+    }
+
+    pub fn format(mut self, value: ThumbFormat) -> Self {
+        self.options.format = value;
+        self
+    }
+}
+
+mod command {
+    use super::GetThumbLinkCommand;
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+    use crate::streaming::StreamingLink;
+
+    impl GetThumbLinkCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let mut res = self.identifier.to_http_params();
+            res.extend(self.options.to_http_params());
+            res
+        }
+    }
+
+    impl Command for GetThumbLinkCommand {
+        type Output = StreamingLink;
+
+        fn request(&self) -> Request<'_> {
+            Request::new("getthumblink", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<StreamingLink>(body)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::GetThumbLinkCommand;
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use crate::thumbnail::{ThumbFormat, ThumbSize};
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn success() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/getthumblink")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("fileid".into(), "42".into()),
+                Matcher::UrlEncoded("size".into(), "64x64".into()),
+                Matcher::UrlEncoded("crop".into(), "1".into()),
+                Matcher::UrlEncoded("type".into(), "png".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                r#"{
+    "result": 0,
+    "size": "64x64",
+    "expires": "Sat, 24 Jul 2021 03:18:31 +0000",
+    "path": "\/DLZCAt2vXZejNfL5ZruLVZZTk2ev7Z2ZZNR5ZZdoz6ZXZQZZErw4bH0PfzBQt3LlgXMliXVtietX\/thumb.png",
+    "hosts": [
+        "edef2.pcloud.com",
+        "eu3.pcloud.com"
+    ]
+}"#,
+            )
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let result = GetThumbLinkCommand::new(42.into(), ThumbSize::new(64, 64))
+            .crop(true)
+            .format(ThumbFormat::Png)
+            .execute(&api)
+            .await
+            .unwrap();
+        assert_eq!(result.url().unwrap(), "https://edef2.pcloud.com/DLZCAt2vXZejNfL5ZruLVZZTk2ev7Z2ZZNR5ZZdoz6ZXZQZZErw4bH0PfzBQt3LlgXMliXVtietX/thumb.png");
+        m.assert();
+    }
+
+    #[tokio::test]
+    async fn without_host() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/getthumblink")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::UrlEncoded("fileid".into(), "42".into()))
+            .with_status(200)
+            .with_body(r#"{"result": 0, "size": "64x64", "path": "/thumb.jpg", "hosts": []}"#)
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let api = HttpClient::new(creds, Region::new(server.url()));
+        let result = GetThumbLinkCommand::new(42.into(), ThumbSize::new(64, 64))
+            .execute(&api)
+            .await
+            .unwrap();
+        assert!(result.url().is_none());
+        m.assert();
+    }
+}
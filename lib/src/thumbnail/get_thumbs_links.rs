@@ -0,0 +1,186 @@
+//! Resources needed to get links to the thumbnails of several files at once
+
+use super::{ThumbFormat, ThumbOptions, ThumbSize};
+use crate::error::Error;
+use crate::streaming::StreamingLink;
+
+/// Command to get links to the thumbnails of several files at once
+///
+/// Executing this command will return a [`ThumbLink`](ThumbLink) for each requested file.
+/// A failure for one of the files doesn't fail the whole command.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/thumbnails/getthumbslinks.html)
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use pcloud::thumbnail::ThumbSize;
+/// use pcloud::thumbnail::get_thumbs_links::GetThumbsLinksCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = GetThumbsLinksCommand::new(vec![12, 42], ThumbSize::new(64, 64));
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct GetThumbsLinksCommand {
+    pub file_ids: Vec<u64>,
+    pub options: ThumbOptions,
+}
+
+impl GetThumbsLinksCommand {
+    pub fn new(file_ids: Vec<u64>, size: ThumbSize) -> Self {
+        Self {
+            file_ids,
+            options: size.into(),
+        }
+    }
+
+    pub fn crop(mut self, value: bool) -> Self {
+        self.options.crop = value;
+        self
+    }
+
+    pub fn format(mut self, value: ThumbFormat) -> Self {
+        self.options.format = value;
+        self
+    }
+}
+
+/// The link to the thumbnail of a file, or the reason why it couldn't be generated.
+#[derive(Debug)]
+pub struct ThumbLink {
+    pub file_id: u64,
+    pub link: Result<StreamingLink, Error>,
+}
+
+mod command {
+    use super::{GetThumbsLinksCommand, ThumbLink};
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request, Response};
+    use crate::streaming::StreamingLink;
+
+    #[derive(Debug, serde::Deserialize)]
+    struct ThumbLinkPayload {
+        #[serde(rename = "fileid")]
+        file_id: u64,
+        #[serde(flatten)]
+        inner: Response<StreamingLink>,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct ThumbsLinksPayload {
+        thumbs: Vec<ThumbLinkPayload>,
+    }
+
+    impl GetThumbsLinksCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let file_ids = self
+                .file_ids
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            let mut res = vec![("fileids", file_ids)];
+            res.extend(self.options.to_http_params());
+            res
+        }
+    }
+
+    impl Command for GetThumbsLinksCommand {
+        type Output = Vec<ThumbLink>;
+
+        fn immediate(&self) -> Option<Self::Output> {
+            self.file_ids.is_empty().then(Vec::new)
+        }
+
+        fn request(&self) -> Request<'_> {
+            Request::new("getthumbslinks", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<ThumbsLinksPayload>(body).map(|res| {
+                res.thumbs
+                    .into_iter()
+                    .map(|item| ThumbLink {
+                        file_id: item.file_id,
+                        link: item.inner.payload(),
+                    })
+                    .collect()
+            })
+        }
+    }
+}
+
+#[cfg(all(test, feature = "client-http"))]
+mod http_tests {
+    use super::GetThumbsLinksCommand;
+    use crate::credentials::Credentials;
+    use crate::http::HttpClient;
+    use crate::prelude::HttpCommand;
+    use crate::region::Region;
+    use crate::thumbnail::ThumbSize;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn success() {
+        crate::tests::init();
+        let mut server = mockito::Server::new_async().await;
+        let m = server
+            .mock("GET", "/getthumbslinks")
+            .match_header("authorization", "Bearer access-token")
+            .match_query(Matcher::AllOf(vec![
+                Matcher::UrlEncoded("fileids".into(), "12,42".into()),
+                Matcher::UrlEncoded("size".into(), "32x32".into()),
+            ]))
+            .with_status(200)
+            .with_body(
+                r#"{
+    "result": 0,
+    "thumbs": [
+        {
+            "fileid": 12,
+            "result": 0,
+            "size": "32x32",
+            "expires": "Sat, 24 Jul 2021 03:18:31 +0000",
+            "path": "\/foo\/thumb.jpg",
+            "hosts": ["edef2.pcloud.com"]
+        },
+        {
+            "fileid": 42,
+            "result": 2009,
+            "error": "File not found."
+        }
+    ]
+}"#,
+            )
+            .create();
+        let creds = Credentials::AccessToken("access-token".into());
+        let dc = Region::new(server.url());
+        let api = HttpClient::new(creds, dc);
+        let result = GetThumbsLinksCommand::new(vec![12, 42], ThumbSize::new(32, 32))
+            .execute(&api)
+            .await
+            .unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].file_id, 12);
+        assert_eq!(
+            result[0].link.as_ref().unwrap().url().unwrap(),
+            "https://edef2.pcloud.com/foo/thumb.jpg"
+        );
+        assert!(matches!(
+            result[1].link,
+            Err(crate::error::Error::Protocol(2009, _))
+        ));
+        m.assert();
+    }
+}
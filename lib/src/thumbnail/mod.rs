@@ -0,0 +1,121 @@
+pub mod get_thumb;
+pub mod get_thumb_link;
+pub mod get_thumbs_links;
+pub mod save_thumb;
+
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+/// The size of a thumbnail, in pixels.
+///
+/// The width should be between 16 and 2048, and divisible by 4 or 5.
+/// The height should be between 16 and 1024, and divisible by 4 or 5.
+///
+/// ```
+/// use pcloud::thumbnail::ThumbSize;
+///
+/// let size: ThumbSize = "64x32".parse().unwrap();
+/// assert_eq!(size, ThumbSize::new(64, 32));
+/// assert_eq!(size.to_string(), "64x32");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct ThumbSize {
+    pub width: u16,
+    pub height: u16,
+}
+
+impl ThumbSize {
+    pub fn new(width: u16, height: u16) -> Self {
+        Self { width, height }
+    }
+}
+
+impl std::fmt::Display for ThumbSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+impl FromStr for ThumbSize {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (width, height) = value
+            .split_once('x')
+            .ok_or_else(|| format!("invalid thumbnail size {value:?}, expected WIDTHxHEIGHT"))?;
+        let width = width
+            .parse::<u16>()
+            .map_err(|err| format!("invalid thumbnail width {width:?}: {err}"))?;
+        let height = height
+            .parse::<u16>()
+            .map_err(|err| format!("invalid thumbnail height {height:?}: {err}"))?;
+        Ok(Self { width, height })
+    }
+}
+
+impl TryFrom<String> for ThumbSize {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::from_str(value.as_str())
+    }
+}
+
+impl From<ThumbSize> for String {
+    fn from(value: ThumbSize) -> Self {
+        value.to_string()
+    }
+}
+
+/// The image format of a thumbnail
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ThumbFormat {
+    #[default]
+    Jpeg,
+    Png,
+}
+
+/// The options shared by all the thumbnail commands
+#[derive(Clone, Copy, Debug)]
+pub struct ThumbOptions {
+    pub size: ThumbSize,
+    /// If set, the thumbnail will be cropped to the exact size, otherwise the ratio is kept.
+    pub crop: bool,
+    pub format: ThumbFormat,
+}
+
+impl From<ThumbSize> for ThumbOptions {
+    fn from(size: ThumbSize) -> Self {
+        Self {
+            size,
+            crop: false,
+            format: ThumbFormat::default(),
+        }
+    }
+}
+
+impl ThumbOptions {
+    pub(crate) fn to_http_params(self) -> Vec<(&'static str, String)> {
+        let mut res = vec![("size", self.size.to_string())];
+        if self.crop {
+            res.push(("crop", "1".to_string()));
+        }
+        if self.format == ThumbFormat::Png {
+            res.push(("type", "png".to_string()));
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ThumbSize;
+
+    #[test]
+    fn parse_size() {
+        assert_eq!("32x16".parse::<ThumbSize>(), Ok(ThumbSize::new(32, 16)));
+        assert!("32".parse::<ThumbSize>().is_err());
+        assert!("32xfoo".parse::<ThumbSize>().is_err());
+    }
+}
@@ -0,0 +1,113 @@
+//! Resources needed to save the thumbnail of a file as a new file
+
+use super::{ThumbFormat, ThumbOptions, ThumbSize};
+use crate::entry::File;
+use crate::file::FileIdentifier;
+use crate::folder::FolderIdentifier;
+
+/// Command to generate the thumbnail of a file and save it in a folder
+///
+/// Executing this command will return a [`SavedThumb`](SavedThumb) on success.
+///
+/// [More about it on the documentation](https://docs.pcloud.com/methods/thumbnails/savethumb.html)
+///
+/// # Example using the [`HttpClient`](crate::http::HttpClient)
+///
+/// To use this, the `client-http` feature should be enabled.
+///
+/// ```no_run
+/// use pcloud::http::HttpClientBuilder;
+/// use pcloud::prelude::HttpCommand;
+/// use pcloud::thumbnail::ThumbSize;
+/// use pcloud::thumbnail::save_thumb::SaveThumbCommand;
+///
+/// # tokio_test::block_on(async {
+/// let client = HttpClientBuilder::from_env().build().unwrap();
+/// let cmd = SaveThumbCommand::new(12.into(), ThumbSize::new(256, 256), 42.into(), "thumb.jpg".into());
+/// match cmd.execute(&client).await {
+///   Ok(res) => println!("success"),
+///   Err(err) => eprintln!("error: {:?}", err),
+/// }
+/// # })
+/// ```
+#[derive(Debug)]
+pub struct SaveThumbCommand {
+    pub identifier: FileIdentifier,
+    pub options: ThumbOptions,
+    pub to_folder: FolderIdentifier,
+    pub to_name: String,
+    pub no_overwrite: bool,
+}
+
+impl SaveThumbCommand {
+    pub fn new(
+        identifier: FileIdentifier,
+        size: ThumbSize,
+        to_folder: FolderIdentifier,
+        to_name: String,
+    ) -> Self {
+        Self {
+            identifier,
+            options: size.into(),
+            to_folder,
+            to_name,
+            no_overwrite: false,
+        }
+    }
+
+    pub fn crop(mut self, value: bool) -> Self {
+        self.options.crop = value;
+        self
+    }
+
+    pub fn format(mut self, value: ThumbFormat) -> Self {
+        self.options.format = value;
+        self
+    }
+
+    /// If set, the command fails when a file with the same name already exists.
+    pub fn no_overwrite(mut self, value: bool) -> Self {
+        self.no_overwrite = value;
+        self
+    }
+}
+
+/// Structure returned when saving a thumbnail
+#[derive(Debug, serde::Deserialize)]
+pub struct SavedThumb {
+    pub metadata: File,
+    pub width: u32,
+    pub height: u32,
+}
+
+mod command {
+    use super::{SaveThumbCommand, SavedThumb};
+    use crate::error::Error;
+    use crate::prelude::Command;
+    use crate::request::{self, Request};
+
+    impl SaveThumbCommand {
+        fn to_http_params(&self) -> Vec<(&str, String)> {
+            let mut res = self.identifier.to_http_params();
+            res.extend(self.options.to_http_params());
+            res.push(self.to_folder.to_named_http_param("topath", "tofolderid"));
+            res.push(("toname", self.to_name.clone()));
+            if self.no_overwrite {
+                res.push(("noover", "1".to_string()));
+            }
+            res
+        }
+    }
+
+    impl Command for SaveThumbCommand {
+        type Output = SavedThumb;
+
+        fn request(&self) -> Request<'_> {
+            Request::new("savethumb", self.to_http_params())
+        }
+
+        fn parse(&self, body: &[u8]) -> Result<Self::Output, Error> {
+            request::parse::<SavedThumb>(body)
+        }
+    }
+}
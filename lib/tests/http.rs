@@ -1,4 +1,4 @@
-#![cfg(all(feature = "protected", feature = "client-http"))]
+#![cfg(feature = "testing")]
 
 use pcloud::file::checksum::FileCheckSumCommand;
 use pcloud::file::download::FileDownloadCommand;
@@ -9,9 +9,9 @@ use pcloud::folder::delete::FolderDeleteCommand;
 use pcloud::folder::rename::FolderMoveCommand;
 use pcloud::folder::rename::FolderRenameCommand;
 use pcloud::folder::ROOT;
-use pcloud::http::HttpClientBuilder;
 use pcloud::prelude::HttpCommand;
 use pcloud::streaming::get_file_link::GetFileLinkCommand;
+use pcloud::testing::FakeServer;
 use rand::distributions::Alphanumeric;
 use rand::Rng;
 use std::io::Cursor;
@@ -40,7 +40,12 @@ async fn complete() {
     let folder_name = create_folder();
     let renamed_name = create_folder();
     let child_name = create_folder();
-    let client = HttpClientBuilder::from_env().build().unwrap();
+    let server = FakeServer::start().await.unwrap();
+    let client = server
+        .client_builder()
+        .credentials(server.user_password())
+        .build()
+        .unwrap();
     // create folder
     let folder = FolderCreateCommand::new(folder_name.clone(), ROOT)
         .execute(&client)